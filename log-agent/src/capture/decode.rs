@@ -0,0 +1,260 @@
+use crate::config::Encoding;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+
+/// Fin d'un segment de sortie
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Ending {
+    /// Saut de ligne (\n ou \r\n)
+    Newline,
+    /// \r seul: la ligne va être réécrite (barre de progression)
+    CarriageReturn,
+    /// Ligne incomplète envoyée faute de nouveaux octets (invite sans saut de ligne)
+    Idle,
+    /// Ligne plus longue que la limite du lecteur: la suite arrive dans les segments suivants
+    Overflow,
+    /// Fin du flux
+    Eof,
+}
+
+/// Ligne décodée depuis les octets de la sortie
+pub struct Line {
+    /// Texte sans le saut de ligne final
+    pub text: String,
+    /// Octets d'origine (sans le saut de ligne), None s'ils sont identiques au texte
+    pub raw: Option<Vec<u8>>,
+    /// Des octets invalides ont été remplacés par U+FFFD
+    pub repaired: bool,
+    pub ending: Ending,
+}
+
+/// Découpe une sortie en lignes au niveau des octets, puis les décode
+///
+/// Contrairement à `read_line`, des octets invalides n'interrompent pas la
+/// lecture: ils sont remplacés et la ligne est marquée comme réparée.
+/// Les segments terminés par \r sont rendus séparément, et une ligne
+/// incomplète est rendue après `idle` sans nouvel octet. Le buffer ne
+/// dépasse pas `max_bytes` (0 = pas de limite): une ligne plus longue est
+/// rendue en plusieurs segments `Ending::Overflow`.
+pub struct LineReader<R> {
+    reader: BufReader<R>,
+    encoding: Encoding,
+    idle: Option<Duration>,
+    max_bytes: usize,
+    buf: Vec<u8>,
+    /// Un BOM UTF-16 n'est retiré qu'en début de flux
+    first: bool,
+}
+
+impl<R: AsyncRead + Unpin> LineReader<R> {
+    pub fn new(reader: BufReader<R>, encoding: Encoding, idle: Option<Duration>, max_bytes: usize) -> Self {
+        // Au moins un caractère complet par segment
+        let max_bytes = if max_bytes == 0 { 0 } else { max_bytes.max(4) };
+        Self { reader, encoding, idle, max_bytes, buf: Vec::new(), first: true }
+    }
+
+    /// Segment suivant (None à EOF)
+    ///
+    /// Annulable (timeout): les octets déjà lus restent dans le buffer pour l'appel suivant.
+    pub async fn next_line(&mut self) -> std::io::Result<Option<Line>> {
+        let width = unit_width(self.encoding);
+        let big_endian = self.encoding == Encoding::Utf16Be;
+
+        let ending = loop {
+            let available = match self.idle {
+                Some(idle) if !self.buf.is_empty() => {
+                    match tokio::time::timeout(idle, self.reader.fill_buf()).await {
+                        Ok(result) => result?,
+                        Err(_) => break Ending::Idle,
+                    }
+                }
+                _ => self.reader.fill_buf().await?,
+            };
+            if available.is_empty() {
+                if self.buf.is_empty() {
+                    return Ok(None);
+                }
+                break Ending::Eof;
+            }
+
+            // Recherche de \n ou \r, unité par unité (un octet 0x0A isolé peut appartenir à un caractère UTF-16)
+            let mut used = 0;
+            let mut ending = None;
+            for byte in available {
+                self.buf.push(*byte);
+                used += 1;
+                let len = self.buf.len();
+                if !len.is_multiple_of(width) {
+                    continue;
+                }
+                match unit(&self.buf[len - width..], big_endian) {
+                    0x0A => ending = Some(Ending::Newline),
+                    0x0D => ending = Some(Ending::CarriageReturn),
+                    _ if self.max_bytes > 0 && len >= self.max_bytes => ending = Some(Ending::Overflow),
+                    _ => continue,
+                }
+                break;
+            }
+            // \r\n: le \n est consommé avec le \r (s'il est déjà arrivé)
+            if ending == Some(Ending::CarriageReturn)
+                && let Some(next) = available.get(used..used + width)
+                && unit(next, big_endian) == 0x0A
+            {
+                used += width;
+                ending = Some(Ending::Newline);
+            }
+            self.reader.consume(used);
+            if let Some(ending) = ending {
+                break ending;
+            }
+        };
+
+        // Longueur du contenu et octets consommés (un caractère coupé par la
+        // limite reste dans le buffer pour le segment suivant)
+        let (content, consumed) = match ending {
+            Ending::Newline | Ending::CarriageReturn => (self.buf.len() - width, self.buf.len()),
+            Ending::Idle | Ending::Eof => (self.buf.len(), self.buf.len()),
+            Ending::Overflow => {
+                let cut = char_boundary(&self.buf, self.encoding, big_endian);
+                (cut, cut)
+            }
+        };
+        let content_bytes = &self.buf[..content];
+        let (text, raw, repaired) = match self.encoding {
+            Encoding::Utf8 => decode_utf8(content_bytes),
+            Encoding::Latin1 => decode_latin1(content_bytes),
+            Encoding::Utf16Le | Encoding::Utf16Be => decode_utf16(content_bytes, big_endian, self.first),
+        };
+        self.first = false;
+        self.buf.drain(..consumed);
+        Ok(Some(Line { text, raw, repaired, ending }))
+    }
+}
+
+/// Fin du dernier caractère complet du buffer (coupure d'une ligne trop longue)
+fn char_boundary(buf: &[u8], encoding: Encoding, big_endian: bool) -> usize {
+    let len = buf.len();
+    match encoding {
+        Encoding::Latin1 => len,
+        Encoding::Utf8 => {
+            // Début du dernier caractère: s'il est incomplet, il part avec le segment suivant
+            let Some(start) = buf.iter().rposition(|byte| byte & 0xC0 != 0x80) else {
+                return len;
+            };
+            let needed = match buf[start] {
+                0xF0.. => 4,
+                0xE0.. => 3,
+                0xC0.. => 2,
+                _ => 1,
+            };
+            if start > 0 && len - start < needed { start } else { len }
+        }
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            // Surrogate haut sans son surrogate bas
+            let last = unit(&buf[len - 2..], big_endian);
+            if len > 2 && (0xD800..0xDC00).contains(&last) { len - 2 } else { len }
+        }
+    }
+}
+
+/// Taille d'une unité de code en octets
+fn unit_width(encoding: Encoding) -> usize {
+    match encoding {
+        Encoding::Utf8 | Encoding::Latin1 => 1,
+        Encoding::Utf16Le | Encoding::Utf16Be => 2,
+    }
+}
+
+/// Unité de code (un octet en UTF-8 / latin1, deux en UTF-16)
+fn unit(bytes: &[u8], big_endian: bool) -> u16 {
+    match bytes {
+        [byte] => *byte as u16,
+        [a, b] if big_endian => u16::from_be_bytes([*a, *b]),
+        [a, b] => u16::from_le_bytes([*a, *b]),
+        _ => 0,
+    }
+}
+
+/// Texte, octets d'origine s'ils diffèrent du texte, et si la ligne a été réparée
+type Decoded = (String, Option<Vec<u8>>, bool);
+
+fn decode_utf8(bytes: &[u8]) -> Decoded {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => (text.to_string(), None, false),
+        Err(_) => (String::from_utf8_lossy(bytes).into_owned(), Some(bytes.to_vec()), true),
+    }
+}
+
+fn decode_latin1(bytes: &[u8]) -> Decoded {
+    let text: String = bytes.iter().map(|b| *b as char).collect();
+    let raw = (!bytes.is_ascii()).then(|| bytes.to_vec());
+    (text, raw, false)
+}
+
+fn decode_utf16(bytes: &[u8], big_endian: bool, first: bool) -> Decoded {
+    let units = bytes.chunks_exact(2).map(|pair| unit(pair, big_endian));
+    let mut repaired = false;
+    let mut text: String = char::decode_utf16(units)
+        .map(|c| {
+            c.unwrap_or_else(|_| {
+                repaired = true;
+                char::REPLACEMENT_CHARACTER
+            })
+        })
+        .collect();
+    // Octet final isolé (flux coupé au milieu d'un caractère)
+    if !bytes.len().is_multiple_of(2) {
+        repaired = true;
+        text.push(char::REPLACEMENT_CHARACTER);
+    }
+    if first && text.starts_with('\u{FEFF}') {
+        text.remove(0);
+    }
+    (text, Some(bytes.to_vec()), repaired)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn segments(input: &[u8], encoding: Encoding, max_bytes: usize) -> Vec<(String, Ending)> {
+        let mut reader = LineReader::new(BufReader::new(input), encoding, None, max_bytes);
+        let mut segments = Vec::new();
+        while let Some(line) = reader.next_line().await.unwrap() {
+            segments.push((line.text, line.ending));
+        }
+        segments
+    }
+
+    #[tokio::test]
+    async fn long_lines_are_cut_at_the_reader_limit() {
+        let segments = segments(b"abcdefghij\nok\n", Encoding::Utf8, 4).await;
+        assert_eq!(
+            segments,
+            vec![
+                ("abcd".to_string(), Ending::Overflow),
+                ("efgh".to_string(), Ending::Overflow),
+                ("ij".to_string(), Ending::Newline),
+                ("ok".to_string(), Ending::Newline),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn cut_keeps_multibyte_characters_whole() {
+        // "aé€" = 1 + 2 + 3 octets: la limite tombe au milieu de "€"
+        let segments = segments("aé€\n".as_bytes(), Encoding::Utf8, 4).await;
+        assert_eq!(
+            segments,
+            vec![("aé".to_string(), Ending::Overflow), ("€".to_string(), Ending::Newline)]
+        );
+    }
+
+    #[tokio::test]
+    async fn no_limit_reads_whole_lines() {
+        let line = "x".repeat(100_000);
+        let input = format!("{}\n", line);
+        let segments = segments(input.as_bytes(), Encoding::Utf8, 0).await;
+        assert_eq!(segments, vec![(line, Ending::Newline)]);
+    }
+}
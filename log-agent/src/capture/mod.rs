@@ -1,138 +1,693 @@
-use crate::config::Config;
-use crate::types::{LogLevel, LogMessage, LogSource};
+pub mod decode;
+pub mod pty;
+pub mod ready;
+pub mod stop;
+
+use crate::config::{AnsiMode, Config};
+use crate::deps::{LifecycleHandle, Lifecycles};
+use crate::metrics::{self, TreeSampler};
+use crate::pipeline::Pipeline;
+use crate::process::ProcessSpec;
+use crate::types::{full_limit, LogLevel, LogMessage, LogSource};
+use decode::{Ending, Line, LineReader};
 use owo_colors::OwoColorize;
-use std::process::Stdio;
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::Command;
+use pty::{PtyPair, Viewport};
+use ready::{LogProbe, ReadyProbe, ReadyWaiter, Readiness};
+use nix::sys::signal::Signal;
+use stop::{terminate, StopPolicy};
+use std::io::IsTerminal;
+use std::process::{ExitStatus, Stdio};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, BufReader};
+use tokio::process::{Child, Command};
 use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 
 pub struct ProcessCapture {
     pub project: String,
-    pub command: Vec<String>,
+    pub spec: ProcessSpec,
+    pub pipeline: Pipeline,
+    /// Préfixe affiché devant chaque ligne (mode multi-process)
+    pub prefix: Option<String>,
+    /// Avancement partagé avec les autres processus (depends_on)
+    pub lifecycles: Option<Lifecycles>,
+}
+
+/// Contexte partagé par les tâches de capture d'un processus
+#[derive(Clone)]
+pub struct CaptureContext {
+    pub project: String,
+    pub pid: u32,
     pub config: Config,
+    pub pipeline: Pipeline,
+    /// Affiche chaque log dans le terminal (mode one-shot)
+    pub echo: bool,
+    /// Nom du processus ajouté aux logs (mode multi-process)
+    pub process: Option<String>,
+    /// Préfixe coloré affiché devant chaque ligne en mode echo
+    pub prefix: Option<String>,
+    /// Regex ready_when vérifiée sur chaque ligne (rempli par spawn_captured)
+    pub ready: Option<Arc<LogProbe>>,
+    /// Publie started/ready pour les processus qui en dépendent
+    pub lifecycle: Option<LifecycleHandle>,
+}
+
+/// Processus lancé avec ses tâches de capture
+pub struct SpawnedProcess {
+    pub child: Child,
+    pub pid: u32,
+    pub stdout_task: JoinHandle<()>,
+    pub stderr_task: JoinHandle<()>,
+    /// Propagation de la taille du terminal (mode PTY uniquement)
+    pub resize_task: Option<JoinHandle<()>>,
+    /// Attente de ready_when (envoie l'événement ready ou startup_failed)
+    pub ready_task: Option<JoinHandle<()>>,
+}
+
+type StreamReader = Box<dyn AsyncRead + Unpin + Send>;
+
+/// Lance la commande et démarre la capture de stdout/stderr
+///
+/// Avec `agent.pty`, l'enfant écrit dans des pseudo-terminaux au lieu de
+/// pipes: il garde ses couleurs et son line buffering. `spec.env` fixe le
+/// dossier de travail et les variables (env_file relu à chaque lancement).
+/// Avec `ready_when`, une tâche attend que le processus soit prêt.
+pub fn spawn_captured(
+    spec: &ProcessSpec,
+    mut ctx: CaptureContext,
+    tx: mpsc::Sender<LogMessage>,
+) -> Result<SpawnedProcess, Box<dyn std::error::Error + Send + Sync>> {
+    let (command, env) = (&spec.command, &spec.env);
+    if command.is_empty() {
+        return Err("No command provided".into());
+    }
+
+    let waiter = match &spec.ready_when {
+        Some(ready_when) => {
+            let (log_probe, waiter) = ReadyProbe::from_config(ready_when)?.arm();
+            ctx.ready = log_probe;
+            Some(waiter)
+        }
+        None => None,
+    };
+
+    let mut cmd = Command::new(&command[0]);
+    cmd.args(&command[1..]);
+
+    if let Some(cwd) = env.cwd_path() {
+        if !cwd.is_dir() {
+            return Err(format!("cwd not found: {}", cwd.display()).into());
+        }
+        cmd.current_dir(cwd);
+    }
+    if env.clear_env {
+        cmd.env_clear();
+    }
+    cmd.envs(env.resolve()?);
+
+    // L'enfant a son propre groupe de processus (pgid = pid) pour pouvoir
+    // arrêter aussi ses descendants. En mode PTY, setsid s'en charge.
+    let pty = if ctx.config.agent.pty {
+        // Sans echo, la sortie s'affiche dans le panneau des logs de la TUI
+        let viewport = match ctx.echo {
+            true => Viewport::Terminal,
+            false => Viewport::LogPane { process: ctx.process.clone() },
+        };
+        Some(PtyPair::attach(&mut cmd, viewport)?)
+    } else {
+        cmd.stdin(child_stdin(std::io::stdin().is_terminal()))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .process_group(0);
+        None
+    };
+
+    let started = tokio::time::Instant::now();
+    let mut child = cmd.spawn()?;
+    // Fermer nos copies des slaves PTY pour recevoir EOF à la sortie de l'enfant
+    drop(cmd);
+
+    let pid = child.id().ok_or("Failed to get PID")?;
+    ctx.pid = pid;
+
+    let (stdout, stderr, resize_task): (StreamReader, StreamReader, _) = match pty {
+        Some(pty) => {
+            let (stdout, stderr) = pty.readers()?;
+            (Box::new(stdout), Box::new(stderr), Some(pty.forward_resize()))
+        }
+        None => (
+            Box::new(child.stdout.take().ok_or("Failed to capture stdout")?),
+            Box::new(child.stderr.take().ok_or("Failed to capture stderr")?),
+            None,
+        ),
+    };
+
+    if let Some(lifecycle) = &ctx.lifecycle {
+        lifecycle.started(waiter.is_none());
+    }
+
+    let ready_task = waiter.map(|waiter| {
+        tokio::spawn(report_ready(waiter, started, ctx.clone(), tx.clone()))
+    });
+
+    let ctx_stdout = ctx.clone();
+    let tx_stdout = tx.clone();
+    let stdout_task = tokio::spawn(async move {
+        capture_stream(BufReader::new(stdout), LogSource::Stdout, ctx_stdout, tx_stdout).await;
+    });
+
+    let stderr_task = tokio::spawn(async move {
+        capture_stream(BufReader::new(stderr), LogSource::Stderr, ctx, tx).await;
+    });
+
+    Ok(SpawnedProcess {
+        child,
+        pid,
+        stdout_task,
+        stderr_task,
+        resize_task,
+        ready_task,
+    })
+}
+
+/// Entrée de l'enfant hors mode PTY
+///
+/// Dans son propre groupe, l'enfant n'est pas au premier plan du terminal: une
+/// lecture l'arrêterait (SIGTTIN) et `run` attendrait indéfiniment. Il lit
+/// donc une entrée vide quand stdin est un terminal (un pipe ou un fichier
+/// reste transmis).
+fn child_stdin(terminal: bool) -> Stdio {
+    if terminal { Stdio::null() } else { Stdio::inherit() }
+}
+
+/// Attend ready_when puis signale le résultat (terminal en mode echo, serveur MCP)
+async fn report_ready(
+    waiter: ReadyWaiter,
+    started: tokio::time::Instant,
+    ctx: CaptureContext,
+    tx: mpsc::Sender<LogMessage>,
+) {
+    let readiness = waiter.wait(started).await;
+    let message = format!("Process {}", readiness);
+
+    if ctx.echo {
+        let prefix = ctx.prefix.as_deref().unwrap_or_default();
+        match readiness {
+            Readiness::Ready { .. } => eprintln!("{}{}", prefix, format!("✓ {}", message).green()),
+            Readiness::TimedOut(_) => eprintln!("{}{}", prefix, format!("✗ {}", message).red()),
+        }
+    }
+
+    if let Some(lifecycle) = &ctx.lifecycle {
+        match readiness {
+            Readiness::Ready { .. } => lifecycle.ready(),
+            Readiness::TimedOut(_) => lifecycle.startup_failed(),
+        }
+    }
+
+    let event = match readiness {
+        Readiness::Ready { elapsed, .. } => {
+            LogMessage::ready(ctx.project.clone(), message, ctx.pid, elapsed.as_millis() as u64)
+        }
+        Readiness::TimedOut(_) => LogMessage::startup_failed(ctx.project.clone(), message, ctx.pid),
+    };
+    let _ = tx.send(event.with_process(ctx.process.clone())).await;
 }
 
 impl ProcessCapture {
-    pub fn new(project: String, command: Vec<String>, config: Config) -> Self {
-        Self { project, command, config }
+    pub fn new(project: String, spec: ProcessSpec, pipeline: Pipeline) -> Self {
+        Self { project, spec, pipeline, prefix: None, lifecycles: None }
+    }
+
+    /// Partage l'avancement avec les autres processus (attente des depends_on)
+    pub fn with_lifecycles(mut self, lifecycles: Lifecycles) -> Self {
+        self.lifecycles = Some(lifecycles);
+        self
+    }
+
+    /// Préfixe chaque ligne affichée et tague les logs avec le nom du processus
+    pub fn with_prefix(mut self, prefix: String) -> Self {
+        self.prefix = Some(prefix);
+        self
+    }
+
+    /// Nom du processus capturé
+    pub fn label(&self) -> String {
+        self.spec.label()
     }
 
     /// Lance le processus et retourne un handle
     pub fn spawn_with_tx(
         self,
         tx: mpsc::Sender<LogMessage>,
-    ) -> tokio::task::JoinHandle<Result<(), String>> {
+    ) -> tokio::task::JoinHandle<Result<ExitStatus, String>> {
         tokio::spawn(async move {
             self.run(tx).await.map_err(|e| e.to_string())
         })
     }
 
-    /// Lance le processus et capture les logs stdout/stderr
+    /// Lance le processus, capture les logs stdout/stderr et retourne son statut de sortie
     async fn run(
         self,
         tx: mpsc::Sender<LogMessage>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        if self.command.is_empty() {
-            return Err("No command provided".into());
+    ) -> Result<ExitStatus, Box<dyn std::error::Error + Send + Sync>> {
+        let label = self.label();
+        let process_name = self.prefix.as_ref().map(|_| label.clone());
+        let prefix = self.prefix.as_deref().unwrap_or_default();
+        let lifecycle = self.lifecycles.as_ref().map(|l| l.handle(&label));
+
+        // Attendre les dépendances (depends_on) avant de lancer la commande
+        if let Some(lifecycles) = &self.lifecycles
+            && !self.spec.depends_on.is_empty()
+        {
+            let waiting: Vec<String> = self.spec.depends_on.iter().map(|d| d.to_string()).collect();
+            eprintln!("{}{}", prefix, format!("… Waiting for {}", waiting.join(", ")).bright_black());
+
+            let result = tokio::select! {
+                result = lifecycles.wait(&self.spec.depends_on) => result,
+                _ = tokio::signal::ctrl_c() => Err("interrupted".to_string()),
+            };
+            if let Err(reason) = result {
+                lifecycles.handle(&label).not_started(reason.clone());
+                return Err(format!("{}: not started, {}", label, reason).into());
+            }
         }
 
-        let program = &self.command[0];
-        let args = &self.command[1..]; 
+        let ctx = CaptureContext {
+            project: self.project.clone(),
+            pid: 0,
+            config: self.spec.config.clone(),
+            pipeline: self.pipeline.clone(),
+            echo: true,
+            process: process_name.clone(),
+            prefix: self.prefix.clone(),
+            ready: None,
+            lifecycle: lifecycle.clone(),
+        };
 
-        // Spawn le processus enfant
-        let mut child = Command::new(program)
-            .args(args)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()?;
-
-        let pid = child.id().ok_or("Failed to get PID")?;
-        println!("{}", format!("✓ Process started (PID: {})", pid).bright_black());
-
-        let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
-        let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
-
-        // Capture stdout
-        let tx_stdout = tx.clone();
-        let project_stdout = self.project.clone();
-        let config_stdout = self.config.clone();
-        let stdout_task = tokio::spawn(async move {
-            capture_stream(
-                BufReader::new(stdout),
-                project_stdout,
-                LogSource::Stdout,
-                pid,
-                tx_stdout,
-                config_stdout,
-            )
-            .await;
-        });
+        let started = Instant::now();
+        let mut process = spawn_captured(&self.spec, ctx, tx.clone()).map_err(|e| {
+            if let Some(lifecycle) = &lifecycle {
+                lifecycle.not_started(e.to_string());
+            }
+            format!("{}: {}", label, e)
+        })?;
+
+        let mode = if self.spec.config.agent.pty { ", pty" } else { "" };
+        match &self.prefix {
+            Some(prefix) => println!(
+                "{}{}",
+                prefix,
+                format!("✓ Process started (PID: {}{})", process.pid, mode).bright_black()
+            ),
+            None => println!("{}", format!("✓ Process started (PID: {}{})", process.pid, mode).bright_black()),
+        }
 
-        // Capture stderr
-        let tx_stderr = tx;
-        let project_stderr = self.project.clone();
-        let config_stderr = self.config.clone();
-        let stderr_task = tokio::spawn(async move {
-            capture_stream(
-                BufReader::new(stderr),
-                project_stderr,
-                LogSource::Stderr,
-                pid,
-                tx_stderr,
-                config_stderr,
-            )
-            .await;
+        // Mesures périodiques des ressources envoyées au serveur MCP
+        let interval = self.spec.config.agent.metrics_interval_ms;
+        let metrics_task = (interval > 0).then(|| {
+            tokio::spawn(metrics::report_metrics(
+                TreeSampler::new(process.pid),
+                Duration::from_millis(interval),
+                self.project.clone(),
+                process_name.clone(),
+                tx.clone(),
+            ))
         });
 
-        // Attendre que le processus se termine
-        let _status = child.wait().await?;
+        // Attendre que le processus se termine, ou l'arrêter proprement sur Ctrl+C
+        // (il est dans son propre groupe et ne reçoit pas le SIGINT du terminal).
+        // En mode PTY, c'est SIGINT qui lui est transmis, comme depuis un terminal.
+        // Les processus qui en dépendent sont arrêtés d'abord (ordre inverse du démarrage).
+        let status = tokio::select! {
+            status = process.child.wait() => status?,
+            _ = tokio::signal::ctrl_c() => {
+                if let Some(lifecycles) = &self.lifecycles {
+                    lifecycles.wait_dependents_stopped(&label).await;
+                }
+                let mut policy = StopPolicy::from_config(&self.spec.config.agent).unwrap_or_default();
+                if self.spec.config.agent.pty {
+                    policy.signal = Signal::SIGINT;
+                }
+                let outcome = terminate(Some(&mut process.child), process.pid, policy).await;
+                let message = format!("Process {}", outcome);
+                match outcome {
+                    stop::StopOutcome::Killed(_) => eprintln!("{}{}", prefix, format!("⚠ {}", message).yellow()),
+                    _ => eprintln!("{}{}", prefix, format!("✓ {}", message).bright_black()),
+                }
+                // Statut mis en cache par terminate()
+                process.child.wait().await?
+            }
+        };
 
         // Attendre que les tâches de capture se terminent
-        let _ = stdout_task.await;
-        let _ = stderr_task.await;
+        let _ = process.stdout_task.await;
+        let _ = process.stderr_task.await;
+        if let Some(task) = process.resize_task {
+            task.abort();
+        }
+        if let Some(task) = process.ready_task {
+            task.abort();
+        }
+        if let Some(task) = metrics_task {
+            task.abort();
+        }
+
+        if let Some(lifecycle) = &lifecycle {
+            lifecycle.exited(exit_code(&status));
+        }
 
-        Ok(())
+        // Résumé de sortie
+        let summary = format!(
+            "Process {} after {}",
+            describe_exit(&status),
+            format_duration(started.elapsed())
+        );
+        if status.success() {
+            eprintln!("{}{}", prefix, format!("✓ {}", summary).bright_black());
+        } else {
+            eprintln!("{}{}", prefix, format!("✗ {}", summary).red());
+        }
+
+        // Dernier message pour le serveur MCP
+        let event = LogMessage::exited(self.project.clone(), summary, process.pid, exit_code(&status))
+            .with_process(process_name);
+        let _ = tx.send(event).await;
+
+        Ok(status)
+    }
+}
+
+/// Code de sortie à propager: celui de l'enfant, ou 128+signal s'il a été tué
+pub fn exit_code(status: &ExitStatus) -> i32 {
+    use std::os::unix::process::ExitStatusExt;
+
+    status
+        .code()
+        .or_else(|| status.signal().map(|signal| 128 + signal))
+        .unwrap_or(1)
+}
+
+/// Description courte de la sortie ("exited with code 1", "killed by SIGTERM (code 143)")
+pub fn describe_exit(status: &ExitStatus) -> String {
+    use std::os::unix::process::ExitStatusExt;
+
+    match (status.code(), status.signal()) {
+        (Some(code), _) => format!("exited with code {}", code),
+        (None, Some(signal)) => {
+            let name = nix::sys::signal::Signal::try_from(signal)
+                .map(|s| s.to_string())
+                .unwrap_or_else(|_| format!("signal {}", signal));
+            format!("killed by {} (code {})", name, 128 + signal)
+        }
+        _ => "exited".to_string(),
+    }
+}
+
+/// Durée lisible: "850ms", "12.3s", "4m05s"
+fn format_duration(duration: std::time::Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 60 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else if duration.as_millis() >= 1000 {
+        format!("{:.1}s", duration.as_secs_f64())
+    } else {
+        format!("{}ms", duration.as_millis())
     }
 }
 
 /// Capture un stream (stdout ou stderr) ligne par ligne
-async fn capture_stream<R>(
-    mut reader: BufReader<R>,
-    project: String,
+///
+/// Chaque ligne passe par le pipeline (filtres) avant d'être affichée
+/// et envoyée au channel. Une ligne réécrite avec \r n'est envoyée que
+/// dans son état final; les états intermédiaires ne vont qu'à la TUI.
+/// Les lignes de continuation ([multiline]) rejoignent l'événement en cours,
+/// envoyé à la première ligne qui n'en fait pas partie ou après flush_ms.
+pub async fn capture_stream<R>(
+    reader: BufReader<R>,
     source: LogSource,
-    pid: u32,
+    ctx: CaptureContext,
     tx: mpsc::Sender<LogMessage>,
-    config: Config,
 ) where
     R: tokio::io::AsyncRead + Unpin,
 {
-    let mut line = String::new();
+    let idle = Duration::from_millis(ctx.config.agent.partial_line_timeout_ms);
+    let max_line_bytes = ctx.config.agent.max_line_bytes;
+    let mut lines = LineReader::new(
+        reader,
+        ctx.config.agent.encoding,
+        (!idle.is_zero()).then_some(idle),
+        max_line_bytes,
+    );
+    // Ligne plus longue que max_line_bytes, reçue en plusieurs segments
+    let mut long: Option<LongLine> = None;
+    // Une seule notice par flux: une sortie binaire en produirait une par ligne
+    let mut repair_noticed = false;
+    // Dernier état de la ligne en cours de réécriture (\r)
+    let mut progress: Option<Line> = None;
+    // Événement multiligne en attente de ses continuations
+    let mut pending: Option<Pending> = None;
 
     loop {
-        line.clear();
-        match reader.read_line(&mut line).await {
-            Ok(0) => break, // EOF
-            Ok(_) => {
-                let message = line.trim_end().to_string();
-                if !message.is_empty() {
-                    // Crée le message de log
-                    let log = LogMessage::new(project.clone(), message.clone(), source.clone(), pid);
-                    
-                    // Affiche dans le terminal avec coloration
-                    print_colored_log(&log, &config);
-
-                    // Envoie le log au channel
-                    if let Err(e) = tx.send(log).await {
-                        eprintln!("{}", format!("Failed to send log to channel: {}", e).red());
-                        break;
+        // L'événement en attente part si aucune ligne n'arrive pendant flush_ms
+        let next = match (&pending, ctx.pipeline.multiline()) {
+            (Some(_), Some(multiline)) => {
+                match tokio::time::timeout(multiline.flush, lines.next_line()).await {
+                    Ok(next) => next,
+                    Err(_) => {
+                        if let Some(event) = pending.take()
+                            && !emit(event.log, &ctx, &tx, &mut repair_noticed).await
+                        {
+                            break;
+                        }
+                        continue;
                     }
                 }
             }
-            Err(_) => {
+            _ => lines.next_line().await,
+        };
+
+        let mut dropped = 0;
+        let mut line = match next {
+            Ok(Some(line)) => line,
+            // EOF, ou erreur de lecture (EIO d'un PTY à la sortie du processus...):
+            // une ligne longue ou une progression jamais terminée part dans son dernier état
+            Ok(None) | Err(_) => {
+                if let Some(started) = long.take() {
+                    let end = Line { text: String::new(), raw: None, repaired: false, ending: Ending::Eof };
+                    let (line, bytes) = started.finish(end, max_line_bytes);
+                    dropped = bytes;
+                    line
+                } else if let Some(last) = progress.take() {
+                    Line { ending: Ending::Eof, ..last }
+                } else {
+                    break;
+                }
+            }
+        };
+
+        // Segment d'une ligne trop longue: conservé jusqu'à full_limit, le reste est compté
+        if line.ending == Ending::Overflow {
+            match &mut long {
+                Some(long) => long.push(line, max_line_bytes),
+                None => long = Some(LongLine::new(line)),
+            }
+            continue;
+        }
+        if let Some(started) = long.take() {
+            (line, dropped) = started.finish(line, max_line_bytes);
+        }
+
+        if line.ending == Ending::CarriageReturn {
+            if line.text.trim().is_empty() {
+                continue;
+            }
+            // Mode TUI: la ligne est mise à jour sur place (rien n'est affiché en mode echo)
+            if !ctx.echo {
+                // L'événement en attente s'affiche avant la progression
+                if let Some(event) = pending.take()
+                    && !emit(event.log, &ctx, &tx, &mut repair_noticed).await
+                {
+                    break;
+                }
+                let message = line.text.trim_end().to_string();
+                let update = LogMessage::progress(ctx.project.clone(), message, source.clone(), ctx.pid)
+                    .with_process(ctx.process.clone());
+                if let Some(update) = ctx.pipeline.preview(update)
+                    && tx.send(update).await.is_err()
+                {
+                    break;
+                }
+            }
+            progress = Some(line);
+            continue;
+        }
+
+        // Fin d'une ligne réécrite: son état final est le dernier segment non vide
+        if let Some(last) = progress.take()
+            && line.text.trim().is_empty()
+        {
+            line = last;
+        }
+
+        let message = line.text.trim_end().to_string();
+        if message.is_empty() {
+            continue;
+        }
+
+        // ready_when: vérifié avant les filtres (une ligne ignorée peut signaler le démarrage)
+        if let Some(probe) = &ctx.ready {
+            probe.check(&message);
+        }
+
+        let Some(multiline) = ctx.pipeline.multiline() else {
+            let log = LogMessage::new(ctx.project.clone(), message, source.clone(), ctx.pid)
+                .with_process(ctx.process.clone())
+                .with_raw(line.raw, line.repaired)
+                .with_dropped(dropped);
+            if !emit(log, &ctx, &tx, &mut repair_noticed).await {
                 break;
             }
+            continue;
+        };
+
+        // Ligne de continuation: ajoutée à l'événement en cours
+        if let Some(event) = &mut pending
+            && event.lines < multiline.max_lines
+            && multiline.continues(&event.last, &message)
+        {
+            event.log.append_line(&message, line.raw, line.repaired);
+            event.last = message;
+            event.lines += 1;
+            continue;
+        }
+
+        // Nouvel événement: le précédent est complet
+        let log = LogMessage::new(ctx.project.clone(), message.clone(), source.clone(), ctx.pid)
+            .with_process(ctx.process.clone())
+            .with_raw(line.raw, line.repaired)
+            .with_dropped(dropped);
+        if let Some(event) = pending.replace(Pending { log, last: message, lines: 1 })
+            && !emit(event.log, &ctx, &tx, &mut repair_noticed).await
+        {
+            break;
         }
     }
+
+    if let Some(event) = pending {
+        emit(event.log, &ctx, &tx, &mut repair_noticed).await;
+    }
+}
+
+/// Ligne plus longue que max_line_bytes en cours de réception
+///
+/// Le texte est conservé jusqu'à `full_limit` (mémoire bornée même pour une
+/// sortie sans saut de ligne), les octets suivants sont seulement comptés.
+struct LongLine {
+    line: Line,
+    dropped: usize,
+}
+
+impl LongLine {
+    fn new(line: Line) -> Self {
+        Self { line, dropped: 0 }
+    }
+
+    /// Ajoute un segment (au-delà de la limite, seule sa taille est gardée)
+    fn push(&mut self, segment: Line, max_line_bytes: usize) {
+        let room = full_limit(max_line_bytes).saturating_sub(self.line.text.len());
+        if room == 0 {
+            self.dropped += segment.raw.as_ref().map_or(segment.text.len(), Vec::len);
+            return;
+        }
+        self.append(segment);
+    }
+
+    /// Dernier segment de la ligne (saut de ligne, \r, fin du flux...)
+    ///
+    /// Retourne la ligne complète et le nombre d'octets non conservés.
+    fn finish(mut self, segment: Line, max_line_bytes: usize) -> (Line, usize) {
+        let ending = segment.ending;
+        self.push(segment, max_line_bytes);
+        (Line { ending, ..self.line }, self.dropped)
+    }
+
+    fn append(&mut self, segment: Line) {
+        if self.line.raw.is_some() || segment.raw.is_some() {
+            let mut raw = self.line.raw.take().unwrap_or_else(|| self.line.text.as_bytes().to_vec());
+            raw.extend_from_slice(segment.raw.as_deref().unwrap_or(segment.text.as_bytes()));
+            self.line.raw = Some(raw);
+        }
+        self.line.text.push_str(&segment.text);
+        self.line.repaired |= segment.repaired;
+    }
+}
+
+/// Événement multiligne en attente de ses lignes de continuation
+struct Pending {
+    log: LogMessage,
+    /// Dernière ligne ajoutée (règles "after")
+    last: String,
+    lines: usize,
+}
+
+/// Passe un log dans le pipeline, l'affiche (mode echo) puis l'envoie au channel
+///
+/// Retourne false si le channel est fermé.
+async fn emit(
+    log: LogMessage,
+    ctx: &CaptureContext,
+    tx: &mpsc::Sender<LogMessage>,
+    repair_noticed: &mut bool,
+) -> bool {
+    // Analyse, niveau et filtres
+    let Some(log) = ctx.pipeline.process(log) else {
+        return true;
+    };
+
+    // Affiche dans le terminal avec coloration
+    if ctx.echo {
+        // Verrou pour que préfixe et message ne se mélangent pas entre processus
+        let _stderr = std::io::stderr().lock();
+        if log.data.repaired && !*repair_noticed {
+            *repair_noticed = true;
+            eprintln!("{}", repair_notice(&log).yellow());
+        }
+        if let Some(prefix) = &ctx.prefix {
+            eprint!("{}", prefix);
+        }
+        print_colored_log(&log, &ctx.config);
+    }
+
+    // Envoie le log au channel
+    if let Err(e) = tx.send(log).await {
+        if ctx.echo {
+            eprintln!("{}", format!("Failed to send log to channel: {}", e).red());
+        }
+        return false;
+    }
+    true
+}
+
+/// Flux d'origine d'un log ("api stderr", "stdout"...)
+pub fn stream_name(log: &LogMessage) -> String {
+    let source = match log.data.source {
+        LogSource::Stdout => "stdout",
+        LogSource::Stderr => "stderr",
+    };
+    match &log.data.process {
+        Some(process) => format!("{} {}", process, source),
+        None => source.to_string(),
+    }
+}
+
+/// Notice affichée à la première ligne réparée d'un flux
+pub fn repair_notice(log: &LogMessage) -> String {
+    format!(
+        "⚠ Invalid bytes in {}: replaced with U+FFFD (raw bytes kept in saved logs, further repairs not reported)",
+        stream_name(log)
+    )
 }
 
 /// Affiche un log avec coloration selon le niveau et la config
@@ -140,9 +695,20 @@ fn print_colored_log(log: &LogMessage, config: &Config) {
     use owo_colors::OwoColorize;
     use crate::config::types::Style;
     
+    // Codes ANSI du processus conservés si ansi.terminal = "styled"
+    let text = match &log.data.styled {
+        Some(styled) if config.ansi.terminal == AnsiMode::Styled => styled,
+        _ => &log.data.message,
+    };
+    // Ligne coupée (max_line_bytes): début du message et taille du reste
+    let message = match log.data.truncated_bytes {
+        Some(bytes) => format!("{} [+{} bytes]", text, bytes),
+        None => text.clone(),
+    };
+
     // Vérifier si les couleurs sont activées
     if !config.output.colors {
-        eprintln!("{}", log.data.message);
+        eprintln!("{}", message);
         return;
     }
     
@@ -154,8 +720,6 @@ fn print_colored_log(log: &LogMessage, config: &Config) {
         LogLevel::Info => &config.colors.info,
     };
     
-    let message = &log.data.message;
-    
     // Déterminer si on a des styles
     let has_bold = color_style.style.iter().any(|s| matches!(s, Style::Bold));
     let has_italic = color_style.style.iter().any(|s| matches!(s, Style::Italic));
@@ -163,7 +727,7 @@ fn print_colored_log(log: &LogMessage, config: &Config) {
     // Appliquer la couleur avec les styles
     match &color_style.fg {
         Some(color) => {
-            let colored_text = color.apply_to_string(message);
+            let colored_text = color.apply_to_string(&message);
             if has_bold && has_italic {
                 eprintln!("{}", colored_text.bold().italic());
             } else if has_bold {
@@ -185,3 +749,22 @@ fn print_colored_log(log: &LogMessage, config: &Config) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reading_stdin_from_a_terminal_gets_eof() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "printf 'Continue? [y/N] '; read x; echo got:$x"])
+            .stdin(child_stdin(true))
+            .stdout(Stdio::piped())
+            .process_group(0);
+        let output = tokio::time::timeout(Duration::from_secs(5), cmd.output())
+            .await
+            .expect("child blocked on stdin")
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "Continue? [y/N] got:\n");
+    }
+}
@@ -0,0 +1,167 @@
+use nix::pty::{openpty, Winsize};
+use std::io;
+use std::os::fd::{AsRawFd, OwnedFd};
+use std::pin::Pin;
+use std::process::Stdio;
+use std::task::{ready, Context, Poll};
+use tokio::io::unix::AsyncFd;
+use tokio::io::{AsyncRead, ReadBuf};
+use tokio::process::Command;
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::task::JoinHandle;
+
+/// Zone où la sortie de l'enfant est affichée (taille annoncée au PTY)
+#[derive(Debug, Clone)]
+pub enum Viewport {
+    /// Terminal entier (mode normal)
+    Terminal,
+    /// Panneau des logs de la TUI, messages précédés de l'heure, du niveau
+    /// et du nom du processus
+    LogPane { process: Option<String> },
+}
+
+/// Paire de pseudo-terminaux: un pour stdout, un pour stderr
+///
+/// Les deux sorties sont des TTY (couleurs et line buffering conservés
+/// par les outils) tout en restant séparées.
+pub struct PtyPair {
+    stdout: OwnedFd,
+    stderr: OwnedFd,
+    viewport: Viewport,
+}
+
+impl PtyPair {
+    /// Ouvre les pseudo-terminaux et y branche stdout/stderr de la commande
+    ///
+    /// L'enfant démarre une nouvelle session avec le PTY de stdout comme
+    /// terminal de contrôle, pour recevoir SIGWINCH lors des redimensionnements.
+    /// Il ne reçoit donc plus le Ctrl+C du terminal: voir `interrupt`.
+    pub fn attach(cmd: &mut Command, viewport: Viewport) -> io::Result<Self> {
+        let size = viewport.winsize();
+        let out = openpty(Some(&size), None).map_err(io::Error::from)?;
+        let err = openpty(Some(&size), None).map_err(io::Error::from)?;
+
+        cmd.stdout(Stdio::from(out.slave));
+        cmd.stderr(Stdio::from(err.slave));
+
+        // SAFETY: setsid et ioctl sont async-signal-safe, aucune allocation ici
+        unsafe {
+            cmd.pre_exec(|| {
+                if libc::setsid() == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+                if libc::ioctl(libc::STDOUT_FILENO, libc::TIOCSCTTY as _, 0) == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+
+        Ok(Self {
+            stdout: out.master,
+            stderr: err.master,
+            viewport,
+        })
+    }
+
+    /// Crée les lecteurs async de stdout et stderr
+    pub fn readers(&self) -> io::Result<(PtyReader, PtyReader)> {
+        Ok((
+            PtyReader::new(self.stdout.try_clone()?)?,
+            PtyReader::new(self.stderr.try_clone()?)?,
+        ))
+    }
+
+    /// Propage les changements de taille du terminal aux deux PTY
+    pub fn forward_resize(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let Ok(mut winch) = signal(SignalKind::window_change()) else {
+                return;
+            };
+
+            while winch.recv().await.is_some() {
+                let size = self.viewport.winsize();
+                for fd in [&self.stdout, &self.stderr] {
+                    // SAFETY: fd est un master PTY ouvert, size vit pendant l'appel
+                    unsafe {
+                        libc::ioctl(fd.as_raw_fd(), libc::TIOCSWINSZ as _, &size);
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Lecture async non bloquante du côté master d'un PTY
+pub struct PtyReader {
+    fd: AsyncFd<OwnedFd>,
+}
+
+impl PtyReader {
+    fn new(fd: OwnedFd) -> io::Result<Self> {
+        // SAFETY: fd est un descripteur valide possédé par cette structure
+        unsafe {
+            let flags = libc::fcntl(fd.as_raw_fd(), libc::F_GETFL);
+            if flags == -1 || libc::fcntl(fd.as_raw_fd(), libc::F_SETFL, flags | libc::O_NONBLOCK) == -1 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+
+        Ok(Self {
+            fd: AsyncFd::new(fd)?,
+        })
+    }
+}
+
+impl AsyncRead for PtyReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        loop {
+            let mut guard = ready!(self.fd.poll_read_ready(cx))?;
+            let unfilled = buf.initialize_unfilled();
+
+            let result = guard.try_io(|inner| {
+                // SAFETY: unfilled est un buffer valide de la taille passée
+                let n = unsafe {
+                    libc::read(inner.as_raw_fd(), unfilled.as_mut_ptr().cast(), unfilled.len())
+                };
+                if n < 0 {
+                    Err(io::Error::last_os_error())
+                } else {
+                    Ok(n as usize)
+                }
+            });
+
+            match result {
+                Ok(Ok(n)) => {
+                    buf.advance(n);
+                    return Poll::Ready(Ok(()));
+                }
+                // Linux renvoie EIO quand tous les slaves sont fermés: c'est la fin du flux
+                Ok(Err(e)) if e.raw_os_error() == Some(libc::EIO) => return Poll::Ready(Ok(())),
+                Ok(Err(e)) => return Poll::Ready(Err(e)),
+                Err(_would_block) => continue,
+            }
+        }
+    }
+}
+
+impl Viewport {
+    /// Taille de la zone pour le terminal courant (80x24 si indisponible)
+    fn winsize(&self) -> Winsize {
+        let (cols, rows) = crossterm::terminal::size().unwrap_or((80, 24));
+        let (cols, rows) = match self {
+            Viewport::Terminal => (cols, rows),
+            Viewport::LogPane { process } => crate::tui::log_pane_size(cols, rows, process.as_deref()),
+        };
+        Winsize {
+            ws_row: rows,
+            ws_col: cols,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use crate::config::ReadyWhen;
+use regex::Regex;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+/// Intervalle entre deux tentatives de connexion TCP
+const TCP_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Condition de disponibilité compilée depuis ready_when
+#[derive(Debug, Clone)]
+pub struct ReadyProbe {
+    log: Option<Regex>,
+    tcp: Option<u16>,
+    timeout: Duration,
+}
+
+impl ReadyProbe {
+    /// Compile ready_when (erreur si la regex est invalide ou si aucune condition n'est définie)
+    pub fn from_config(ready_when: &ReadyWhen) -> Result<Self, String> {
+        if ready_when.log.is_none() && ready_when.tcp.is_none() {
+            return Err("ready_when needs 'log' or 'tcp'".to_string());
+        }
+        let log = ready_when
+            .log
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .map_err(|e| format!("Invalid ready_when log pattern: {}", e))?;
+
+        Ok(Self {
+            log,
+            tcp: ready_when.tcp,
+            timeout: Duration::from_secs(ready_when.timeout),
+        })
+    }
+
+    /// Prépare la sonde pour un lancement
+    ///
+    /// Retourne la partie à brancher sur la capture des logs (si la condition
+    /// porte sur une ligne) et l'attente à lancer une fois le processus démarré.
+    pub fn arm(&self) -> (Option<Arc<LogProbe>>, ReadyWaiter) {
+        let (log_probe, log_rx) = match &self.log {
+            Some(regex) => {
+                let (tx, rx) = watch::channel(None);
+                (Some(Arc::new(LogProbe { regex: regex.clone(), matched: tx })), Some(rx))
+            }
+            None => (None, None),
+        };
+
+        let waiter = ReadyWaiter {
+            log: log_rx,
+            tcp: self.tcp,
+            timeout: self.timeout,
+        };
+        (log_probe, waiter)
+    }
+}
+
+/// Regex cherchée dans les lignes capturées (partagée par stdout et stderr)
+#[derive(Debug)]
+pub struct LogProbe {
+    regex: Regex,
+    matched: watch::Sender<Option<String>>,
+}
+
+impl LogProbe {
+    /// Vérifie une ligne; la première correspondance est retenue
+    pub fn check(&self, line: &str) {
+        if self.matched.borrow().is_some() {
+            return;
+        }
+        if let Some(captures) = self.regex.captures(line) {
+            let detail = captures.get(1).unwrap_or_else(|| captures.get(0).unwrap());
+            self.matched.send_replace(Some(detail.as_str().to_string()));
+        }
+    }
+}
+
+/// Résultat de l'attente de disponibilité
+#[derive(Debug, Clone, PartialEq)]
+pub enum Readiness {
+    /// Conditions remplies après `elapsed` (detail = groupe capturé ou port)
+    Ready { elapsed: Duration, detail: Option<String> },
+    /// Conditions non remplies dans le délai
+    TimedOut(Duration),
+}
+
+impl fmt::Display for Readiness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Readiness::Ready { elapsed, detail: Some(detail) } => {
+                write!(f, "ready in {:.1}s ({})", elapsed.as_secs_f32(), detail)
+            }
+            Readiness::Ready { elapsed, detail: None } => {
+                write!(f, "ready in {:.1}s", elapsed.as_secs_f32())
+            }
+            Readiness::TimedOut(timeout) => {
+                write!(f, "startup failed: not ready after {}s", timeout.as_secs())
+            }
+        }
+    }
+}
+
+/// Attente des conditions ready_when d'un lancement
+pub struct ReadyWaiter {
+    log: Option<watch::Receiver<Option<String>>>,
+    tcp: Option<u16>,
+    timeout: Duration,
+}
+
+impl ReadyWaiter {
+    /// Attend que toutes les conditions soient remplies ou que le délai expire
+    pub async fn wait(self, started: Instant) -> Readiness {
+        let checks = async {
+            let mut detail = None;
+            if let Some(mut rx) = self.log {
+                if let Ok(matched) = rx.wait_for(Option::is_some).await {
+                    detail = matched.clone();
+                } else {
+                    // Capture terminée sans correspondance
+                    std::future::pending::<()>().await;
+                }
+            }
+            if let Some(port) = self.tcp {
+                while TcpStream::connect(("localhost", port)).await.is_err() {
+                    tokio::time::sleep(TCP_POLL_INTERVAL).await;
+                }
+                detail.get_or_insert_with(|| format!("port {}", port));
+            }
+            detail
+        };
+
+        match tokio::time::timeout_at(started + self.timeout, checks).await {
+            Ok(detail) => Readiness::Ready { elapsed: started.elapsed(), detail },
+            Err(_) => Readiness::TimedOut(self.timeout),
+        }
+    }
+}
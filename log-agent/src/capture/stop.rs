@@ -0,0 +1,123 @@
+use crate::config::AgentConfig;
+use nix::errno::Errno;
+use nix::sys::signal::{killpg, Signal};
+use nix::unistd::Pid;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::process::Child;
+use tokio::time::Instant;
+
+/// Intervalle de vérification pendant le délai de grâce
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Façon d'arrêter un processus: signal au groupe, délai de grâce, puis SIGKILL
+#[derive(Debug, Clone, Copy)]
+pub struct StopPolicy {
+    pub signal: Signal,
+    pub grace: Duration,
+}
+
+impl StopPolicy {
+    /// Politique définie par agent.stop_signal et agent.stop_timeout
+    pub fn from_config(agent: &AgentConfig) -> Result<Self, String> {
+        Ok(Self {
+            signal: parse_signal(&agent.stop_signal)?,
+            grace: Duration::from_secs(agent.stop_timeout),
+        })
+    }
+}
+
+impl Default for StopPolicy {
+    fn default() -> Self {
+        Self {
+            signal: Signal::SIGTERM,
+            grace: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Étape qui a mis fin au processus
+#[derive(Debug, Clone, PartialEq)]
+pub enum StopOutcome {
+    /// Aucun processus à arrêter
+    NotRunning,
+    /// Le groupe s'est terminé après le signal d'arrêt
+    Stopped(Signal),
+    /// Le groupe n'a pas réagi dans le délai: tué avec SIGKILL
+    Killed(Duration),
+}
+
+impl fmt::Display for StopOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StopOutcome::NotRunning => write!(f, "was not running"),
+            StopOutcome::Stopped(signal) => write!(f, "stopped by {}", signal),
+            StopOutcome::Killed(grace) => write!(
+                f,
+                "did not stop within {}s, process group killed (SIGKILL)",
+                grace.as_secs_f32()
+            ),
+        }
+    }
+}
+
+/// Parse un nom de signal ("SIGTERM", "TERM", "int"...)
+pub fn parse_signal(name: &str) -> Result<Signal, String> {
+    let upper = name.trim().to_uppercase();
+    let full = if upper.starts_with("SIG") { upper } else { format!("SIG{}", upper) };
+    Signal::from_str(&full).map_err(|_| format!("Unknown signal: {}", name))
+}
+
+/// Arrête le groupe de processus `pgid` selon la politique
+///
+/// Le signal d'arrêt est envoyé à tout le groupe (pas seulement à l'enfant
+/// direct), puis on attend que le groupe entier ait disparu. Passé le délai
+/// de grâce, le groupe est tué avec SIGKILL.
+pub async fn terminate(child: Option<&mut Child>, pgid: u32, policy: StopPolicy) -> StopOutcome {
+    let pgid = Pid::from_raw(pgid as i32);
+    let mut child = child;
+
+    // Enfant déjà sorti mais pas encore récupéré
+    if let Some(c) = child.as_deref_mut()
+        && let Ok(Some(_)) = c.try_wait()
+    {
+        child = None;
+    }
+
+    if killpg(pgid, policy.signal) == Err(Errno::ESRCH) {
+        reap(&mut child).await;
+        return StopOutcome::NotRunning;
+    }
+
+    let deadline = Instant::now() + policy.grace;
+    loop {
+        // Récupérer l'enfant direct dès qu'il sort: un zombie compte encore dans le groupe
+        if let Some(c) = child.as_deref_mut()
+            && let Ok(Some(_)) = c.try_wait()
+        {
+            child = None;
+        }
+
+        if killpg(pgid, None) == Err(Errno::ESRCH) {
+            reap(&mut child).await;
+            return StopOutcome::Stopped(policy.signal);
+        }
+
+        if Instant::now() >= deadline {
+            break;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+
+    let _ = killpg(pgid, Signal::SIGKILL);
+    reap(&mut child).await;
+    StopOutcome::Killed(policy.grace)
+}
+
+/// Attend la fin de l'enfant direct s'il n'a pas encore été récupéré
+async fn reap(child: &mut Option<&mut Child>) {
+    if let Some(child) = child.take() {
+        let _ = child.wait().await;
+    }
+}
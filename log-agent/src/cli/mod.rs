@@ -25,10 +25,19 @@ pub enum Commands {
         #[arg(short, long)]
         watch: bool,
 
+        /// PTY mode - run the command in a pseudo-terminal so it keeps colors and line buffering
+        #[arg(long)]
+        pty: bool,
+
         /// Use a predefined command from config (e.g., --cmd dev, --cmd test)
         /// Looks up the command in [agent.commands] section of config
+        /// Repeat to run several commands together (--cmd api --cmd web)
         #[arg(short = 'C', long)]
-        cmd: Option<String>,
+        cmd: Vec<String>,
+
+        /// Run a group of predefined commands from the [group] section of config
+        #[arg(short, long)]
+        group: Option<String>,
 
         /// Command to run (e.g., "bun dev", "cargo run", "npm start")
         /// If not provided, uses --cmd or default_command from config
@@ -36,6 +45,64 @@ pub enum Commands {
         command: Vec<String>,
     },
 
+    /// Follow existing log files (like tail -F) and capture new lines
+    Tail {
+        /// Files or glob patterns (e.g., /var/log/nginx/*.log "logs/**/*.log")
+        #[arg(required = true)]
+        paths: Vec<String>,
+
+        /// Project name for identification (overrides config)
+        #[arg(short, long)]
+        project: Option<String>,
+
+        /// Enable verbose output
+        #[arg(short, long)]
+        verbose: bool,
+
+        /// Watch mode - show the lines in the TUI
+        #[arg(short, long)]
+        watch: bool,
+
+        /// Read files from the beginning instead of only new lines
+        #[arg(long, conflicts_with = "lines")]
+        from_start: bool,
+
+        /// Start with the last N lines of each file
+        #[arg(short = 'n', long)]
+        lines: Option<usize>,
+
+        /// Tag each file's lines with its name as source (process) or as project
+        #[arg(short, long, value_enum, default_value_t = crate::tail::TailTag::Source)]
+        tag: crate::tail::TailTag,
+
+        /// Encoding of the files (overrides config)
+        #[arg(short, long, value_enum)]
+        encoding: Option<crate::config::Encoding>,
+    },
+
+    /// Read logs from stdin (e.g., docker compose logs -f | mcp-log-agent pipe)
+    Pipe {
+        /// Project name for identification (overrides config)
+        #[arg(short, long)]
+        project: Option<String>,
+
+        /// Enable verbose output
+        #[arg(short, long)]
+        verbose: bool,
+
+        /// Watch mode - show the lines in the TUI (keys are read from the terminal)
+        #[arg(short, long)]
+        watch: bool,
+
+        /// Source name added to each line (process field)
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Encoding of the input (overrides config)
+        #[arg(short, long, value_enum)]
+        encoding: Option<crate::config::Encoding>,
+    },
+
     /// Test socket connection to MCP server
     Test {
         /// Send a test message
@@ -106,6 +173,30 @@ pub enum ConfigAction {
     /// Detect configuration sources
     Detect,
     
+    /// Import commands from Procfile, package.json, Makefile or .cargo/config.toml
+    /// into [agent.commands]
+    Import {
+        /// Only read these sources (default: all detected)
+        #[arg(short, long, value_enum)]
+        source: Vec<crate::config::import::ImportSource>,
+
+        /// Target global configuration
+        #[arg(short, long)]
+        global: bool,
+
+        /// Replace commands that already exist with the same name
+        #[arg(short, long)]
+        force: bool,
+
+        /// Write without asking for confirmation
+        #[arg(short = 'y', long)]
+        yes: bool,
+
+        /// Only show the preview, don't write anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
     /// Reset configuration to defaults
     Reset {
         /// Reset global configuration
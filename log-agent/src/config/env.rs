@@ -0,0 +1,227 @@
+use super::types::{CommandConfig, Config, EnvConfig};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Fragments de noms de variables dont la valeur est masquée à l'affichage
+const SECRET_MARKERS: [&str; 9] = [
+    "SECRET",
+    "TOKEN",
+    "PASSWORD",
+    "PASSWD",
+    "API_KEY",
+    "APIKEY",
+    "PRIVATE",
+    "CREDENTIAL",
+    "AUTH",
+];
+
+impl EnvConfig {
+    /// Dossier de travail de la commande (None = dossier courant)
+    pub fn cwd_path(&self) -> Option<PathBuf> {
+        self.cwd.as_ref().map(PathBuf::from)
+    }
+
+    /// Chemin du fichier .env, relatif au cwd de la commande
+    pub fn env_file_path(&self) -> Option<PathBuf> {
+        let file = Path::new(self.env_file.as_ref()?);
+        Some(match self.cwd_path() {
+            Some(cwd) if file.is_relative() => cwd.join(file),
+            _ => file.to_path_buf(),
+        })
+    }
+
+    /// Variables à définir pour la commande: env_file puis env (env l'emporte)
+    ///
+    /// Le fichier est relu à chaque appel, un redémarrage prend donc en
+    /// compte les modifications du .env.
+    pub fn resolve(&self) -> Result<Vec<(String, String)>, String> {
+        let mut vars = match self.env_file_path() {
+            Some(path) => load_dotenv(&path)?,
+            None => Vec::new(),
+        };
+
+        let mut inline: Vec<_> = self.env.iter().collect();
+        inline.sort();
+        for (key, value) in inline {
+            vars.retain(|(k, _)| k != key);
+            vars.push((key.clone(), value.clone()));
+        }
+
+        Ok(vars)
+    }
+
+    /// Vrai si aucune option d'environnement n'est définie
+    pub fn is_empty(&self) -> bool {
+        self.env.is_empty() && self.env_file.is_none() && self.cwd.is_none() && !self.clear_env
+    }
+}
+
+/// Lit un fichier .env
+pub fn load_dotenv(path: &Path) -> Result<Vec<(String, String)>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read env_file {}: {}", path.display(), e))?;
+    parse_dotenv(&content).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Parse la syntaxe dotenv
+///
+/// Supporte les commentaires, le préfixe `export`, les valeurs entre
+/// guillemets simples (littérales) ou doubles (échappements \n, \t, \", \\)
+/// et les commentaires en fin de ligne après une valeur sans guillemets.
+/// Les références `${VAR}` ne sont pas développées.
+pub fn parse_dotenv(content: &str) -> Result<Vec<(String, String)>, String> {
+    let mut vars: Vec<(String, String)> = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line.strip_prefix("export ").map(str::trim_start).unwrap_or(line);
+        let Some((key, raw)) = line.split_once('=') else {
+            return Err(format!("line {}: expected KEY=VALUE", index + 1));
+        };
+
+        let key = key.trim();
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.') {
+            return Err(format!("line {}: invalid variable name '{}'", index + 1, key));
+        }
+
+        let value = parse_value(raw.trim())
+            .ok_or_else(|| format!("line {}: unterminated quoted value", index + 1))?;
+
+        vars.retain(|(k, _)| k != key);
+        vars.push((key.to_string(), value));
+    }
+
+    Ok(vars)
+}
+
+/// Valeur d'une ligne dotenv (None si un guillemet n'est pas fermé)
+fn parse_value(raw: &str) -> Option<String> {
+    if let Some(rest) = raw.strip_prefix('\'') {
+        let end = rest.find('\'')?;
+        return Some(rest[..end].to_string());
+    }
+
+    if let Some(rest) = raw.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = rest.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => return Some(value),
+                '\\' => match chars.next()? {
+                    'n' => value.push('\n'),
+                    't' => value.push('\t'),
+                    'r' => value.push('\r'),
+                    other => value.push(other),
+                },
+                c => value.push(c),
+            }
+        }
+        return None;
+    }
+
+    // Sans guillemets: un " #" commence un commentaire
+    let value = match raw.find(" #") {
+        Some(pos) => &raw[..pos],
+        None => raw,
+    };
+    Some(value.trim_end().to_string())
+}
+
+/// Vrai si le nom de variable ressemble à un secret (token, mot de passe...)
+pub fn is_secret(key: &str) -> bool {
+    let upper = key.to_uppercase();
+    SECRET_MARKERS.iter().any(|marker| upper.contains(marker))
+}
+
+/// Valeur affichable: masquée si la variable ressemble à un secret
+pub fn display_value(key: &str, value: &str) -> String {
+    if is_secret(key) && !value.is_empty() {
+        "********".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Masque les valeurs secrètes des `env` de [agent.commands] (affichage de la config)
+pub fn mask_secrets(config: &mut Config) {
+    for cmd_config in config.agent.commands.values_mut() {
+        if let CommandConfig::Detailed(options) = cmd_config {
+            for (key, value) in options.env.env.iter_mut() {
+                *value = display_value(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn dotenv_syntax() {
+        let content = r#"
+# comment
+export PORT=3000
+NAME = plain value # trailing comment
+LITERAL='a "quoted" $VAR \n'
+ESCAPED="line\nnext \"q\" \\"
+EMPTY=
+URL=http://x/#anchor
+"#;
+        assert_eq!(
+            parse_dotenv(content).unwrap(),
+            pairs(&[
+                ("PORT", "3000"),
+                ("NAME", "plain value"),
+                ("LITERAL", "a \"quoted\" $VAR \\n"),
+                ("ESCAPED", "line\nnext \"q\" \\"),
+                ("EMPTY", ""),
+                ("URL", "http://x/#anchor"),
+            ])
+        );
+    }
+
+    #[test]
+    fn dotenv_last_definition_wins() {
+        assert_eq!(parse_dotenv("A=1\nB=2\nA=3").unwrap(), pairs(&[("B", "2"), ("A", "3")]));
+    }
+
+    #[test]
+    fn dotenv_errors_name_the_line() {
+        assert_eq!(parse_dotenv("A=1\nnot a pair").unwrap_err(), "line 2: expected KEY=VALUE");
+        assert_eq!(parse_dotenv("BAD-NAME=1").unwrap_err(), "line 1: invalid variable name 'BAD-NAME'");
+        assert_eq!(parse_dotenv("A=\"open").unwrap_err(), "line 1: unterminated quoted value");
+    }
+
+    #[test]
+    fn inline_env_overrides_env_file() {
+        let dir = std::env::temp_dir().join(format!("mcp-log-agent-env-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(".env"), "A=file\nB=file\n").unwrap();
+
+        let env = EnvConfig {
+            env: [("B".to_string(), "inline".to_string())].into(),
+            env_file: Some(".env".to_string()),
+            cwd: Some(dir.to_string_lossy().into_owned()),
+            ..Default::default()
+        };
+        assert_eq!(env.resolve().unwrap(), pairs(&[("A", "file"), ("B", "inline")]));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn secrets_are_masked() {
+        assert_eq!(display_value("GITHUB_TOKEN", "abc"), "********");
+        assert_eq!(display_value("db_password", "abc"), "********");
+        assert_eq!(display_value("PORT", "3000"), "3000");
+        assert_eq!(display_value("API_KEY", ""), "");
+    }
+}
@@ -0,0 +1,294 @@
+use super::types::CommandConfig;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Fichier du projet dont on peut importer des commandes
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum ImportSource {
+    /// Process types d'un Procfile
+    Procfile,
+    /// Scripts de package.json
+    PackageJson,
+    /// Cibles .PHONY d'un Makefile
+    Makefile,
+    /// Section [alias] de .cargo/config.toml
+    Cargo,
+}
+
+impl ImportSource {
+    /// Toutes les sources, dans l'ordre de priorité en cas de nom en double
+    pub const ALL: [ImportSource; 4] = [
+        ImportSource::Procfile,
+        ImportSource::PackageJson,
+        ImportSource::Makefile,
+        ImportSource::Cargo,
+    ];
+
+    /// Chemin du fichier lu, relatif au dossier du projet
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            ImportSource::Procfile => "Procfile",
+            ImportSource::PackageJson => "package.json",
+            ImportSource::Makefile => "Makefile",
+            ImportSource::Cargo => ".cargo/config.toml",
+        }
+    }
+}
+
+impl fmt::Display for ImportSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.file_name())
+    }
+}
+
+/// Commande trouvée dans un fichier du projet
+#[derive(Debug, Clone)]
+pub struct ImportedCommand {
+    pub name: String,
+    pub source: ImportSource,
+    pub config: CommandConfig,
+}
+
+/// Changement prévu sur [agent.commands]
+#[derive(Debug, Clone)]
+pub enum ImportChange {
+    /// Nouvelle commande
+    Add(ImportedCommand),
+    /// Commande existante remplacée (--force)
+    Replace { previous: CommandConfig, command: ImportedCommand },
+    /// Commande existante conservée
+    Keep { existing: CommandConfig, command: ImportedCommand },
+}
+
+/// Lit les commandes déclarées dans les sources demandées (toutes si vide)
+///
+/// Une source absente est ignorée. Si deux sources déclarent le même nom,
+/// la première dans l'ordre de `ImportSource::ALL` l'emporte.
+pub fn detect_commands(
+    dir: &Path,
+    sources: &[ImportSource],
+) -> Result<Vec<ImportedCommand>, Box<dyn std::error::Error>> {
+    let mut commands: Vec<ImportedCommand> = Vec::new();
+
+    for source in ImportSource::ALL {
+        if !sources.is_empty() && !sources.contains(&source) {
+            continue;
+        }
+
+        let path = dir.join(source.file_name());
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e).into()),
+        };
+
+        let found = match source {
+            ImportSource::Procfile => parse_procfile(&content),
+            ImportSource::PackageJson => parse_package_json(&content, package_manager(dir))
+                .map_err(|e| format!("Invalid {}: {}", path.display(), e))?,
+            ImportSource::Makefile => parse_makefile(&content),
+            ImportSource::Cargo => parse_cargo_aliases(&content)
+                .map_err(|e| format!("Invalid {}: {}", path.display(), e))?,
+        };
+
+        for (name, command) in found {
+            if commands.iter().any(|c| c.name == name) {
+                continue;
+            }
+            // Syntaxe simple: aucune option écrite (watch = false masquerait agent.watch)
+            commands.push(ImportedCommand {
+                name,
+                source,
+                config: CommandConfig::Simple(command),
+            });
+        }
+    }
+
+    Ok(commands)
+}
+
+/// Compare les commandes importées avec celles déjà configurées
+pub fn plan_import(
+    existing: &HashMap<String, CommandConfig>,
+    imported: Vec<ImportedCommand>,
+    force: bool,
+) -> Vec<ImportChange> {
+    imported
+        .into_iter()
+        .map(|command| match existing.get(&command.name) {
+            None => ImportChange::Add(command),
+            Some(previous) if force && previous.command() != command.config.command() => {
+                ImportChange::Replace { previous: previous.clone(), command }
+            }
+            Some(existing) => ImportChange::Keep { existing: existing.clone(), command },
+        })
+        .collect()
+}
+
+/// Procfile: une ligne "name: command" par process type, lancée via sh
+fn parse_procfile(content: &str) -> Vec<(String, Vec<String>)> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (name, command) = line.split_once(':')?;
+            let (name, command) = (name.trim(), command.trim());
+            if name.is_empty() || command.is_empty() {
+                return None;
+            }
+            Some((
+                name.to_string(),
+                vec!["sh".to_string(), "-c".to_string(), command.to_string()],
+            ))
+        })
+        .collect()
+}
+
+/// package.json: chaque script devient "<pm> run <script>"
+fn parse_package_json(
+    content: &str,
+    package_manager: &str,
+) -> Result<Vec<(String, Vec<String>)>, serde_json::Error> {
+    let package: serde_json::Value = serde_json::from_str(content)?;
+
+    let Some(scripts) = package.get("scripts").and_then(|s| s.as_object()) else {
+        return Ok(Vec::new());
+    };
+
+    Ok(scripts
+        .keys()
+        .map(|name| {
+            (
+                name.clone(),
+                vec![package_manager.to_string(), "run".to_string(), name.clone()],
+            )
+        })
+        .collect())
+}
+
+/// Gestionnaire de paquets du projet, déduit du lockfile (npm par défaut)
+fn package_manager(dir: &Path) -> &'static str {
+    if dir.join("bun.lockb").exists() || dir.join("bun.lock").exists() {
+        "bun"
+    } else if dir.join("pnpm-lock.yaml").exists() {
+        "pnpm"
+    } else if dir.join("yarn.lock").exists() {
+        "yarn"
+    } else {
+        "npm"
+    }
+}
+
+/// Makefile: les cibles déclarées dans .PHONY deviennent "make <target>"
+fn parse_makefile(content: &str) -> Vec<(String, Vec<String>)> {
+    let mut targets: Vec<String> = Vec::new();
+    let mut lines = content.lines();
+
+    while let Some(line) = lines.next() {
+        let Some(rest) = line.trim_start().strip_prefix(".PHONY") else {
+            continue;
+        };
+        let Some(rest) = rest.trim_start().strip_prefix(':') else {
+            continue;
+        };
+
+        // Les listes peuvent continuer sur plusieurs lignes avec "\"
+        let mut declared = rest.to_string();
+        while declared.trim_end().ends_with('\\') {
+            let trimmed = declared.trim_end().trim_end_matches('\\').to_string();
+            declared = format!("{} {}", trimmed, lines.next().unwrap_or_default());
+        }
+
+        for target in declared.split_whitespace() {
+            if !targets.iter().any(|t| t == target) {
+                targets.push(target.to_string());
+            }
+        }
+    }
+
+    targets
+        .into_iter()
+        .map(|target| (target.clone(), vec!["make".to_string(), target]))
+        .collect()
+}
+
+/// .cargo/config.toml: chaque alias devient "cargo <alias>"
+fn parse_cargo_aliases(content: &str) -> Result<Vec<(String, Vec<String>)>, toml::de::Error> {
+    let table: toml::Table = toml::from_str(content)?;
+
+    let Some(aliases) = table.get("alias").and_then(|a| a.as_table()) else {
+        return Ok(Vec::new());
+    };
+
+    Ok(aliases
+        .keys()
+        .map(|name| (name.clone(), vec!["cargo".to_string(), name.clone()]))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn procfile_runs_through_sh() {
+        let commands = parse_procfile("# comment\nweb: npm start\n\nworker:  node w.js --queue=a:b\nbroken\n");
+        assert_eq!(
+            commands,
+            vec![
+                ("web".to_string(), strings(&["sh", "-c", "npm start"])),
+                ("worker".to_string(), strings(&["sh", "-c", "node w.js --queue=a:b"])),
+            ]
+        );
+    }
+
+    #[test]
+    fn makefile_phony_targets_with_continuation() {
+        let commands = parse_makefile(".PHONY: build \\\n  test\nbuild:\n\tcargo build\n.PHONY: test lint\n");
+        let names: Vec<&str> = commands.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["build", "test", "lint"]);
+        assert_eq!(commands[0].1, strings(&["make", "build"]));
+    }
+
+    #[test]
+    fn package_json_scripts_use_package_manager() {
+        let commands = parse_package_json(r#"{"scripts":{"dev":"vite"}}"#, "pnpm").unwrap();
+        assert_eq!(commands, vec![("dev".to_string(), strings(&["pnpm", "run", "dev"]))]);
+        assert!(parse_package_json("{}", "npm").unwrap().is_empty());
+    }
+
+    #[test]
+    fn imported_commands_use_simple_syntax() {
+        let dir = std::env::temp_dir().join(format!("mcp-log-agent-import-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("Procfile"), "web: npm start\n").unwrap();
+
+        let commands = detect_commands(&dir, &[ImportSource::Procfile]).unwrap();
+        // Pas d'options écrites: watch = false masquerait agent.watch
+        assert!(matches!(commands[0].config, CommandConfig::Simple(_)));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn plan_keeps_existing_unless_forced() {
+        let imported = || {
+            vec![ImportedCommand {
+                name: "web".to_string(),
+                source: ImportSource::Procfile,
+                config: CommandConfig::Simple(strings(&["npm", "start"])),
+            }]
+        };
+        let existing = HashMap::from([("web".to_string(), CommandConfig::Simple(strings(&["node", "app.js"])))]);
+
+        assert!(matches!(plan_import(&existing, imported(), false)[0], ImportChange::Keep { .. }));
+        assert!(matches!(plan_import(&existing, imported(), true)[0], ImportChange::Replace { .. }));
+        assert!(matches!(plan_import(&HashMap::new(), imported(), false)[0], ImportChange::Add(_)));
+    }
+}
@@ -1,4 +1,5 @@
-use super::types::Config;
+use super::types::{CommandConfig, Config, Encoding, RestartPolicy};
+use clap::ValueEnum;
 use super::themes::{ThemeManager, ThemeConfig};
 use std::fs;
 use std::path::PathBuf;
@@ -119,6 +120,29 @@ fn apply_env_vars(mut config: Config) -> Config {
         && let Ok(attempts) = val.parse() {
             config.agent.retry_attempts = attempts;
         }
+    if let Ok(val) = std::env::var("MCP_LOG_AGENT_STOP_SIGNAL") {
+        config.agent.stop_signal = val;
+    }
+    if let Ok(val) = std::env::var("MCP_LOG_AGENT_STOP_TIMEOUT")
+        && let Ok(timeout) = val.parse() {
+            config.agent.stop_timeout = timeout;
+        }
+    if let Ok(val) = std::env::var("MCP_LOG_AGENT_RESTART") {
+        match val.to_lowercase().as_str() {
+            "never" => config.agent.restart = RestartPolicy::Never,
+            "on-failure" => config.agent.restart = RestartPolicy::OnFailure,
+            "always" => config.agent.restart = RestartPolicy::Always,
+            _ => {}
+        }
+    }
+    if let Ok(val) = std::env::var("MCP_LOG_AGENT_MAX_RESTARTS")
+        && let Ok(max) = val.parse() {
+            config.agent.max_restarts = max;
+        }
+    if let Ok(val) = std::env::var("MCP_LOG_AGENT_ENCODING")
+        && let Ok(encoding) = Encoding::from_str(&val, true) {
+            config.agent.encoding = encoding;
+        }
 
     // Output
     if let Ok(val) = std::env::var("MCP_LOG_AGENT_COLORS") {
@@ -307,6 +331,94 @@ default_project = "default"
 # Env var: MCP_LOG_AGENT_WATCH
 watch = false
 
+# pty: Run commands in a pseudo-terminal instead of pipes
+# Default: false
+# Tools keep their colors and line buffering as if run in a terminal.
+# stdout and stderr each get their own PTY so they stay separated.
+# Can be overridden per command: { command = [...], pty = true }
+# CLI flag: --pty
+pty = false
+
+# stop_signal: Signal sent to stop or restart a command
+# Default: "SIGTERM"
+# Commands run in their own process group: the signal reaches every
+# process they started (npm -> node -> esbuild...), not just the first one.
+# Possible values: "SIGTERM", "SIGINT", "SIGHUP", "SIGQUIT"... (SIG prefix optional)
+# Can be overridden per command: { command = [...], stop_signal = "SIGINT" }
+# Env var: MCP_LOG_AGENT_STOP_SIGNAL
+stop_signal = "SIGTERM"
+
+# stop_timeout: Grace period in seconds before the process group is killed (SIGKILL)
+# Default: 5
+# Can be overridden per command: { command = [...], stop_timeout = 10 }
+# Env var: MCP_LOG_AGENT_STOP_TIMEOUT
+stop_timeout = 5
+
+# restart: Restart a command automatically when it exits (watch mode only)
+# Default: "never"
+# Possible values: "never", "on-failure" (non-zero exit or killed by a signal), "always"
+# The delay doubles on each restart (see restart_backoff_ms, max 30s).
+# Can be overridden per command: { command = [...], restart = "on-failure" }
+# Env var: MCP_LOG_AGENT_RESTART
+restart = "never"
+
+# max_restarts: Restarts allowed within restart_window before giving up ("crash loop")
+# Default: 5
+# Press 'r' in the TUI to restart manually and reset the counter
+# Can be overridden per command: { command = [...], max_restarts = 10 }
+# Env var: MCP_LOG_AGENT_MAX_RESTARTS
+max_restarts = 5
+
+# restart_window: Window in seconds over which restarts are counted
+# Default: 60
+restart_window = 60
+
+# restart_backoff_ms: Delay before the first restart in milliseconds
+# Default: 500
+restart_backoff_ms = 500
+
+# watch_debounce_ms: Quiet period in milliseconds before restarting on file changes
+# Default: 300
+# Applies to commands with watch_paths (see [agent.commands])
+watch_debounce_ms = 300
+
+# metrics_interval_ms: Interval in milliseconds between resource samples
+# Default: 2000
+# CPU%, RSS, open fds and threads of each process tree (read from /proc, Linux only)
+# Shown in the TUI header and sent to the MCP server as "metrics" messages. 0 disables
+metrics_interval_ms = 2000
+
+# encoding: Encoding of the output of captured commands
+# Default: "utf-8"
+# Possible values: "utf-8", "latin1", "utf-16le", "utf-16be"
+# Invalid bytes never stop the capture: they are replaced with U+FFFD, the
+# line is flagged "repaired" and its raw bytes are kept when logs are saved ('s').
+# Can be overridden per command: { command = [...], encoding = "latin1" }
+# CLI flag: --encoding (tail, pipe)
+# Env var: MCP_LOG_AGENT_ENCODING
+encoding = "utf-8"
+
+# partial_line_timeout_ms: Idle time in milliseconds before an unfinished line is sent
+# Default: 1000
+# Prompts like "Continue? [y/N]" never end with a newline: they are sent after
+# this delay without new output. 0 waits for the newline (or the end of output).
+# Progress bars redrawn with \r are shown as one updating line in the TUI;
+# only their final state is sent to the MCP server.
+partial_line_timeout_ms = 1000
+
+# max_line_bytes: Maximum length of a line in bytes (0 = no limit)
+# Default: 65536
+# Longer lines (minified bundles, big JSON dumps) are cut: the TUI shows the
+# beginning with a "[+N bytes]" indicator, Enter shows the full line.
+max_line_bytes = 65536
+
+# long_lines: How lines longer than max_line_bytes are sent to the MCP server
+# Default: "truncate"
+# Possible values:
+#   "truncate" - Only the first max_line_bytes, with "truncated_bytes" set
+#   "split"    - The whole line, as linked chunks ("chunk": { id, index, count })
+long_lines = "truncate"
+
 # verbose: Enable verbose logging output from the agent itself
 # Default: false
 # Possible values: true, false
@@ -315,10 +427,12 @@ verbose = false
 
 # connection_timeout: Socket connection timeout in seconds
 # Default: 5
+# Also the maximum delay between two reconnection attempts
 # Env var: MCP_LOG_AGENT_CONNECTION_TIMEOUT
 connection_timeout = 5
 
-# retry_attempts: Number of connection retry attempts on failure
+# retry_attempts: Number of reconnection attempts with exponential backoff
+# (100ms, 200ms, 400ms...) before retrying every connection_timeout seconds
 # Default: 3
 # Env var: MCP_LOG_AGENT_RETRY_ATTEMPTS
 retry_attempts = 3
@@ -355,6 +469,45 @@ auto_quit_delay = 5
 # build = ["npm", "run", "build"]
 # test = ["npm", "test"]
 # start = ["npm", "start"]
+# serve = { command = ["npm", "run", "serve"], watch = true, pty = true }
+# worker = { command = ["node", "worker.js"], watch = true, restart = "on-failure", max_restarts = 3 }
+#
+# Restart on file changes (watch mode), like nodemon:
+#   watch_paths  - Files or directories watched recursively (relative to cwd)
+#   ignore_globs - Changes to ignore (.git, node_modules, target are always ignored)
+# api = { command = ["node", "server.js"], watch = true, watch_paths = ["src"], ignore_globs = ["**/*.test.js"] }
+#
+# Readiness probe: when is the process ready (not just started)?
+#   ready_when = { log = "<regex>" }  - A stdout/stderr line matches (first group is shown)
+#   ready_when = { tcp = <port> }     - A local TCP port accepts connections
+#   timeout                           - Seconds before startup is marked as failed (default: 60)
+# web = { command = ["npm", "run", "dev"], ready_when = { log = "listening on .*:(\\d+)" } }
+# db = { command = ["docker", "compose", "up", "db"], ready_when = { tcp = 5432, timeout = 120 } }
+#
+# Dependencies: start a command once others are "started", "ready" or "completed" (exit 0)
+#   Dependencies are started first (added automatically), stopped last
+# app = { command = ["npm", "start"], depends_on = { db = "ready", build = "completed" } }
+# migrate = { command = ["npm", "run", "migrate"], depends_on = ["db"] }
+#
+# Per-command environment and working directory:
+#   cwd       - Directory the command runs from (relative to the current directory)
+#   env_file  - Dotenv file loaded before start, relative to cwd (re-read on restart)
+#   env       - Extra variables, override those from env_file
+#   clear_env - Don't inherit the agent's environment (set PATH in env if needed)
+# api = { command = ["cargo", "run"], cwd = "services/api", env_file = ".env", env = { RUST_LOG = "debug" } }
+
+# ============================================================================
+# [group] - Commands started together from one agent
+# ============================================================================
+# Each group lists names from [agent.commands]. Run them with:
+#   mcp-log-agent run --group fullstack
+# or pick commands directly: mcp-log-agent run --cmd api --cmd web
+#
+# Every process is tagged with its name in the logs, gets a colored prefix
+# in the terminal and its own supervisor in watch mode.
+
+[group]
+# fullstack = ["api", "web", "worker"]
 
 # ============================================================================
 # [output] - Output formatting and display settings
@@ -401,6 +554,113 @@ ignore_patterns = []
 # Note: "debug" captures all levels, "error" captures only errors
 min_level = "debug"
 
+# ============================================================================
+# [levels] - Log level detection
+# ============================================================================
+[levels]
+
+# presets: Recognize common formats after your rules
+# Default: true
+# Formats: [ERROR] / <warn>, level=warn / "level":"error", Rust/log4j "WARN ...",
+#          glog "E0102 ...", logcat " E ", and words like "error", "panic", "warning"
+presets = true
+
+# stdout / stderr: Level of lines matched by no rule and no preset
+# Default: "info"
+# Possible values: "debug", "info", "warn", "error"
+# Example: stderr = "warn" for tools that only write problems to stderr
+stdout = "info"
+stderr = "info"
+
+# rules: Regex rules tested in order before the presets (first match wins)
+# Optional source restricts a rule to "stdout" or "stderr"
+# Example:
+# [[levels.rules]]
+# pattern = "(?i)deprecat"
+# level = "warn"
+#
+# [[levels.rules]]
+# pattern = "Compiled successfully"
+# level = "info"
+# source = "stderr"
+
+# ============================================================================
+# [parsing] - Structured log lines
+# ============================================================================
+[parsing]
+
+# json: Parse lines that are a JSON object (pino, bunyan, tracing-subscriber, zap)
+# Default: true
+# The message key becomes the displayed message, other keys are sent as "fields"
+json = true
+
+# logfmt: Parse key=value lines (time=... level=info msg="..." user=42)
+# Default: true
+# Only lines made entirely of key=value pairs with a level or message key are parsed
+logfmt = true
+
+# level_keys / message_keys / timestamp_keys: Keys to read, the first present wins
+# "a.b" reads key "b" of object "a" (or a key literally named "a.b")
+# Levels: names (info, WARN, fatal...) or numbers (pino/bunyan: 30 = info, 50 = error)
+# Timestamps: RFC 3339 strings, or epoch in milliseconds (pino) or seconds (zap)
+level_keys = ["level", "lvl", "severity", "log.level"]
+message_keys = ["msg", "message", "fields.message"]
+timestamp_keys = ["time", "timestamp", "ts", "@timestamp"]
+
+# ============================================================================
+# [multiline] - Group stack traces and panics into one event
+# ============================================================================
+[multiline]
+
+# enabled: Merge continuation lines into the previous line's event
+# Default: true
+enabled = true
+
+# presets: Recognize common continuation lines after your rules
+# Default: true
+# Stack frames ("    at ...", "  File ...") and the indented lines after them,
+# "Caused by:", Python tracebacks after an error line,
+# Rust panics ("thread 'main' panicked at ..." and its backtrace)
+presets = true
+
+# flush_ms: Send the event after this time without a continuation line
+# Default: 100
+flush_ms = 100
+
+# max_lines: Maximum lines in one event (further lines start a new one)
+# Default: 500
+max_lines = 500
+
+# rules: Regex of continuation lines, tested before the presets
+# Optional after: only when the previous line matches this regex
+# Example:
+# [[multiline.rules]]
+# pattern = "^\\|"
+#
+# [[multiline.rules]]
+# pattern = "^Details: "
+# after = "^Request failed"
+
+# ============================================================================
+# [ansi] - Color codes (ANSI escape sequences) written by the process
+# ============================================================================
+# Messages are always stripped of color codes for searching, filters and levels.
+# Each output gets either the clean message or the original colored one.
+[ansi]
+
+# terminal: Messages echoed in the terminal (clean, styled)
+# Default: "styled"
+terminal = "styled"
+
+# tui: Messages shown in the TUI (clean, styled)
+# Default: "styled"
+tui = "styled"
+
+# socket: Messages sent to the MCP server and the spool (clean, styled)
+# Default: "clean"
+# "styled" puts escape codes back in "message" (searches and AI context see them)
+socket = "clean"
+
 # ============================================================================
 # [performance] - Performance tuning settings
 # ============================================================================
@@ -411,11 +671,20 @@ min_level = "debug"
 # Higher values use more memory but handle bursts better
 buffer_size = 1000
 
-# flush_interval: Interval in milliseconds to flush buffered logs
+# flush_interval: Interval in milliseconds to flush buffered logs to the MCP server
 # Default: 100
-# Lower values reduce latency but increase overhead
+# Lower values reduce latency but increase overhead (0 = send every log immediately)
 flush_interval = 100
 
+# batch_size: Maximum number of logs sent in a single socket write
+# Default: 500
+# A batch is flushed when it is full or when flush_interval expires
+batch_size = 500
+
+# flush_on_error: Send error-level logs immediately instead of waiting for the flush
+# Default: true
+flush_on_error = true
+
 # ============================================================================
 # [performance.tui] - TUI (Terminal User Interface) performance settings
 # ============================================================================
@@ -435,6 +704,29 @@ tick_rate_ms = 250
 # Default: 100 (10 FPS)
 # Prevents lag with high-frequency log output
 frame_rate_ms = 100
+
+# ============================================================================
+# [spool] - Disk spool for logs produced while the MCP server is down
+# ============================================================================
+# Undelivered logs are appended to <dir>/<project>.jsonl and replayed in
+# order as soon as the connection to the MCP server comes back.
+[spool]
+
+# enabled: Keep undelivered logs on disk
+# Default: true
+enabled = true
+
+# dir: Spool directory
+# Default: ~/.cache/mcp-log-agent/spool
+# dir = "/tmp/mcp-log-agent-spool"
+
+# max_size_mb: Maximum size of a project spool file (oldest logs are dropped first)
+# Default: 10
+max_size_mb = 10
+
+# max_age_secs: Spooled logs older than this are not replayed
+# Default: 3600 (1 hour)
+max_age_secs = 3600
 "###;
     
     // Créer le répertoire parent si nécessaire
@@ -459,6 +751,38 @@ pub fn save_config(config: &Config, path: &PathBuf) -> Result<(), Box<dyn std::e
     Ok(())
 }
 
+/// Ajoute ou remplace des commandes dans [agent.commands] d'un fichier de config
+///
+/// Seule cette table est modifiée: les autres clés du fichier restent absentes
+/// (elles ne masquent pas la config globale), comme avec `set_config_value`.
+pub fn save_commands(path: &PathBuf, commands: Vec<(String, CommandConfig)>) -> Result<(), Box<dyn std::error::Error>> {
+    let mut table: toml::Table = if path.exists() {
+        toml::from_str(&fs::read_to_string(path)?)?
+    } else {
+        toml::Table::new()
+    };
+
+    let agent = table
+        .entry("agent")
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+        .as_table_mut()
+        .ok_or("'agent' is not a section")?;
+    let section = agent
+        .entry("commands")
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+        .as_table_mut()
+        .ok_or("'agent.commands' is not a section")?;
+    for (name, command) in commands {
+        section.insert(name, toml::Value::try_from(command)?);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, toml::to_string_pretty(&table)?)?;
+    Ok(())
+}
+
 /// Vérifie si une config locale existe
 pub fn has_local_config() -> bool {
     get_local_config_path().exists()
@@ -533,18 +857,39 @@ fn parse_value_for_field(section: &str, field: &str, value: &str) -> Result<toml
     match (section, field) {
         // Booleans
         ("agent", "verbose") |
+        ("agent", "pty") |
         ("output", "colors") |
         ("output", "show_timestamps") |
-        ("output", "show_pid") => {
+        ("output", "show_pid") |
+        ("spool", "enabled") |
+        ("levels", "presets") |
+        ("parsing", "json") |
+        ("parsing", "logfmt") |
+        ("multiline", "enabled") |
+        ("multiline", "presets") |
+        ("performance", "flush_on_error") => {
             let bool_val = value.to_lowercase() == "true";
             Ok(toml::Value::Boolean(bool_val))
         }
         
         // Integers
         ("agent", "connection_timeout") |
+        ("agent", "stop_timeout") |
+        ("agent", "max_restarts") |
+        ("agent", "restart_window") |
+        ("agent", "restart_backoff_ms") |
+        ("agent", "watch_debounce_ms") |
+        ("agent", "metrics_interval_ms") |
+        ("agent", "partial_line_timeout_ms") |
+        ("agent", "max_line_bytes") |
         ("agent", "retry_attempts") |
+        ("multiline", "flush_ms") |
+        ("multiline", "max_lines") |
         ("performance", "buffer_size") |
-        ("performance", "flush_interval") => {
+        ("performance", "flush_interval") |
+        ("performance", "batch_size") |
+        ("spool", "max_size_mb") |
+        ("spool", "max_age_secs") => {
             let int_val: i64 = value.parse()
                 .map_err(|_| format!("'{}' is not a valid integer", value))?;
             Ok(toml::Value::Integer(int_val))
@@ -558,16 +903,51 @@ fn parse_value_for_field(section: &str, field: &str, value: &str) -> Result<toml
             }
         }
         
-        ("filters", "min_level") => {
+        ("agent", "restart") => {
+            match value.to_lowercase().as_str() {
+                "never" | "on-failure" | "always" => Ok(toml::Value::String(value.to_lowercase())),
+                _ => Err(format!("Invalid restart policy '{}'. Must be: never, on-failure, always", value).into())
+            }
+        }
+
+        ("agent", "encoding") => {
+            match Encoding::from_str(value, true) {
+                Ok(encoding) => Ok(toml::Value::String(encoding.to_string())),
+                Err(_) => Err(format!("Invalid encoding '{}'. Must be: utf-8, latin1, utf-16le, utf-16be", value).into())
+            }
+        }
+
+        ("agent", "long_lines") => {
+            match value.to_lowercase().as_str() {
+                "truncate" | "split" => Ok(toml::Value::String(value.to_lowercase())),
+                _ => Err(format!("Invalid long_lines mode '{}'. Must be: truncate, split", value).into())
+            }
+        }
+
+        ("ansi", "terminal") |
+        ("ansi", "tui") |
+        ("ansi", "socket") => {
+            match value.to_lowercase().as_str() {
+                "clean" | "styled" => Ok(toml::Value::String(value.to_lowercase())),
+                _ => Err(format!("Invalid ansi mode '{}'. Must be: clean, styled", value).into())
+            }
+        }
+
+        ("filters", "min_level") |
+        ("levels", "stdout") |
+        ("levels", "stderr") => {
             match value.to_lowercase().as_str() {
                 "debug" | "info" | "warn" | "error" => Ok(toml::Value::String(value.to_lowercase())),
                 _ => Err(format!("Invalid log level '{}'. Must be: debug, info, warn, error", value).into())
             }
         }
         
-        // Arrays (default_command, ignore_patterns)
+        // Arrays (default_command, ignore_patterns, parsing keys)
         ("agent", "default_command") |
-        ("filters", "ignore_patterns") => {
+        ("filters", "ignore_patterns") |
+        ("parsing", "level_keys") |
+        ("parsing", "message_keys") |
+        ("parsing", "timestamp_keys") => {
             // Parser comme JSON array ou string séparé par des virgules
             if value.starts_with('[') {
                 // JSON array format
@@ -2,6 +2,8 @@ pub mod types;
 pub mod loader;
 pub mod color_schemes;
 pub mod themes;
+pub mod import;
+pub mod env;
 
 pub use types::*;
 pub use loader::*;
@@ -96,15 +96,246 @@ impl ColorName {
 /// Configuration d'une commande prédéfinie
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
+// Lu une seule fois au démarrage: la taille de la variante détaillée importe peu
+#[allow(clippy::large_enum_variant)]
 pub enum CommandConfig {
     /// Simple: juste la commande
     Simple(Vec<String>),
     /// Détaillée: commande + options
-    Detailed {
-        command: Vec<String>,
-        #[serde(default)]
-        watch: bool,
-    },
+    Detailed(CommandOptions),
+}
+
+/// Options d'une commande prédéfinie (syntaxe détaillée)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommandOptions {
+    pub command: Vec<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub watch: bool,
+    /// Surcharge agent.pty pour cette commande
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pty: Option<bool>,
+    /// Surcharge agent.stop_signal pour cette commande
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_signal: Option<String>,
+    /// Surcharge agent.stop_timeout pour cette commande
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_timeout: Option<u64>,
+    /// Surcharge agent.restart pour cette commande
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restart: Option<RestartPolicy>,
+    /// Surcharge agent.max_restarts pour cette commande
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_restarts: Option<u32>,
+    /// Surcharge agent.encoding pour cette commande
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<Encoding>,
+    /// Fichiers ou dossiers surveillés: un changement redémarre la commande (mode watch)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub watch_paths: Vec<String>,
+    /// Globs des chemins dont les changements sont ignorés
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore_globs: Vec<String>,
+    /// Condition de disponibilité: ligne de log ou port TCP
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ready_when: Option<ReadyWhen>,
+    /// Commandes à démarrer avant celle-ci
+    #[serde(default, skip_serializing_if = "DependsOn::is_empty")]
+    pub depends_on: DependsOn,
+    #[serde(flatten)]
+    pub env: EnvConfig,
+}
+
+/// Redémarrage automatique d'un processus sorti (mode watch)
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RestartPolicy {
+    /// Jamais: le processus reste arrêté
+    #[default]
+    Never,
+    /// Seulement si le code de sortie est non nul (ou tué par un signal)
+    OnFailure,
+    /// À chaque sortie, même réussie
+    Always,
+}
+
+/// Encodage de la sortie des commandes capturées
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, clap::ValueEnum)]
+pub enum Encoding {
+    /// UTF-8, les octets invalides sont remplacés par U+FFFD
+    #[default]
+    #[serde(rename = "utf-8", alias = "utf8")]
+    #[value(name = "utf-8", alias = "utf8")]
+    Utf8,
+    /// ISO-8859-1: chaque octet est un caractère (jamais d'erreur)
+    #[serde(rename = "latin1", alias = "iso-8859-1")]
+    #[value(name = "latin1", alias = "iso-8859-1")]
+    Latin1,
+    /// UTF-16 little endian (sortie de programmes Windows)
+    #[serde(rename = "utf-16le")]
+    #[value(name = "utf-16le")]
+    Utf16Le,
+    /// UTF-16 big endian
+    #[serde(rename = "utf-16be")]
+    #[value(name = "utf-16be")]
+    Utf16Be,
+}
+
+impl std::fmt::Display for Encoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Encoding::Utf8 => "utf-8",
+            Encoding::Latin1 => "latin1",
+            Encoding::Utf16Le => "utf-16le",
+            Encoding::Utf16Be => "utf-16be",
+        })
+    }
+}
+
+/// Envoi au serveur MCP des lignes plus longues que max_line_bytes
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LongLines {
+    /// Seul le début est envoyé, avec le nombre d'octets retirés
+    #[default]
+    Truncate,
+    /// La ligne entière part en morceaux liés (champ chunk)
+    Split,
+}
+
+/// Message avec ou sans ses codes ANSI (couleurs) pour une destination
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnsiMode {
+    /// Codes retirés
+    #[default]
+    Clean,
+    /// Message tel qu'écrit par le processus
+    Styled,
+}
+
+/// Dépendances d'une commande
+///
+/// Liste de noms (condition "started") ou table nom -> condition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DependsOn {
+    List(Vec<String>),
+    Conditions(HashMap<String, DependencyCondition>),
+}
+
+/// Ce qu'une dépendance doit avoir atteint avant de lancer la commande
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyCondition {
+    /// Processus lancé
+    Started,
+    /// Condition ready_when remplie (équivaut à started sans ready_when)
+    Ready,
+    /// Processus terminé avec le code 0 (étape de build, codegen...)
+    Completed,
+}
+
+impl Default for DependsOn {
+    fn default() -> Self {
+        DependsOn::List(Vec::new())
+    }
+}
+
+impl DependsOn {
+    /// Vrai si aucune dépendance n'est déclarée
+    pub fn is_empty(&self) -> bool {
+        match self {
+            DependsOn::List(names) => names.is_empty(),
+            DependsOn::Conditions(conditions) => conditions.is_empty(),
+        }
+    }
+
+    /// Dépendances et leur condition, triées par nom
+    pub fn entries(&self) -> Vec<(String, DependencyCondition)> {
+        let mut entries: Vec<_> = match self {
+            DependsOn::List(names) => names
+                .iter()
+                .map(|name| (name.clone(), DependencyCondition::Started))
+                .collect(),
+            DependsOn::Conditions(conditions) => conditions
+                .iter()
+                .map(|(name, condition)| (name.clone(), *condition))
+                .collect(),
+        };
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        entries
+    }
+}
+
+/// Condition pour considérer un processus comme prêt (ready_when)
+///
+/// Si `log` et `tcp` sont tous deux définis, les deux doivent être remplis.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReadyWhen {
+    /// Regex cherchée dans stdout/stderr (le premier groupe capturé est affiché)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log: Option<String>,
+    /// Port TCP local qui doit accepter les connexions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tcp: Option<u16>,
+    /// Délai en secondes avant de considérer le démarrage comme échoué
+    #[serde(default = "default_ready_timeout")]
+    pub timeout: u64,
+}
+
+fn default_ready_timeout() -> u64 {
+    60
+}
+
+/// Environnement et dossier de travail d'une commande
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EnvConfig {
+    /// Variables ajoutées (prioritaires sur env_file)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+    /// Fichier dotenv, relatif à cwd
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_file: Option<String>,
+    /// Dossier de travail, relatif au dossier courant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    /// Ne pas hériter de l'environnement de l'agent
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub clear_env: bool,
+}
+
+impl CommandConfig {
+    /// Commande et arguments
+    pub fn command(&self) -> &[String] {
+        match self {
+            CommandConfig::Simple(command) => command,
+            CommandConfig::Detailed(options) => &options.command,
+        }
+    }
+
+    /// Mode watch propre à la commande (None = réglage global)
+    pub fn watch(&self) -> Option<bool> {
+        match self {
+            CommandConfig::Simple(_) => None,
+            CommandConfig::Detailed(options) => Some(options.watch),
+        }
+    }
+
+    /// Options détaillées (None pour la syntaxe simple)
+    pub fn options(&self) -> Option<&CommandOptions> {
+        match self {
+            CommandConfig::Simple(_) => None,
+            CommandConfig::Detailed(options) => Some(options),
+        }
+    }
+
+    /// Mode PTY propre à la commande (None = réglage global)
+    pub fn pty(&self) -> Option<bool> {
+        match self {
+            CommandConfig::Simple(_) => None,
+            CommandConfig::Detailed(options) => options.pty,
+        }
+    }
 }
 
 /// Configuration principale
@@ -123,7 +354,20 @@ pub struct Config {
     #[serde(default)]
     pub filters: FilterConfig,
     #[serde(default)]
+    pub levels: LevelsConfig,
+    #[serde(default)]
+    pub parsing: ParsingConfig,
+    #[serde(default)]
+    pub multiline: MultilineConfig,
+    #[serde(default)]
+    pub ansi: AnsiConfig,
+    #[serde(default)]
     pub performance: PerformanceConfig,
+    #[serde(default)]
+    pub spool: SpoolConfig,
+    /// Groupes de commandes lancées ensemble (--group name)
+    #[serde(default)]
+    pub group: HashMap<String, Vec<String>>,
 }
 
 fn default_theme_name() -> String {
@@ -145,6 +389,45 @@ pub struct AgentConfig {
     pub verbose: bool,
     #[serde(default)]
     pub watch: bool,
+    /// Lance la commande dans un pseudo-terminal (couleurs et line buffering conservés)
+    #[serde(default)]
+    pub pty: bool,
+    /// Signal envoyé au groupe de processus pour l'arrêter (stop, restart)
+    #[serde(default = "default_stop_signal")]
+    pub stop_signal: String,
+    /// Délai de grâce en secondes avant SIGKILL
+    #[serde(default = "default_stop_timeout")]
+    pub stop_timeout: u64,
+    /// Redémarrage automatique en mode watch (never, on-failure, always)
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    /// Nombre max de redémarrages dans restart_window avant le "crash loop"
+    #[serde(default = "default_max_restarts")]
+    pub max_restarts: u32,
+    /// Fenêtre en secondes sur laquelle les redémarrages sont comptés
+    #[serde(default = "default_restart_window")]
+    pub restart_window: u64,
+    /// Délai avant le premier redémarrage en ms (doublé à chaque redémarrage)
+    #[serde(default = "default_restart_backoff_ms")]
+    pub restart_backoff_ms: u64,
+    /// Délai en ms sans nouveau changement avant de redémarrer (watch_paths)
+    #[serde(default = "default_watch_debounce_ms")]
+    pub watch_debounce_ms: u64,
+    /// Intervalle en ms entre deux mesures CPU/RSS/fds (0 = désactivé)
+    #[serde(default = "default_metrics_interval_ms")]
+    pub metrics_interval_ms: u64,
+    /// Encodage de la sortie des commandes (utf-8, latin1, utf-16le, utf-16be)
+    #[serde(default)]
+    pub encoding: Encoding,
+    /// Délai en ms sans nouvel octet avant d'envoyer une ligne incomplète (0 = attendre le saut de ligne)
+    #[serde(default = "default_partial_line_timeout_ms")]
+    pub partial_line_timeout_ms: u64,
+    /// Taille max d'une ligne en octets, au-delà elle est coupée (0 = pas de limite)
+    #[serde(default = "default_max_line_bytes")]
+    pub max_line_bytes: usize,
+    /// Envoi des lignes trop longues au serveur MCP (truncate, split)
+    #[serde(default)]
+    pub long_lines: LongLines,
     #[serde(default = "default_connection_timeout")]
     pub connection_timeout: u64,
     #[serde(default = "default_retry_attempts")]
@@ -155,6 +438,42 @@ pub struct AgentConfig {
     pub auto_quit_delay: u8,
 }
 
+fn default_stop_signal() -> String {
+    "SIGTERM".to_string()
+}
+
+fn default_stop_timeout() -> u64 {
+    5
+}
+
+fn default_max_restarts() -> u32 {
+    5
+}
+
+fn default_restart_window() -> u64 {
+    60
+}
+
+fn default_restart_backoff_ms() -> u64 {
+    500
+}
+
+fn default_watch_debounce_ms() -> u64 {
+    300
+}
+
+fn default_metrics_interval_ms() -> u64 {
+    2000
+}
+
+fn default_partial_line_timeout_ms() -> u64 {
+    1000
+}
+
+fn default_max_line_bytes() -> usize {
+    65536
+}
+
 fn default_socket_path() -> String {
     "/tmp/log-agent.sock".to_string()
 }
@@ -298,15 +617,14 @@ impl<'de> Deserialize<'de> for Color {
         // 3. Sinon essayer de parser comme RGB "r,g,b"
         if value.contains(',') {
             let parts: Vec<&str> = value.split(',').collect();
-            if parts.len() == 3 {
-                if let (Ok(r), Ok(g), Ok(b)) = (
+            if parts.len() == 3
+                && let (Ok(r), Ok(g), Ok(b)) = (
                     parts[0].trim().parse::<u8>(),
                     parts[1].trim().parse::<u8>(),
                     parts[2].trim().parse::<u8>(),
                 ) {
                     return Ok(Color::Rgb(r, g, b));
                 }
-            }
         }
         
         // Si rien ne correspond, retourner une erreur
@@ -373,6 +691,126 @@ pub enum LogLevel {
 }
 
 
+/// Détection du niveau des lignes capturées
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelsConfig {
+    /// Règles testées dans l'ordre, avant les presets (la première qui matche gagne)
+    #[serde(default)]
+    pub rules: Vec<LevelRule>,
+    /// Formats courants reconnus après les règles ([INFO], level=warn, glog, Rust...)
+    #[serde(default = "default_true")]
+    pub presets: bool,
+    /// Niveau d'une ligne stdout qu'aucune règle ne reconnaît
+    #[serde(default = "default_unmatched_level")]
+    pub stdout: LogLevel,
+    /// Niveau d'une ligne stderr qu'aucune règle ne reconnaît
+    #[serde(default = "default_unmatched_level")]
+    pub stderr: LogLevel,
+}
+
+fn default_unmatched_level() -> LogLevel {
+    LogLevel::Info
+}
+
+/// Règle [[levels.rules]]: une regex qui donne le niveau de la ligne
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelRule {
+    pub pattern: String,
+    pub level: LogLevel,
+    /// Ne s'applique qu'à ce flux (stdout ou stderr)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<crate::types::LogSource>,
+}
+
+/// Lignes structurées: niveau, message et horodatage lus dans la ligne
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParsingConfig {
+    /// Analyse les lignes qui sont un objet JSON (pino, bunyan, tracing-subscriber, zap)
+    #[serde(default = "default_true")]
+    pub json: bool,
+    /// Analyse les lignes logfmt (time=... level=info msg="..." user=42) qui ont un niveau ou un message
+    #[serde(default = "default_true")]
+    pub logfmt: bool,
+    /// Clés du niveau, la première présente gagne ("a.b" = clé b de l'objet a)
+    #[serde(default = "default_level_keys")]
+    pub level_keys: Vec<String>,
+    /// Clés du message affiché
+    #[serde(default = "default_message_keys")]
+    pub message_keys: Vec<String>,
+    /// Clés de l'horodatage (RFC 3339, ou epoch en secondes / millisecondes)
+    #[serde(default = "default_timestamp_keys")]
+    pub timestamp_keys: Vec<String>,
+}
+
+fn default_level_keys() -> Vec<String> {
+    ["level", "lvl", "severity", "log.level"].map(String::from).to_vec()
+}
+
+fn default_message_keys() -> Vec<String> {
+    ["msg", "message", "fields.message"].map(String::from).to_vec()
+}
+
+fn default_timestamp_keys() -> Vec<String> {
+    ["time", "timestamp", "ts", "@timestamp"].map(String::from).to_vec()
+}
+
+/// Regroupement des lignes d'un même événement (stack traces, panics)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultilineConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Règles testées avant les presets (une seule qui matche suffit)
+    #[serde(default)]
+    pub rules: Vec<MultilineRule>,
+    /// Formats courants: indentation, "at ...", Traceback, panics Rust, "Caused by:"
+    #[serde(default = "default_true")]
+    pub presets: bool,
+    /// Délai en ms sans ligne de continuation avant d'envoyer l'événement
+    #[serde(default = "default_multiline_flush_ms")]
+    pub flush_ms: u64,
+    /// Nombre max de lignes d'un événement (les suivantes en commencent un nouveau)
+    #[serde(default = "default_multiline_max_lines")]
+    pub max_lines: usize,
+}
+
+fn default_multiline_flush_ms() -> u64 {
+    100
+}
+
+fn default_multiline_max_lines() -> usize {
+    500
+}
+
+/// Règle [[multiline.rules]]: une ligne qui matche continue l'événement précédent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultilineRule {
+    pub pattern: String,
+    /// Ne s'applique que si la ligne précédente matche cette regex
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+}
+
+/// Codes ANSI des messages selon la destination
+///
+/// Le pipeline retire toujours les codes du message (recherche, filtres, niveau);
+/// la version colorée est gardée pour les destinations en "styled".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnsiConfig {
+    /// Terminal (mode normal)
+    #[serde(default = "default_ansi_styled")]
+    pub terminal: AnsiMode,
+    /// TUI (mode watch)
+    #[serde(default = "default_ansi_styled")]
+    pub tui: AnsiMode,
+    /// Serveur MCP et spool
+    #[serde(default)]
+    pub socket: AnsiMode,
+}
+
+fn default_ansi_styled() -> AnsiMode {
+    AnsiMode::Styled
+}
+
 /// Configuration des performances
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PerformanceConfig {
@@ -380,10 +818,38 @@ pub struct PerformanceConfig {
     pub buffer_size: usize,
     #[serde(default = "default_flush_interval")]
     pub flush_interval: u64,
+    /// Nombre max de logs envoyés en une seule écriture socket
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+    /// Les logs de niveau error partent immédiatement sans attendre flush_interval
+    #[serde(default = "default_true")]
+    pub flush_on_error: bool,
     #[serde(default)]
     pub tui: TuiConfig,
 }
 
+/// Configuration du spool disque (logs non délivrés au serveur MCP)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpoolConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Dossier du spool (défaut: ~/.cache/mcp-log-agent/spool)
+    #[serde(default)]
+    pub dir: Option<String>,
+    #[serde(default = "default_spool_max_size_mb")]
+    pub max_size_mb: u64,
+    #[serde(default = "default_spool_max_age_secs")]
+    pub max_age_secs: u64,
+}
+
+fn default_spool_max_size_mb() -> u64 {
+    10
+}
+
+fn default_spool_max_age_secs() -> u64 {
+    3600
+}
+
 /// Configuration de la TUI
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TuiConfig {
@@ -445,6 +911,10 @@ fn default_flush_interval() -> u64 {
     100
 }
 
+fn default_batch_size() -> usize {
+    500
+}
+
 // ==================== Default helpers for TUI colors ====================
 
 fn default_tui_header_bg() -> Color {
@@ -567,7 +1037,13 @@ impl Default for Config {
             theme: default_theme_name(),
             colors: ColorConfig::default(),
             filters: FilterConfig::default(),
+            levels: LevelsConfig::default(),
+            parsing: ParsingConfig::default(),
+            multiline: MultilineConfig::default(),
+            ansi: AnsiConfig::default(),
             performance: PerformanceConfig::default(),
+            spool: SpoolConfig::default(),
+            group: HashMap::new(),
         }
     }
 }
@@ -581,6 +1057,19 @@ impl Default for AgentConfig {
             commands: HashMap::new(),
             verbose: false,
             watch: false,
+            pty: false,
+            stop_signal: default_stop_signal(),
+            stop_timeout: default_stop_timeout(),
+            restart: RestartPolicy::Never,
+            max_restarts: default_max_restarts(),
+            restart_window: default_restart_window(),
+            restart_backoff_ms: default_restart_backoff_ms(),
+            watch_debounce_ms: default_watch_debounce_ms(),
+            metrics_interval_ms: default_metrics_interval_ms(),
+            encoding: Encoding::default(),
+            partial_line_timeout_ms: default_partial_line_timeout_ms(),
+            max_line_bytes: default_max_line_bytes(),
+            long_lines: LongLines::default(),
             connection_timeout: 5,
             retry_attempts: 3,
             auto_quit: false,
@@ -664,16 +1153,74 @@ impl Default for FilterConfig {
     }
 }
 
+impl Default for LevelsConfig {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            presets: true,
+            stdout: default_unmatched_level(),
+            stderr: default_unmatched_level(),
+        }
+    }
+}
+
+impl Default for ParsingConfig {
+    fn default() -> Self {
+        Self {
+            json: true,
+            logfmt: true,
+            level_keys: default_level_keys(),
+            message_keys: default_message_keys(),
+            timestamp_keys: default_timestamp_keys(),
+        }
+    }
+}
+
+impl Default for MultilineConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            rules: Vec::new(),
+            presets: true,
+            flush_ms: default_multiline_flush_ms(),
+            max_lines: default_multiline_max_lines(),
+        }
+    }
+}
+
+impl Default for AnsiConfig {
+    fn default() -> Self {
+        Self {
+            terminal: AnsiMode::Styled,
+            tui: AnsiMode::Styled,
+            socket: AnsiMode::Clean,
+        }
+    }
+}
+
 impl Default for PerformanceConfig {
     fn default() -> Self {
         Self {
             buffer_size: 1000,
             flush_interval: 100,
+            batch_size: 500,
+            flush_on_error: true,
             tui: TuiConfig::default(),
         }
     }
 }
 
+impl Default for SpoolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dir: None,
+            max_size_mb: 10,
+            max_age_secs: 3600,
+        }
+    }
+}
+
 impl Default for TuiConfig {
     fn default() -> Self {
         Self {
@@ -0,0 +1,414 @@
+use crate::config::{CommandConfig, DependencyCondition};
+use crate::process::ProcessSpec;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// Dépendance d'un processus sur une autre commande
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dependency {
+    pub name: String,
+    pub condition: DependencyCondition,
+}
+
+impl fmt::Display for Dependency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let condition = match self.condition {
+            DependencyCondition::Started => "started",
+            DependencyCondition::Ready => "ready",
+            DependencyCondition::Completed => "completed",
+        };
+        write!(f, "{} ({})", self.name, condition)
+    }
+}
+
+/// Dépendances déclarées par une commande de [agent.commands]
+pub fn dependencies(cmd_config: &CommandConfig) -> Vec<Dependency> {
+    cmd_config
+        .options()
+        .map(|options| options.depends_on.entries())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, condition)| Dependency { name, condition })
+        .collect()
+}
+
+/// Ordre de démarrage: les commandes demandées et leurs dépendances, dépendances d'abord
+///
+/// Les dépendances non demandées explicitement sont ajoutées. Erreur si une
+/// dépendance n'existe pas ou si les dépendances forment un cycle.
+pub fn start_order(
+    commands: &HashMap<String, CommandConfig>,
+    names: &[String],
+) -> Result<Vec<String>, String> {
+    let mut order = Vec::new();
+    let mut path = Vec::new();
+    for name in names {
+        visit(commands, name, &mut path, &mut order)?;
+    }
+    Ok(order)
+}
+
+/// Vérifie toutes les dépendances de [agent.commands] (noms connus, pas de cycle)
+pub fn check_dependencies(commands: &HashMap<String, CommandConfig>) -> Result<(), String> {
+    let mut names: Vec<String> = commands.keys().cloned().collect();
+    names.sort();
+    start_order(commands, &names).map(|_| ())
+}
+
+/// Groupes d'arrêt: indices des processus, ceux qui dépendent des autres d'abord
+///
+/// `processes` est dans l'ordre de démarrage (dépendances d'abord). Les processus
+/// d'un même groupe ne dépendent pas les uns des autres: ils peuvent être
+/// arrêtés en parallèle, le groupe suivant une fois le précédent arrêté.
+pub fn stop_tiers(processes: &[&ProcessSpec]) -> Vec<Vec<usize>> {
+    // Profondeur: 0 sans dépendance lancée, sinon 1 + celle de la plus profonde
+    let mut depths: Vec<usize> = Vec::with_capacity(processes.len());
+    for spec in processes {
+        let depth = spec
+            .depends_on
+            .iter()
+            .filter_map(|dependency| {
+                processes[..depths.len()]
+                    .iter()
+                    .position(|p| p.label() == dependency.name)
+            })
+            .map(|index| depths[index] + 1)
+            .max()
+            .unwrap_or(0);
+        depths.push(depth);
+    }
+
+    let deepest = depths.iter().copied().max().unwrap_or(0);
+    (0..=deepest)
+        .rev()
+        .map(|depth| (0..processes.len()).filter(|&i| depths[i] == depth).collect::<Vec<_>>())
+        .filter(|tier| !tier.is_empty())
+        .collect()
+}
+
+/// Parcours en profondeur: `path` contient la chaîne en cours (détection de cycle)
+fn visit(
+    commands: &HashMap<String, CommandConfig>,
+    name: &str,
+    path: &mut Vec<String>,
+    order: &mut Vec<String>,
+) -> Result<(), String> {
+    if order.iter().any(|n| n == name) {
+        return Ok(());
+    }
+    if let Some(start) = path.iter().position(|n| n == name) {
+        let mut cycle = path[start..].to_vec();
+        cycle.push(name.to_string());
+        return Err(format!("Dependency cycle: {}", cycle.join(" -> ")));
+    }
+
+    let Some(cmd_config) = commands.get(name) else {
+        return Err(match path.last() {
+            Some(parent) => format!("Command '{}' depends on unknown command '{}'", parent, name),
+            None => format!("Predefined command '{}' not found", name),
+        });
+    };
+
+    path.push(name.to_string());
+    for dependency in dependencies(cmd_config) {
+        visit(commands, &dependency.name, path, order)?;
+    }
+    path.pop();
+
+    order.push(name.to_string());
+    Ok(())
+}
+
+/// Avancement d'un processus, tel que vu par ceux qui en dépendent
+#[derive(Debug, Clone, Default)]
+struct Progress {
+    started: bool,
+    ready: bool,
+    startup_failed: bool,
+    exit_code: Option<i32>,
+    /// Raison pour laquelle le processus n'a pas été lancé
+    not_started: Option<String>,
+}
+
+/// Résultat de la vérification des dépendances d'un processus
+#[derive(Debug, Clone, PartialEq)]
+pub enum DependencyStatus {
+    Waiting,
+    Satisfied,
+    Failed(String),
+}
+
+/// Avancement de tous les processus lancés ensemble (partagé entre leurs tâches)
+#[derive(Clone)]
+pub struct Lifecycles {
+    progress: Arc<watch::Sender<HashMap<String, Progress>>>,
+    /// Processus -> processus qui dépendent de lui
+    dependents: Arc<HashMap<String, Vec<String>>>,
+}
+
+impl Lifecycles {
+    pub fn new(processes: &[ProcessSpec]) -> Self {
+        let mut progress = HashMap::new();
+        let mut dependents: HashMap<String, Vec<String>> = HashMap::new();
+        for spec in processes {
+            progress.insert(spec.label(), Progress::default());
+            for dependency in &spec.depends_on {
+                dependents
+                    .entry(dependency.name.clone())
+                    .or_default()
+                    .push(spec.label());
+            }
+        }
+
+        Self {
+            progress: Arc::new(watch::channel(progress).0),
+            dependents: Arc::new(dependents),
+        }
+    }
+
+    /// Handle pour publier l'avancement d'un processus
+    pub fn handle(&self, name: &str) -> LifecycleHandle {
+        LifecycleHandle {
+            lifecycles: self.clone(),
+            name: name.to_string(),
+        }
+    }
+
+    /// État actuel des dépendances (non bloquant)
+    pub fn check(&self, dependencies: &[Dependency]) -> DependencyStatus {
+        evaluate(&self.progress.borrow(), dependencies)
+    }
+
+    /// Attend que les dépendances soient remplies (Err si l'une a échoué)
+    pub async fn wait(&self, dependencies: &[Dependency]) -> Result<(), String> {
+        let mut rx = self.progress.subscribe();
+        let progress = rx
+            .wait_for(|progress| evaluate(progress, dependencies) != DependencyStatus::Waiting)
+            .await
+            .map_err(|e| e.to_string())?;
+        match evaluate(&progress, dependencies) {
+            DependencyStatus::Failed(reason) => Err(reason),
+            _ => Ok(()),
+        }
+    }
+
+    /// Attend l'arrêt des processus qui dépendent de `name` (arrêt en ordre inverse)
+    pub async fn wait_dependents_stopped(&self, name: &str) {
+        let Some(dependents) = self.dependents.get(name) else {
+            return;
+        };
+        let mut rx = self.progress.subscribe();
+        let _ = rx
+            .wait_for(|progress| {
+                dependents.iter().all(|dependent| {
+                    progress
+                        .get(dependent)
+                        .is_none_or(|p| p.exit_code.is_some() || p.not_started.is_some())
+                })
+            })
+            .await;
+    }
+
+    fn update(&self, name: &str, change: impl FnOnce(&mut Progress)) {
+        self.progress.send_modify(|progress| {
+            if let Some(entry) = progress.get_mut(name) {
+                change(entry);
+            }
+        });
+    }
+}
+
+/// Publie l'avancement d'un processus pour ceux qui en dépendent
+#[derive(Clone)]
+pub struct LifecycleHandle {
+    lifecycles: Lifecycles,
+    name: String,
+}
+
+impl LifecycleHandle {
+    /// Processus lancé (`ready` s'il n'a pas de ready_when)
+    pub fn started(&self, ready: bool) {
+        self.lifecycles.update(&self.name, |p| {
+            *p = Progress { started: true, ready, ..Default::default() };
+        });
+    }
+
+    /// Condition ready_when remplie
+    pub fn ready(&self) {
+        self.lifecycles.update(&self.name, |p| p.ready = true);
+    }
+
+    /// Condition ready_when non remplie dans le délai
+    pub fn startup_failed(&self) {
+        self.lifecycles.update(&self.name, |p| p.startup_failed = true);
+    }
+
+    /// Processus terminé
+    pub fn exited(&self, code: i32) {
+        self.lifecycles.update(&self.name, |p| p.exit_code = Some(code));
+    }
+
+    /// Processus de nouveau en attente de lancement (redémarrage complet)
+    pub fn reset(&self) {
+        self.lifecycles.update(&self.name, |p| *p = Progress::default());
+    }
+
+    /// Processus jamais lancé (erreur de lancement ou dépendance en échec)
+    pub fn not_started(&self, reason: String) {
+        self.lifecycles.update(&self.name, |p| p.not_started = Some(reason));
+    }
+}
+
+/// Vérifie chaque dépendance selon sa condition
+fn evaluate(progress: &HashMap<String, Progress>, dependencies: &[Dependency]) -> DependencyStatus {
+    let mut status = DependencyStatus::Satisfied;
+
+    for dependency in dependencies {
+        let name = &dependency.name;
+        let Some(p) = progress.get(name) else {
+            return DependencyStatus::Failed(format!("dependency '{}' is not running", name));
+        };
+        if p.not_started.is_some() {
+            return DependencyStatus::Failed(format!("dependency '{}' was not started", name));
+        }
+
+        let dependency_status = match dependency.condition {
+            DependencyCondition::Started if p.started => DependencyStatus::Satisfied,
+            DependencyCondition::Ready if p.ready => DependencyStatus::Satisfied,
+            DependencyCondition::Ready if p.startup_failed => {
+                DependencyStatus::Failed(format!("dependency '{}' did not become ready", name))
+            }
+            DependencyCondition::Ready => match p.exit_code {
+                Some(code) => DependencyStatus::Failed(format!(
+                    "dependency '{}' exited with code {} before being ready",
+                    name, code
+                )),
+                None => DependencyStatus::Waiting,
+            },
+            DependencyCondition::Completed => match p.exit_code {
+                Some(0) => DependencyStatus::Satisfied,
+                Some(code) => DependencyStatus::Failed(format!(
+                    "dependency '{}' exited with code {}",
+                    name, code
+                )),
+                None => DependencyStatus::Waiting,
+            },
+            DependencyCondition::Started => DependencyStatus::Waiting,
+        };
+
+        match dependency_status {
+            DependencyStatus::Failed(_) => return dependency_status,
+            DependencyStatus::Waiting => status = DependencyStatus::Waiting,
+            DependencyStatus::Satisfied => {}
+        }
+    }
+
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn spec(name: &str, depends_on: &[&str]) -> ProcessSpec {
+        let mut spec = ProcessSpec::inline(vec!["true".to_string()], &Config::default());
+        spec.name = Some(name.to_string());
+        spec.depends_on = depends_on
+            .iter()
+            .map(|name| Dependency { name: name.to_string(), condition: DependencyCondition::Started })
+            .collect();
+        spec
+    }
+
+    #[test]
+    fn stop_tiers_stop_dependents_first_and_siblings_together() {
+        // db <- api <- web, db <- worker, cache seul
+        let processes = [
+            spec("db", &[]),
+            spec("cache", &[]),
+            spec("api", &["db"]),
+            spec("worker", &["db"]),
+            spec("web", &["api", "cache"]),
+        ];
+        let refs: Vec<&ProcessSpec> = processes.iter().collect();
+        assert_eq!(stop_tiers(&refs), vec![vec![4], vec![2, 3], vec![0, 1]]);
+    }
+
+    #[test]
+    fn stop_tiers_ignore_dependencies_not_running() {
+        let processes = [spec("api", &["db"]), spec("web", &[])];
+        let refs: Vec<&ProcessSpec> = processes.iter().collect();
+        assert_eq!(stop_tiers(&refs), vec![vec![0, 1]]);
+    }
+
+    fn commands(toml: &str) -> HashMap<String, CommandConfig> {
+        toml::from_str(toml).unwrap()
+    }
+
+    fn dependency(name: &str, condition: DependencyCondition) -> Dependency {
+        Dependency { name: name.to_string(), condition }
+    }
+
+    #[test]
+    fn start_order_puts_dependencies_first() {
+        let commands = commands(
+            r#"
+            db = ["postgres"]
+            api = { command = ["api"], depends_on = ["db"] }
+            web = { command = ["web"], depends_on = { api = "ready" } }
+            "#,
+        );
+        assert_eq!(
+            start_order(&commands, &["web".to_string()]).unwrap(),
+            vec!["db", "api", "web"]
+        );
+    }
+
+    #[test]
+    fn start_order_rejects_cycles_and_unknown_commands() {
+        let cycle = commands(
+            r#"
+            a = { command = ["a"], depends_on = ["b"] }
+            b = { command = ["b"], depends_on = ["a"] }
+            "#,
+        );
+        let err = check_dependencies(&cycle).unwrap_err();
+        assert_eq!(err, "Dependency cycle: a -> b -> a");
+
+        let unknown = commands(r#"api = { command = ["api"], depends_on = ["db"] }"#);
+        let err = check_dependencies(&unknown).unwrap_err();
+        assert!(err.contains("unknown command 'db'"), "{}", err);
+    }
+
+    #[test]
+    fn conditions_follow_lifecycle_progress() {
+        let processes = [spec("db", &[]), spec("migrate", &[])];
+        let lifecycles = Lifecycles::new(&processes);
+        let db = lifecycles.handle("db");
+        let migrate = lifecycles.handle("migrate");
+        let ready = [dependency("db", DependencyCondition::Ready)];
+        let completed = [dependency("migrate", DependencyCondition::Completed)];
+
+        assert_eq!(lifecycles.check(&ready), DependencyStatus::Waiting);
+        db.started(false);
+        let started = [dependency("db", DependencyCondition::Started)];
+        assert_eq!(lifecycles.check(&started), DependencyStatus::Satisfied);
+        assert_eq!(lifecycles.check(&ready), DependencyStatus::Waiting);
+        db.ready();
+        assert_eq!(lifecycles.check(&ready), DependencyStatus::Satisfied);
+
+        migrate.started(true);
+        assert_eq!(lifecycles.check(&completed), DependencyStatus::Waiting);
+        migrate.exited(1);
+        assert!(matches!(lifecycles.check(&completed), DependencyStatus::Failed(_)));
+
+        // Redémarrage complet: on attend de nouveau
+        migrate.reset();
+        db.reset();
+        assert_eq!(lifecycles.check(&completed), DependencyStatus::Waiting);
+        assert_eq!(lifecycles.check(&ready), DependencyStatus::Waiting);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::capture::{capture_stream, CaptureContext};
+use crate::config::Config;
+use crate::pipeline::Pipeline;
+use crate::tail::TailSet;
+use crate::types::{LogMessage, LogSource};
+use std::io::Read;
+use tokio::io::{AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Taille des blocs lus sur stdin
+const CHUNK_SIZE: usize = 8192;
+
+/// Logs qui ne viennent pas d'un processus lancé par l'agent
+pub enum Input {
+    /// Fichiers suivis (mcp-log-agent tail)
+    Files(TailSet),
+    /// Entrée standard (mcp-log-agent pipe), avec un nom de source optionnel
+    Stdin(Option<String>),
+}
+
+impl Input {
+    /// Noms et "commandes" affichés dans la TUI
+    pub fn labels(&self) -> Vec<(String, Vec<String>)> {
+        match self {
+            Input::Files(tail) => tail.files().iter().map(|file| TailSet::label(file)).collect(),
+            Input::Stdin(name) => {
+                let name = name.clone().unwrap_or_else(|| "stdin".to_string());
+                vec![(name, vec!["stdin".to_string()])]
+            }
+        }
+    }
+
+    /// Signale les sources apparues après le lancement (fichiers trouvés par les globs)
+    pub fn with_new_sources(self, tx: mpsc::UnboundedSender<(String, Vec<String>)>) -> Self {
+        match self {
+            Input::Files(tail) => Input::Files(tail.with_new_files(tx)),
+            Input::Stdin(name) => Input::Stdin(name),
+        }
+    }
+
+    /// Lance la lecture; la tâche se termine à la fin de stdin (jamais pour des fichiers)
+    pub fn spawn(
+        self,
+        project: String,
+        config: Config,
+        pipeline: Pipeline,
+        echo: bool,
+        tx: mpsc::Sender<LogMessage>,
+    ) -> JoinHandle<()> {
+        match self {
+            Input::Files(tail) => tail.spawn(project, config, pipeline, echo, tx),
+            Input::Stdin(name) => {
+                let ctx = CaptureContext {
+                    project,
+                    pid: 0,
+                    config,
+                    pipeline,
+                    echo,
+                    process: name,
+                    prefix: None,
+                    ready: None,
+                    lifecycle: None,
+                };
+                tokio::spawn(read_stdin(ctx, tx))
+            }
+        }
+    }
+}
+
+/// Capture les lignes de stdin jusqu'à EOF
+///
+/// La lecture bloquante se fait dans un thread à part: quitter (Ctrl+C, 'q')
+/// n'attend pas que la commande en amont écrive ou ferme le pipe.
+async fn read_stdin(ctx: CaptureContext, tx: mpsc::Sender<LogMessage>) {
+    let (reader, mut writer) = tokio::io::duplex(CHUNK_SIZE * 4);
+    let (chunk_tx, mut chunk_rx) = mpsc::channel::<Vec<u8>>(16);
+
+    std::thread::spawn(move || {
+        let mut stdin = std::io::stdin().lock();
+        let mut buf = vec![0u8; CHUNK_SIZE];
+        loop {
+            match stdin.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    if chunk_tx.blocking_send(buf[..n].to_vec()).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    let forward = async move {
+        while let Some(chunk) = chunk_rx.recv().await {
+            if writer.write_all(&chunk).await.is_err() {
+                break;
+            }
+        }
+        // Fermer le pipe: capture_stream voit EOF après la dernière ligne
+        drop(writer);
+    };
+    tokio::join!(forward, capture_stream(BufReader::new(reader), LogSource::Stdout, ctx, tx));
+}
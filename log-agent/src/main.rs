@@ -1,16 +1,30 @@
 mod capture;
 mod cli;
 mod config;
+mod deps;
+mod input;
+mod metrics;
+mod pipeline;
+mod process;
+mod restart;
 mod socket;
 mod supervisor;
+mod tail;
 mod tui;
 mod types;
+mod watcher;
 
 use capture::ProcessCapture;
 use clap::Parser;
 use cli::{Cli, Commands, ConfigAction};
 use config::Config;
 use owo_colors::OwoColorize;
+use pipeline::Pipeline;
+use capture::ready::ReadyProbe;
+use deps::Lifecycles;
+use input::Input;
+use capture::stop::StopPolicy;
+use process::ProcessSpec;
 use socket::SocketClient;
 use tokio::sync::mpsc;
 
@@ -19,8 +33,22 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Run { project, verbose, watch, cmd, command } => {
-            run_command(project, verbose, watch, cmd, command).await?;
+        Commands::Run { project, verbose, watch, pty, cmd, group, command } => {
+            let code = run_command(project, verbose, watch, pty, cmd, group, command).await?;
+            if code != 0 {
+                std::process::exit(code);
+            }
+        }
+        Commands::Tail { paths, project, verbose, watch, from_start, lines, tag, encoding } => {
+            let start = match (from_start, lines) {
+                (true, _) => tail::StartAt::Beginning,
+                (_, Some(n)) => tail::StartAt::LastLines(n),
+                _ => tail::StartAt::End,
+            };
+            tail_command(project, verbose, watch, encoding, paths, start, tag).await?;
+        }
+        Commands::Pipe { project, verbose, watch, name, encoding } => {
+            pipe_command(project, verbose, watch, encoding, name).await?;
         }
         Commands::Test { message } => {
             test_connection(message).await?;
@@ -37,61 +65,25 @@ async fn run_command(
     project_override: Option<String>, 
     verbose_override: bool, 
     watch: bool, 
-    cmd_name: Option<String>,
+    pty: bool,
+    cmd_names: Vec<String>,
+    group: Option<String>,
     command_args: Vec<String>
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<i32, Box<dyn std::error::Error>> {
     // Charger la configuration
-    let mut config = config::load_config().unwrap_or_else(|e| {
-        eprintln!("{}", format!("Warning: Failed to load config: {}", e).yellow());
-        eprintln!("  Using default configuration");
-        Config::default()
-    });
-    
-    // Déterminer la commande à exécuter et le mode watch
-    // Priorité: CLI args > --cmd > default_command
-    let (command, cmd_watch_override) = if !command_args.is_empty() {
-        // 1. Commande fournie en CLI arguments (pas de watch override spécifique)
-        (command_args, None)
-    } else if let Some(name) = cmd_name {
-        // 2. Commande prédéfinie via --cmd
-        if let Some(cmd_config) = config.agent.commands.get(&name) {
-            // Extraire la commande et le watch override depuis CommandConfig
-            match cmd_config {
-                config::CommandConfig::Simple(cmd) => (cmd.clone(), None),
-                config::CommandConfig::Detailed { command: cmd, watch: cmd_watch } => {
-                    (cmd.clone(), Some(*cmd_watch))
-                }
-            }
-        } else {
-            eprintln!("{}", format!("Error: Predefined command '{}' not found in config", name).red());
-            eprintln!();
-            eprintln!("Available commands in config:");
-            if config.agent.commands.is_empty() {
-                eprintln!("  (none defined)");
-                eprintln!();
-                eprintln!("To define commands, edit your config file:");
-                eprintln!("  mcp-log-agent config init --local");
-                eprintln!("  # Then add commands in [agent.commands] section:");
-                eprintln!("  # [agent.commands]");
-                eprintln!("  # dev = [\"npm\", \"run\", \"dev\"]");
-                eprintln!("  # test = {{ command = [\"npm\", \"test\"], watch = true }}");
-            } else {
-                for (name, cmd_config) in &config.agent.commands {
-                    match cmd_config {
-                        config::CommandConfig::Simple(cmd) => {
-                            eprintln!("  {} = {:?}", name.bright_cyan(), cmd);
-                        }
-                        config::CommandConfig::Detailed { command: cmd, watch: cmd_watch } => {
-                            eprintln!("  {} = {:?} (watch: {})", name.bright_cyan(), cmd, cmd_watch);
-                        }
-                    }
-                }
-            }
-            return Err(format!("Predefined command '{}' not found", name).into());
-        }
+    let config = load_config_with_overrides(project_override, verbose_override);
+
+    // Déterminer les commandes à exécuter
+    // Priorité: CLI args > --group / --cmd > default_command
+    let mut processes = if !command_args.is_empty() {
+        // 1. Commande fournie en CLI arguments (pas d'override spécifique)
+        vec![ProcessSpec::inline(command_args, &config)]
+    } else if group.is_some() || !cmd_names.is_empty() {
+        // 2. Commandes prédéfinies via --group et/ou --cmd
+        resolve_predefined(&config, group, cmd_names)?
     } else if let Some(default_cmd) = &config.agent.default_command {
-        // 3. Commande par défaut de la config (pas de watch override spécifique)
-        (default_cmd.clone(), None)
+        // 3. Commande par défaut de la config (pas d'override spécifique)
+        vec![ProcessSpec::inline(default_cmd.clone(), &config)]
     } else {
         // Aucune commande spécifiée
         eprintln!("{}", "Error: No command provided".red());
@@ -112,29 +104,209 @@ async fn run_command(
         eprintln!("     mcp-log-agent run");
         return Err("No command specified".into());
     };
-    
-    // Appliquer les overrides CLI
-    if let Some(proj) = project_override {
-        config.agent.default_project = proj;
+
+    // Déterminer le mode watch (priorité: CLI flag > commande spécifique > config globale)
+    let use_watch = watch
+        || processes
+            .iter()
+            .any(|p| p.watch.unwrap_or(config.agent.watch));
+
+    // Le flag --pty s'applique à tous les processus
+    if pty {
+        for process in &mut processes {
+            process.config.agent.pty = true;
+        }
     }
-    if verbose_override {
-        config.agent.verbose = true;
+
+    // Vérifier les signaux d'arrêt et les ready_when avant de lancer quoi que ce soit
+    for process in &processes {
+        StopPolicy::from_config(&process.config.agent)
+            .map_err(|e| format!("{} (stop_signal of '{}')", e, process.label()))?;
+        if let Some(ready_when) = &process.ready_when {
+            ReadyProbe::from_config(ready_when)
+                .map_err(|e| format!("{} (ready_when of '{}')", e, process.label()))?;
+        }
     }
     
-    // Déterminer le mode watch (priorité: CLI flag > commande spécifique > config globale)
-    let use_watch = watch || cmd_watch_override.unwrap_or(config.agent.watch);
-    
     let project = config.agent.default_project.clone();
+
+    // Compiler les filtres une seule fois (erreur si un pattern est invalide)
+    let pipeline = Pipeline::new(&config)
+        .map_err(|e| -> Box<dyn std::error::Error> { e.to_string().into() })?;
     
     // Mode TUI avec supervision (--watch ou config.agent.watch = true)
     if use_watch {
-        return tui::run_tui(project, command, config)
+        return tui::run_tui(project, processes, None, config, pipeline)
             .await
+            .map(|_| 0)
             .map_err(|e| -> Box<dyn std::error::Error> { Box::new(std::io::Error::other(e.to_string())) });
     }
     
     // Mode classique (one-shot)
-    // Afficher les informations
+    print_config_source(&project);
+
+    // Créer un channel pour les logs
+    let (tx, rx) = mpsc::channel(config.performance.buffer_size);
+
+    // Démarrer le worker socket dans une tâche séparée
+    let socket_client = SocketClient::from_config(&config);
+    let state_task = tokio::spawn(print_connection_states(
+        socket_client.subscribe(),
+        config.agent.verbose,
+    ));
+    let socket_task = tokio::spawn(async move {
+        let _ = socket_client.start_worker(rx).await;
+    });
+
+    // Plusieurs processus: chaque ligne est préfixée par le nom du processus
+    let multi = processes.len() > 1;
+    let width = processes.iter().map(|p| p.label().len()).max().unwrap_or(0);
+
+    // Créer et lancer la capture de chaque processus (chacun attend ses depends_on)
+    let lifecycles = Lifecycles::new(&processes);
+    let mut capture_handles = Vec::with_capacity(processes.len());
+    for (index, spec) in processes.into_iter().enumerate() {
+        let mut capture = ProcessCapture::new(project.clone(), spec, pipeline.clone())
+            .with_lifecycles(lifecycles.clone());
+        if multi {
+            let prefix = process::prefix(&capture.label(), index, width, config.output.colors);
+            capture = capture.with_prefix(prefix);
+        }
+        capture_handles.push(capture.spawn_with_tx(tx.clone()));
+    }
+    drop(tx);
+
+    // Attendre la fin de tous les processus
+    // Code de sortie: celui du premier processus en échec (127 s'il n'a pas pu démarrer)
+    let mut exit_code = 0;
+    for handle in capture_handles {
+        let code = match handle.await {
+            Ok(Ok(status)) => capture::exit_code(&status),
+            Ok(Err(e)) => {
+                eprintln!("{}", format!("✗ {}", e).red());
+                127
+            }
+            Err(_) => 1,
+        };
+        if exit_code == 0 {
+            exit_code = code;
+        }
+    }
+
+    // Attendre que le worker socket se termine
+    let _ = socket_task.await;
+    let _ = state_task.await;
+
+    print_run_summary(&config, &pipeline);
+
+    Ok(exit_code)
+}
+
+/// Suit des fichiers de logs existants (rotation comprise) jusqu'à Ctrl+C
+async fn tail_command(
+    project_override: Option<String>,
+    verbose_override: bool,
+    watch: bool,
+    encoding: Option<config::Encoding>,
+    paths: Vec<String>,
+    start: tail::StartAt,
+    tag: tail::TailTag,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut config = load_config_with_overrides(project_override, verbose_override);
+    if let Some(encoding) = encoding {
+        config.agent.encoding = encoding;
+    }
+    let tail = tail::TailSet::new(paths, start, tag)?;
+    read_input(config, watch, Input::Files(tail)).await
+}
+
+/// Capture les lignes lues sur stdin jusqu'à EOF (ou Ctrl+C)
+async fn pipe_command(
+    project_override: Option<String>,
+    verbose_override: bool,
+    watch: bool,
+    encoding: Option<config::Encoding>,
+    name: Option<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut config = load_config_with_overrides(project_override, verbose_override);
+    if let Some(encoding) = encoding {
+        config.agent.encoding = encoding;
+    }
+    read_input(config, watch, Input::Stdin(name)).await
+}
+
+/// Capture des logs qui ne viennent pas d'un processus lancé par l'agent (tail, pipe)
+async fn read_input(config: Config, watch: bool, input: Input) -> Result<(), Box<dyn std::error::Error>> {
+    let project = config.agent.default_project.clone();
+    let pipeline = Pipeline::new(&config)
+        .map_err(|e| -> Box<dyn std::error::Error> { e.to_string().into() })?;
+
+    // Mode TUI (--watch ou config.agent.watch = true)
+    if watch || config.agent.watch {
+        return tui::run_tui(project, Vec::new(), Some(input), config, pipeline)
+            .await
+            .map_err(|e| -> Box<dyn std::error::Error> { Box::new(std::io::Error::other(e.to_string())) });
+    }
+
+    print_config_source(&project);
+    match &input {
+        Input::Files(tail) => {
+            for file in tail.files() {
+                let missing = if file.exists() { "" } else { " (waiting for file)" };
+                eprintln!("{}", format!("✓ Following {}{}", file.display(), missing).bright_black());
+            }
+        }
+        Input::Stdin(_) => eprintln!("{}", "✓ Reading from stdin".bright_black()),
+    }
+
+    let (tx, rx) = mpsc::channel(config.performance.buffer_size);
+    let socket_client = SocketClient::from_config(&config);
+    let state_task = tokio::spawn(print_connection_states(
+        socket_client.subscribe(),
+        config.agent.verbose,
+    ));
+    let socket_task = tokio::spawn(async move {
+        let _ = socket_client.start_worker(rx).await;
+    });
+
+    // Jusqu'à la fin de stdin (jamais pour des fichiers) ou Ctrl+C
+    let mut input_task = input.spawn(project, config.clone(), pipeline.clone(), true, tx);
+    tokio::select! {
+        _ = &mut input_task => {}
+        _ = tokio::signal::ctrl_c() => {
+            input_task.abort();
+            let _ = input_task.await;
+        }
+    }
+
+    // Attendre que le worker socket envoie les derniers logs
+    let _ = socket_task.await;
+    let _ = state_task.await;
+
+    print_run_summary(&config, &pipeline);
+    Ok(())
+}
+
+/// Charge la configuration et applique --project et --verbose
+fn load_config_with_overrides(project_override: Option<String>, verbose_override: bool) -> Config {
+    let mut config = config::load_config().unwrap_or_else(|e| {
+        eprintln!("{}", format!("Warning: Failed to load config: {}", e).yellow());
+        eprintln!("  Using default configuration");
+        Config::default()
+    });
+    
+    // Appliquer les overrides CLI
+    if let Some(proj) = project_override {
+        config.agent.default_project = proj;
+    }
+    if verbose_override {
+        config.agent.verbose = true;
+    }
+    config
+}
+
+/// Affiche la configuration utilisée et le projet (mode one-shot)
+fn print_config_source(project: &str) {
     if config::has_local_config() {
         eprintln!("{}", "Using local configuration".bright_green());
         eprintln!("  Location: ./.mcp-log-agent.toml");
@@ -147,35 +319,142 @@ async fn run_command(
     
     eprintln!("{}", format!("Project: {}", project).bright_cyan());
     eprintln!();
+}
 
-    // Créer un channel pour les logs
-    let (tx, rx) = mpsc::channel(config.performance.buffer_size);
+/// Résumé de fin: logs en attente dans le spool et lignes filtrées
+fn print_run_summary(config: &Config, pipeline: &Pipeline) {
+    // Logs restés dans le spool (serveur MCP injoignable)
+    if let Some(spool) = socket::Spool::from_config(config) {
+        let pending = spool.pending_count();
+        if pending > 0 {
+            eprintln!(
+                "{}",
+                format!(
+                    "ℹ {} log(s) spooled to {} (replayed when the MCP server is reachable)",
+                    pending,
+                    spool.path().display()
+                )
+                .bright_black()
+            );
+        }
+        let rejected = spool.rejected_count();
+        if rejected > 0 {
+            eprintln!(
+                "{}",
+                format!(
+                    "⚠ {} unreadable spooled line(s) set aside in {}",
+                    rejected,
+                    spool.rejected_path().display()
+                )
+                .yellow()
+            );
+        }
+    }
 
-    // Démarrer le worker socket dans une tâche séparée
-    let socket_path = config.agent.socket_path.clone();
-    let socket_client = SocketClient::new(Some(socket_path));
-    let socket_task = tokio::spawn(async move {
-        let _ = socket_client.start_worker(rx).await;
-    });
+    // Résumé des lignes écartées par [filters]
+    let dropped = pipeline.dropped_count();
+    if dropped > 0 {
+        eprintln!("{}", format!("ℹ {} line(s) dropped by [filters]", dropped).bright_black());
+    }
+}
 
-    // Créer et lancer la capture du processus
-    let capture = ProcessCapture::new(project, command, config.clone());
+/// Résout --group et --cmd en commandes de [agent.commands] (sans doublons, dans l'ordre des dépendances)
+fn resolve_predefined(
+    config: &Config,
+    group: Option<String>,
+    cmd_names: Vec<String>,
+) -> Result<Vec<ProcessSpec>, Box<dyn std::error::Error>> {
+    let mut names = Vec::new();
 
-    // Lancer la capture (bloquant jusqu'à ce que le processus se termine)
-    let capture_handle = capture.spawn_with_tx(tx);
+    if let Some(group) = group {
+        let Some(members) = config.group.get(&group) else {
+            eprintln!("{}", format!("Error: Group '{}' not found in config", group).red());
+            eprintln!();
+            eprintln!("Available groups in config:");
+            if config.group.is_empty() {
+                eprintln!("  (none defined)");
+                eprintln!();
+                eprintln!("To define groups, add a [group] section to your config file:");
+                eprintln!("  # [group]");
+                eprintln!("  # fullstack = [\"api\", \"web\", \"worker\"]");
+            } else {
+                for (name, members) in &config.group {
+                    eprintln!("  {} = {:?}", name.bright_cyan(), members);
+                }
+            }
+            return Err(format!("Group '{}' not found", group).into());
+        };
+        names.extend(members.iter().cloned());
+    }
+    names.extend(cmd_names);
 
-    // Attendre la fin du processus
-    let _ = capture_handle.await;
+    if let Some(name) = names.iter().find(|n| !config.agent.commands.contains_key(*n)) {
+        print_unknown_command(config, name);
+        return Err(format!("Predefined command '{}' not found", name).into());
+    }
 
-    // Attendre que le worker socket se termine
-    let _ = socket_task.await;
+    // Dépendances d'abord (ajoutées si elles n'ont pas été demandées)
+    let order = deps::start_order(&config.agent.commands, &names)?;
+    let processes = order
+        .iter()
+        .map(|name| ProcessSpec::predefined(name, &config.agent.commands[name], config))
+        .collect();
 
-    Ok(())
+    Ok(processes)
+}
+
+/// Explique qu'une commande prédéfinie est introuvable et liste celles disponibles
+fn print_unknown_command(config: &Config, name: &str) {
+    eprintln!("{}", format!("Error: Predefined command '{}' not found in config", name).red());
+    eprintln!();
+    eprintln!("Available commands in config:");
+    if config.agent.commands.is_empty() {
+        eprintln!("  (none defined)");
+        eprintln!();
+        eprintln!("To define commands, edit your config file:");
+        eprintln!("  mcp-log-agent config init --local");
+        eprintln!("  # Then add commands in [agent.commands] section:");
+        eprintln!("  # [agent.commands]");
+        eprintln!("  # dev = [\"npm\", \"run\", \"dev\"]");
+        eprintln!("  # test = {{ command = [\"npm\", \"test\"], watch = true }}");
+    } else {
+        for (name, cmd_config) in &config.agent.commands {
+            match cmd_config.watch() {
+                Some(watch) => {
+                    eprintln!("  {} = {:?} (watch: {})", name.bright_cyan(), cmd_config.command(), watch);
+                }
+                None => eprintln!("  {} = {:?}", name.bright_cyan(), cmd_config.command()),
+            }
+        }
+    }
+}
+
+/// Affiche les changements d'état de la connexion au serveur MCP (mode one-shot)
+/// Les tentatives intermédiaires ne sont affichées qu'en mode verbose
+async fn print_connection_states(
+    mut state_rx: tokio::sync::watch::Receiver<socket::ConnectionState>,
+    verbose: bool,
+) {
+    use socket::ConnectionState;
+
+    while state_rx.changed().await.is_ok() {
+        let state = state_rx.borrow_and_update().clone();
+        match state {
+            ConnectionState::Connected => eprintln!("{}", "✓ Connected to MCP server".green()),
+            ConnectionState::Reconnecting { .. } if verbose => {
+                eprintln!("{}", format!("⚠ MCP server {}", state).yellow())
+            }
+            ConnectionState::Disconnected(_) => {
+                eprintln!("{}", format!("✗ MCP server {}", state).bright_black())
+            }
+            _ => {}
+        }
+    }
 }
 
 async fn test_connection(message: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
     let config = config::load_config().unwrap_or_default();
-    let socket_client = SocketClient::new(Some(config.agent.socket_path.clone()));
+    let socket_client = SocketClient::from_config(&config);
     socket_client.test_connection(message).await
 }
 
@@ -250,11 +529,14 @@ fn handle_config_command(action: ConfigAction) -> Result<(), Box<dyn std::error:
             }
         }
         ConfigAction::Show { json } => {
-            let config = config::load_config()?;
+            let mut config = config::load_config()?;
+            let resolved = config.clone();
+            config::env::mask_secrets(&mut config);
             if json {
                 println!("{}", serde_json::to_string_pretty(&config)?);
             } else {
                 println!("{}", toml::to_string_pretty(&config)?);
+                print_resolved_environments(&resolved);
             }
         }
         ConfigAction::Get { key } => {
@@ -309,6 +591,18 @@ fn handle_config_command(action: ConfigAction) -> Result<(), Box<dyn std::error:
             println!("  verbose               - Verbose output");
             println!("  connection_timeout    - Connection timeout in seconds");
             println!("  retry_attempts        - Number of retry attempts");
+            println!("  stop_signal           - Signal sent to stop the process group");
+            println!("  stop_timeout          - Grace period in seconds before SIGKILL");
+            println!("  restart               - Automatic restart (never, on-failure, always)");
+            println!("  max_restarts          - Restarts allowed within restart_window");
+            println!("  restart_window        - Window in seconds for counting restarts");
+            println!("  restart_backoff_ms    - Delay before the first restart in ms");
+            println!("  watch_debounce_ms     - Quiet period before restarting on file changes");
+            println!("  metrics_interval_ms   - Interval between CPU/RSS/fds samples (0 = off)");
+            println!("  encoding              - Output encoding (utf-8, latin1, utf-16le, utf-16be)");
+            println!("  partial_line_timeout_ms - Idle time before sending a line without newline (0 = wait)");
+            println!("  max_line_bytes        - Maximum line length, longer lines are cut (0 = no limit)");
+            println!("  long_lines            - Send cut lines truncated or split in chunks (truncate, split)");
             println!();
             println!("{}", "[output]".bright_cyan());
             println!("  colors                - Enable colored output");
@@ -316,6 +610,31 @@ fn handle_config_command(action: ConfigAction) -> Result<(), Box<dyn std::error:
             println!("  show_timestamps       - Show timestamps in output");
             println!("  show_pid              - Show process ID in output");
             println!();
+            println!("{}", "[levels]".bright_cyan());
+            println!("  presets               - Recognize common formats ([WARN], level=error, glog...)");
+            println!("  stdout                - Level of unmatched stdout lines (debug, info, warn, error)");
+            println!("  stderr                - Level of unmatched stderr lines (debug, info, warn, error)");
+            println!("  rules                 - Edit [[levels.rules]] in the config file");
+            println!();
+            println!("{}", "[parsing]".bright_cyan());
+            println!("  json                  - Parse JSON lines (message, level, timestamp, fields)");
+            println!("  logfmt                - Parse key=value lines (time=... level=info msg=\"...\")");
+            println!("  level_keys            - Keys holding the level (\"a.b\" = nested key)");
+            println!("  message_keys          - Keys holding the message");
+            println!("  timestamp_keys        - Keys holding the timestamp");
+            println!();
+            println!("{}", "[multiline]".bright_cyan());
+            println!("  enabled               - Group stack traces and panics into one event");
+            println!("  presets               - Recognize indentation, \"at ...\", Traceback, panics, \"Caused by:\"");
+            println!("  flush_ms              - Time without continuation before sending the event");
+            println!("  max_lines             - Maximum lines in one event");
+            println!("  rules                 - Edit [[multiline.rules]] in the config file");
+            println!();
+            println!("{}", "[ansi]".bright_cyan());
+            println!("  terminal              - Color codes in echoed messages (clean, styled)");
+            println!("  tui                   - Color codes in TUI messages (clean, styled)");
+            println!("  socket                - Color codes in messages sent to the MCP server (clean, styled)");
+            println!();
             println!("{}", "[colors]".bright_cyan());
             println!("  See 'mcp-log-agent config colors list' for color configuration");
         }
@@ -345,6 +664,49 @@ fn handle_config_command(action: ConfigAction) -> Result<(), Box<dyn std::error:
                     }
                 }
             }
+
+            // Vérifier que les patterns de [filters] et [levels] compilent
+            if let Ok(config) = config::load_config()
+                && let Err(e) = Pipeline::new(&config) {
+                    println!("{}", format!("  Filters:       ✗ Error: {}", e).red());
+                    has_errors = true;
+                }
+
+            // Vérifier les signaux d'arrêt (global et par commande)
+            if let Ok(config) = config::load_config() {
+                let mut signals = vec![("agent".to_string(), config.agent.stop_signal.clone())];
+                for (name, cmd_config) in &config.agent.commands {
+                    if let Some(signal) = cmd_config.options().and_then(|o| o.stop_signal.clone()) {
+                        signals.push((format!("agent.commands.{}", name), signal));
+                    }
+                }
+                for (section, signal) in signals {
+                    if let Err(e) = capture::stop::parse_signal(&signal) {
+                        println!("{}", format!("  Stop signal:   ✗ Error in [{}]: {}", section, e).red());
+                        has_errors = true;
+                    }
+                }
+
+                // Vérifier les options des commandes (globs ignorés, ready_when)
+                for (name, cmd_config) in &config.agent.commands {
+                    if let Some(options) = cmd_config.options()
+                        && let Err(e) = watcher::ignore_set(&options.ignore_globs) {
+                            println!("{}", format!("  Watch paths:   ✗ Error in [agent.commands.{}]: {}", name, e).red());
+                            has_errors = true;
+                        }
+                    if let Some(ready_when) = cmd_config.options().and_then(|o| o.ready_when.as_ref())
+                        && let Err(e) = ReadyProbe::from_config(ready_when) {
+                            println!("{}", format!("  Ready when:    ✗ Error in [agent.commands.{}]: {}", name, e).red());
+                            has_errors = true;
+                        }
+                }
+
+                // Vérifier les depends_on (commandes connues, pas de cycle)
+                if let Err(e) = deps::check_dependencies(&config.agent.commands) {
+                    println!("{}", format!("  Dependencies:  ✗ Error: {}", e).red());
+                    has_errors = true;
+                }
+            }
             
             if !has_errors {
                 println!();
@@ -391,6 +753,9 @@ fn handle_config_command(action: ConfigAction) -> Result<(), Box<dyn std::error:
                 println!("{}", "Please specify --global or --local".yellow());
             }
         }
+        ConfigAction::Import { source, global, force, yes, dry_run } => {
+            handle_import(&source, global, force, yes, dry_run)?;
+        }
         ConfigAction::Theme { action } => {
             handle_theme_action(action)?;
         }
@@ -399,6 +764,164 @@ fn handle_config_command(action: ConfigAction) -> Result<(), Box<dyn std::error:
     Ok(())
 }
 
+/// Affiche l'environnement résolu de chaque commande (en commentaires TOML, secrets masqués)
+fn print_resolved_environments(config: &Config) {
+    let mut names: Vec<_> = config
+        .agent
+        .commands
+        .iter()
+        .filter_map(|(name, cmd_config)| Some((name, &cmd_config.options()?.env)))
+        .filter(|(_, env)| !env.is_empty())
+        .collect();
+    if names.is_empty() {
+        return;
+    }
+    names.sort_by_key(|(name, _)| name.as_str());
+
+    println!("{}", "# Resolved command environments".bright_black());
+    for (name, env) in names {
+        println!("{}", format!("# [agent.commands.{}]", name).bright_black());
+        if let Some(cwd) = env.cwd_path() {
+            println!("{}", format!("#   cwd: {}", cwd.display()).bright_black());
+        }
+        if let Some(path) = env.env_file_path() {
+            println!("{}", format!("#   env_file: {}", path.display()).bright_black());
+        }
+        let inherited = if env.clear_env { "cleared" } else { "inherited" };
+        println!("{}", format!("#   agent environment: {}", inherited).bright_black());
+
+        match env.resolve() {
+            Ok(vars) => {
+                for (key, value) in vars {
+                    let value = config::env::display_value(&key, &value);
+                    println!("{}", format!("#   {} = {}", key, value).bright_black());
+                }
+            }
+            Err(e) => println!("{}", format!("#   ✗ {}", e).red()),
+        }
+    }
+}
+
+/// Importe les commandes déclarées par le projet dans [agent.commands]
+fn handle_import(
+    sources: &[config::import::ImportSource],
+    global: bool,
+    force: bool,
+    yes: bool,
+    dry_run: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use config::import::{self, ImportChange};
+
+    let path = if global {
+        config::get_global_config_path().ok_or("Could not determine global config path")?
+    } else {
+        config::get_local_config_path()
+    };
+
+    let imported = import::detect_commands(&std::env::current_dir()?, sources)?;
+    if imported.is_empty() {
+        println!("{}", "No commands found to import".yellow());
+        println!("  Looked for: Procfile, package.json (scripts), Makefile (.PHONY), .cargo/config.toml ([alias])");
+        return Ok(());
+    }
+
+    let target = if path.exists() {
+        config::load_config_from_file(&path)?
+    } else {
+        Config::default()
+    };
+
+    // Aperçu des changements sur [agent.commands]
+    let changes = import::plan_import(&target.agent.commands, imported, force);
+    println!("Changes to [agent.commands] in {}:", path.display());
+    println!();
+
+    let mut pending = 0;
+    for change in &changes {
+        match change {
+            ImportChange::Add(command) => {
+                pending += 1;
+                println!(
+                    "{}",
+                    format!("+ {} = {:?}", command.name, command.config.command()).green()
+                );
+                println!("{}", format!("    from {}", command.source).bright_black());
+            }
+            ImportChange::Replace { previous, command } => {
+                pending += 1;
+                println!("{}", format!("- {} = {:?}", command.name, previous.command()).red());
+                println!(
+                    "{}",
+                    format!("+ {} = {:?}", command.name, command.config.command()).green()
+                );
+                println!("{}", format!("    from {}", command.source).bright_black());
+            }
+            ImportChange::Keep { existing, command } => {
+                let note = if existing.command() == command.config.command() {
+                    "already defined"
+                } else {
+                    "already defined, use --force to replace"
+                };
+                println!(
+                    "{}",
+                    format!("  {} = {:?} ({})", command.name, existing.command(), note).bright_black()
+                );
+            }
+        }
+    }
+    println!();
+
+    if pending == 0 {
+        println!("{}", "✓ Nothing to import, all commands are already defined".green());
+        return Ok(());
+    }
+
+    if dry_run {
+        println!("{}", format!("ℹ Dry run: {} command(s) would be written", pending).bright_black());
+        return Ok(());
+    }
+
+    // save_commands réécrit le fichier: les commentaires existants sont perdus
+    if path.exists() && std::fs::read_to_string(&path)?.lines().any(|l| l.trim_start().starts_with('#')) {
+        println!("{}", "⚠ Comments in this file will not be preserved".yellow());
+    }
+
+    if !yes && !confirm(&format!("Write {} command(s) to {}?", pending, path.display())) {
+        println!("{}", "✗ Import cancelled".yellow());
+        return Ok(());
+    }
+
+    let commands = changes
+        .into_iter()
+        .filter_map(|change| match change {
+            ImportChange::Add(command) | ImportChange::Replace { command, .. } => Some((command.name, command.config)),
+            ImportChange::Keep { .. } => None,
+        })
+        .collect();
+    config::save_commands(&path, commands)?;
+    println!("{}", format!("✓ Imported {} command(s)", pending).green());
+    println!("  File: {}", path.display());
+    println!("  Run one with: mcp-log-agent run --cmd <name>");
+
+    Ok(())
+}
+
+/// Demande une confirmation (non par défaut)
+fn confirm(question: &str) -> bool {
+    use std::io::Write;
+
+    print!("{} [y/N]: ", question);
+    let _ = std::io::stdout().flush();
+
+    let mut input = String::new();
+    if std::io::stdin().read_line(&mut input).is_err() {
+        return false;
+    }
+
+    let input = input.trim().to_lowercase();
+    input == "y" || input == "yes"
+}
+
 fn handle_theme_action(action: cli::ThemeAction) -> Result<(), Box<dyn std::error::Error>> {
     use cli::ThemeAction;
     use config::themes::ThemeManager;
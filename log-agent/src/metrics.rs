@@ -0,0 +1,180 @@
+use crate::types::{LogMessage, ResourceUsage};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// Ligne de /proc/<pid>/stat utile au suivi des ressources
+struct ProcStat {
+    ppid: u32,
+    pgrp: u32,
+    /// Temps CPU utilisateur + système, en ticks d'horloge
+    cpu_ticks: u64,
+    threads: u32,
+    /// Mémoire résidente en pages
+    rss_pages: u64,
+}
+
+/// Échantillonne les ressources d'un arbre de processus via /proc (Linux)
+///
+/// L'arbre comprend le groupe de processus lancé par l'agent et tous les
+/// descendants de son chef, même s'ils ont changé de groupe.
+pub struct TreeSampler {
+    pgid: u32,
+    /// Dernier total de ticks CPU et instant de la mesure (calcul du CPU%)
+    last: Option<(Instant, u64)>,
+    ticks_per_sec: f64,
+    page_size: u64,
+}
+
+impl TreeSampler {
+    pub fn new(pgid: u32) -> Self {
+        // SAFETY: sysconf n'a pas d'effet de bord
+        let (ticks, page) = unsafe { (libc::sysconf(libc::_SC_CLK_TCK), libc::sysconf(libc::_SC_PAGESIZE)) };
+        Self {
+            pgid,
+            last: None,
+            ticks_per_sec: if ticks > 0 { ticks as f64 } else { 100.0 },
+            page_size: if page > 0 { page as u64 } else { 4096 },
+        }
+    }
+
+    /// Groupe de processus mesuré
+    pub fn pgid(&self) -> u32 {
+        self.pgid
+    }
+
+    /// Mesure l'arbre (None s'il n'y a plus aucun processus ou sans /proc)
+    ///
+    /// Le CPU% est calculé depuis la mesure précédente (0 à la première) et
+    /// peut dépasser 100% sur plusieurs cœurs.
+    ///
+    /// Lit /proc de façon bloquante: à appeler hors de la boucle async.
+    pub fn sample(&mut self) -> Option<ResourceUsage> {
+        self.sample_from(&read_all_stats())
+    }
+
+    fn sample_from(&mut self, stats: &HashMap<u32, ProcStat>) -> Option<ResourceUsage> {
+        let members = tree_members(stats, self.pgid);
+        if members.is_empty() {
+            return None;
+        }
+
+        let mut usage = ResourceUsage { processes: members.len() as u32, ..Default::default() };
+        let mut cpu_ticks = 0;
+        for pid in &members {
+            let stat = &stats[pid];
+            cpu_ticks += stat.cpu_ticks;
+            usage.threads += stat.threads;
+            usage.rss_bytes += stat.rss_pages * self.page_size;
+            usage.fds += count_fds(*pid);
+        }
+
+        let now = Instant::now();
+        if let Some((at, ticks)) = self.last {
+            let elapsed = now.duration_since(at).as_secs_f64();
+            if elapsed > 0.0 {
+                let used = cpu_ticks.saturating_sub(ticks) as f64 / self.ticks_per_sec;
+                usage.cpu_percent = (used / elapsed * 100.0) as f32;
+            }
+        }
+        self.last = Some((now, cpu_ticks));
+
+        Some(usage)
+    }
+}
+
+/// Mesure plusieurs arbres avec un seul parcours de /proc (bloquant)
+pub fn sample_all(samplers: &mut [TreeSampler]) -> Vec<Option<ResourceUsage>> {
+    let stats = read_all_stats();
+    samplers.iter_mut().map(|sampler| sampler.sample_from(&stats)).collect()
+}
+
+/// Processus du groupe `pgid` et descendants de son chef
+fn tree_members(stats: &HashMap<u32, ProcStat>, pgid: u32) -> Vec<u32> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for (pid, stat) in stats {
+        children.entry(stat.ppid).or_default().push(*pid);
+    }
+
+    let mut members: HashSet<u32> = stats
+        .iter()
+        .filter(|(pid, stat)| stat.pgrp == pgid || **pid == pgid)
+        .map(|(pid, _)| *pid)
+        .collect();
+    let mut queue: Vec<u32> = members.iter().copied().collect();
+    while let Some(pid) = queue.pop() {
+        for child in children.get(&pid).into_iter().flatten() {
+            if members.insert(*child) {
+                queue.push(*child);
+            }
+        }
+    }
+
+    members.into_iter().collect()
+}
+
+/// Lit /proc/<pid>/stat pour tous les processus visibles
+fn read_all_stats() -> HashMap<u32, ProcStat> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return HashMap::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+        .filter_map(|pid| {
+            let content = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+            Some((pid, parse_stat(&content)?))
+        })
+        .collect()
+}
+
+/// Parse /proc/<pid>/stat (le nom de la commande, entre parenthèses, peut contenir des espaces)
+fn parse_stat(content: &str) -> Option<ProcStat> {
+    let rest = &content[content.rfind(')')? + 1..];
+    // Champs à partir de l'état (champ 3 de proc(5))
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    let field = |n: usize| fields.get(n - 3)?.parse::<u64>().ok();
+
+    Some(ProcStat {
+        ppid: field(4)? as u32,
+        pgrp: field(5)? as u32,
+        cpu_ticks: field(14)? + field(15)?,
+        threads: field(20)? as u32,
+        rss_pages: field(24)?,
+    })
+}
+
+/// Nombre de descripteurs ouverts (0 si /proc/<pid>/fd n'est pas lisible)
+fn count_fds(pid: u32) -> u32 {
+    std::fs::read_dir(format!("/proc/{}/fd", pid)).map_or(0, |entries| entries.count() as u32)
+}
+
+/// Envoie une mesure périodique tant que l'arbre de processus existe (mode one-shot)
+pub async fn report_metrics(
+    mut sampler: TreeSampler,
+    interval: Duration,
+    project: String,
+    process: Option<String>,
+    tx: mpsc::Sender<LogMessage>,
+) {
+    let pid = sampler.pgid;
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        // Parcours de /proc hors du runtime async
+        let Ok((returned, usage)) = tokio::task::spawn_blocking(move || {
+            let usage = sampler.sample();
+            (sampler, usage)
+        })
+        .await
+        else {
+            return;
+        };
+        sampler = returned;
+        let Some(usage) = usage else { return };
+        let message = LogMessage::metrics(project.clone(), usage, pid).with_process(process.clone());
+        if tx.send(message).await.is_err() {
+            return;
+        }
+    }
+}
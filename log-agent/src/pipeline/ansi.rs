@@ -0,0 +1,69 @@
+use regex::Regex;
+use std::borrow::Cow;
+use std::sync::LazyLock;
+
+/// Séquences d'échappement: CSI (couleurs, curseur), OSC (titre, liens) et séquences courtes
+///
+/// Seulement les vrais caractères ESC: un texte comme "\033[31m" (script shell,
+/// chaîne JSON échappée, documentation) fait partie du message et reste tel quel.
+static ESCAPES: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\x1b(?:\[[0-?]*[ -/]*[@-~]|\][^\x07\x1b\n]*(?:\x07|\x1b\\)|[@-Z\\-_])").unwrap()
+});
+
+/// Texte sans ses codes ANSI (emprunté s'il n'en contient pas)
+pub fn strip(text: &str) -> Cow<'_, str> {
+    if text.contains('\x1b') {
+        ESCAPES.replace_all(text, "")
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Position dans `styled` qui garde les `clean_len` premiers octets de son texte sans codes ANSI
+///
+/// Jamais au milieu d'une séquence: celles qui suivent le dernier octet gardé sont exclues.
+pub fn offset_of(styled: &str, clean_len: usize) -> usize {
+    let (mut clean, mut last) = (0, 0);
+    for escape in ESCAPES.find_iter(styled) {
+        let text = escape.start() - last;
+        if clean + text >= clean_len {
+            break;
+        }
+        clean += text;
+        last = escape.end();
+    }
+    (last + (clean_len - clean)).min(styled.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_csi_osc_and_short_sequences() {
+        assert_eq!(strip("\x1b[1;31mERROR\x1b[0m done"), "ERROR done");
+        assert_eq!(strip("\x1b]0;title\x07ready"), "ready");
+        assert_eq!(strip("\x1b]8;;http://x\x1b\\link\x1b]8;;\x1b\\"), "link");
+        assert_eq!(strip("\x1bMsaved"), "saved");
+        assert!(matches!(strip("plain [1m text"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn escape_codes_written_as_text_are_kept() {
+        for text in [r"echo \033[31mfailed\033[0m", r#"{"msg":"\u001b[1mbold"}"#, r#"echo "\e[1m""#, r"\x1b[0m"] {
+            assert!(matches!(strip(text), Cow::Borrowed(_)), "{}", text);
+        }
+        // Seule la vraie séquence est retirée
+        assert_eq!(strip("\x1b[32mok\x1b[0m \\033[0m"), r"ok \033[0m");
+    }
+
+    #[test]
+    fn offset_never_splits_a_sequence() {
+        let styled = "\x1b[31mab\x1b[0mcd";
+        assert_eq!(&styled[..offset_of(styled, 0)], "");
+        assert_eq!(&styled[..offset_of(styled, 1)], "\x1b[31ma");
+        assert_eq!(&styled[..offset_of(styled, 2)], "\x1b[31mab");
+        assert_eq!(&styled[..offset_of(styled, 3)], "\x1b[31mab\x1b[0mc");
+        assert_eq!(offset_of(styled, 4), styled.len());
+    }
+}
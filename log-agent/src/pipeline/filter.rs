@@ -0,0 +1,55 @@
+use crate::config::{FilterConfig, LogLevel as MinLevel};
+use crate::types::{LogLevel, LogMessage};
+use regex::Regex;
+
+/// Filtre compilé depuis la section [filters] de la config
+#[derive(Debug, Clone)]
+pub struct LogFilter {
+    ignore_patterns: Vec<Regex>,
+    min_severity: u8,
+}
+
+impl LogFilter {
+    /// Compile les patterns une seule fois au démarrage
+    pub fn new(config: &FilterConfig) -> Result<Self, regex::Error> {
+        let ignore_patterns = config
+            .ignore_patterns
+            .iter()
+            .map(|pattern| Regex::new(pattern))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let min_severity = match config.min_level {
+            MinLevel::Debug => 0,
+            MinLevel::Info => 1,
+            MinLevel::Warn => 2,
+            MinLevel::Error => 3,
+        };
+
+        Ok(Self {
+            ignore_patterns,
+            min_severity,
+        })
+    }
+
+    /// Retourne true si le log doit être conservé
+    pub fn accepts(&self, log: &LogMessage) -> bool {
+        if severity(&log.data.level) < self.min_severity {
+            return false;
+        }
+
+        !self
+            .ignore_patterns
+            .iter()
+            .any(|re| re.is_match(&log.data.message))
+    }
+}
+
+/// Rang de sévérité d'un niveau (debug < info < warn < error)
+fn severity(level: &LogLevel) -> u8 {
+    match level {
+        LogLevel::Debug => 0,
+        LogLevel::Info => 1,
+        LogLevel::Warn => 2,
+        LogLevel::Error => 3,
+    }
+}
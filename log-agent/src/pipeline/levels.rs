@@ -0,0 +1,161 @@
+use super::ansi;
+use crate::config::{LevelsConfig, LogLevel as ConfigLevel};
+use crate::types::{LogLevel, LogSource};
+use regex::Regex;
+
+/// Formats reconnus par défaut ([levels] presets), testés après les règles de la config
+const PRESETS: &[(&str, LogLevel)] = &[
+    // [ERROR], <warn>, [INFO]...
+    (r"(?i)[\[<](?:error|err|fatal|crit|critical|alert|emerg)[\]>]", LogLevel::Error),
+    (r"(?i)[\[<](?:warn|warning)[\]>]", LogLevel::Warn),
+    (r"(?i)[\[<](?:info|notice)[\]>]", LogLevel::Info),
+    (r"(?i)[\[<](?:debug|trace|verbose)[\]>]", LogLevel::Debug),
+    // logfmt et JSON: level=warn, "level":"error", severity: ERROR
+    (r#"(?i)\b(?:level|lvl|severity)"?\s*[=:]\s*"?(?:error|err|fatal|crit|critical|panic)\b"#, LogLevel::Error),
+    (r#"(?i)\b(?:level|lvl|severity)"?\s*[=:]\s*"?(?:warn|warning)\b"#, LogLevel::Warn),
+    (r#"(?i)\b(?:level|lvl|severity)"?\s*[=:]\s*"?(?:info|notice)\b"#, LogLevel::Info),
+    (r#"(?i)\b(?:level|lvl|severity)"?\s*[=:]\s*"?(?:debug|trace)\b"#, LogLevel::Debug),
+    // Rust (env_logger, tracing), log4j...: niveau en majuscules dans les premiers mots
+    (r"^(?:\S+\s+){0,3}(?:ERROR|FATAL)\b", LogLevel::Error),
+    (r"^(?:\S+\s+){0,3}WARN(?:ING)?\b", LogLevel::Warn),
+    (r"^(?:\S+\s+){0,3}INFO\b", LogLevel::Info),
+    (r"^(?:\S+\s+){0,3}(?:DEBUG|TRACE)\b", LogLevel::Debug),
+    // glog / klog (E0102 15:04:05.000) et logcat (pid tid E Tag: ...)
+    (r"^E\d{4} |\s\d+\s+\d+ E ", LogLevel::Error),
+    (r"^W\d{4} |\s\d+\s+\d+ W ", LogLevel::Warn),
+    (r"^I\d{4} |\s\d+\s+\d+ I ", LogLevel::Info),
+    (r"\s\d+\s+\d+ [DV] ", LogLevel::Debug),
+    // Mots isolés: pas "stderr", "terror", "0 errors" ni "error_count=0"
+    (r"(?i)\b(?:error|fatal|panic|panicked|exception)\b|^Traceback ", LogLevel::Error),
+    (r"(?i)\b(?:warn|warning)\b", LogLevel::Warn),
+];
+
+/// Règle compilée
+#[derive(Debug, Clone)]
+struct Rule {
+    pattern: Regex,
+    level: LogLevel,
+    source: Option<LogSource>,
+}
+
+/// Niveau des lignes compilé depuis la section [levels] de la config
+#[derive(Debug, Clone)]
+pub struct LevelRules {
+    rules: Vec<Rule>,
+    stdout: LogLevel,
+    stderr: LogLevel,
+}
+
+impl LevelRules {
+    /// Compile les règles de la config puis les presets
+    pub fn new(config: &LevelsConfig) -> Result<Self, regex::Error> {
+        let mut rules = config
+            .rules
+            .iter()
+            .map(|rule| {
+                Ok(Rule {
+                    pattern: Regex::new(&rule.pattern)?,
+                    level: level(&rule.level),
+                    source: rule.source.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>, regex::Error>>()?;
+
+        if config.presets {
+            rules.extend(PRESETS.iter().map(|(pattern, level)| Rule {
+                pattern: Regex::new(pattern).expect("preset regex"),
+                level: level.clone(),
+                source: None,
+            }));
+        }
+
+        Ok(Self {
+            rules,
+            stdout: level(&config.stdout),
+            stderr: level(&config.stderr),
+        })
+    }
+
+    /// Niveau de la première règle qui matche, sinon celui du flux
+    pub fn infer(&self, message: &str, source: &LogSource) -> LogLevel {
+        // Couleurs ignorées (messages non nettoyés par le pipeline: progression...)
+        let text = ansi::strip(message);
+
+        self.rules
+            .iter()
+            .filter(|rule| rule.source.as_ref().is_none_or(|s| s == source))
+            .find(|rule| rule.pattern.is_match(&text))
+            .map(|rule| rule.level.clone())
+            .unwrap_or_else(|| match source {
+                LogSource::Stdout => self.stdout.clone(),
+                LogSource::Stderr => self.stderr.clone(),
+            })
+    }
+}
+
+fn level(level: &ConfigLevel) -> LogLevel {
+    match level {
+        ConfigLevel::Debug => LogLevel::Debug,
+        ConfigLevel::Info => LogLevel::Info,
+        ConfigLevel::Warn => LogLevel::Warn,
+        ConfigLevel::Error => LogLevel::Error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LevelRule;
+
+    fn infer(message: &str) -> LogLevel {
+        LevelRules::new(&LevelsConfig::default()).unwrap().infer(message, &LogSource::Stdout)
+    }
+
+    #[test]
+    fn presets_recognize_common_formats() {
+        assert!(matches!(infer("[ERROR] connection refused"), LogLevel::Error));
+        assert!(matches!(infer("<warn> disk almost full"), LogLevel::Warn));
+        assert!(matches!(infer(r#"{"level":"error","msg":"boom"}"#), LogLevel::Error));
+        assert!(matches!(infer("ts=1 level=debug msg=tick"), LogLevel::Debug));
+        assert!(matches!(infer("2026-01-18T10:00:00Z  WARN api: slow query"), LogLevel::Warn));
+        assert!(matches!(infer("E0118 10:00:00.000 1 main.go:12] failed"), LogLevel::Error));
+        assert!(matches!(infer("Traceback (most recent call last):"), LogLevel::Error));
+    }
+
+    #[test]
+    fn isolated_words_only() {
+        assert!(matches!(infer("0 errors, 2 tests passed"), LogLevel::Info));
+        assert!(matches!(infer("error_count=0 terror stderr"), LogLevel::Info));
+        assert!(matches!(infer("thread 'main' panicked at src/main.rs:2:5"), LogLevel::Error));
+        assert!(matches!(infer("\x1b[31mERROR\x1b[0m failed"), LogLevel::Error));
+    }
+
+    #[test]
+    fn config_rules_come_first_and_can_target_a_stream() {
+        let config = LevelsConfig {
+            rules: vec![
+                LevelRule { pattern: "^npm ERR!".to_string(), level: ConfigLevel::Warn, source: None },
+                LevelRule {
+                    pattern: ".".to_string(),
+                    level: ConfigLevel::Debug,
+                    source: Some(LogSource::Stderr),
+                },
+            ],
+            stderr: ConfigLevel::Error,
+            ..LevelsConfig::default()
+        };
+        let rules = LevelRules::new(&config).unwrap();
+        assert!(matches!(rules.infer("npm ERR! code E404", &LogSource::Stdout), LogLevel::Warn));
+        assert!(matches!(rules.infer("compiling", &LogSource::Stderr), LogLevel::Debug));
+        assert!(matches!(rules.infer("compiling", &LogSource::Stdout), LogLevel::Info));
+
+        let without_presets = LevelsConfig {
+            presets: false,
+            stderr: ConfigLevel::Error,
+            ..LevelsConfig::default()
+        };
+        let rules = LevelRules::new(&without_presets).unwrap();
+        assert!(matches!(rules.infer("[WARN] ignored", &LogSource::Stdout), LogLevel::Info));
+        assert!(matches!(rules.infer("[WARN] ignored", &LogSource::Stderr), LogLevel::Error));
+    }
+}
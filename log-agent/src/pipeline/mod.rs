@@ -0,0 +1,142 @@
+pub mod ansi;
+mod filter;
+mod levels;
+mod multiline;
+mod parse;
+
+pub use filter::LogFilter;
+pub use levels::LevelRules;
+pub use multiline::Multiline;
+pub use parse::LineParser;
+
+use crate::config::Config;
+use crate::types::LogMessage;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Étapes de traitement appliquées entre la capture et le channel de logs
+///
+/// Cloner le pipeline partage les compteurs: toutes les tâches de capture
+/// d'un même run alimentent les mêmes statistiques.
+#[derive(Debug, Clone)]
+pub struct Pipeline {
+    multiline: Option<Multiline>,
+    parser: LineParser,
+    levels: LevelRules,
+    filter: LogFilter,
+    /// Longueur max d'un message (agent.max_line_bytes, 0 = pas de limite)
+    max_line_bytes: usize,
+    dropped: Arc<AtomicUsize>,
+}
+
+impl Pipeline {
+    /// Construit le pipeline depuis la config (compile les regex)
+    pub fn new(config: &Config) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let multiline = Multiline::new(&config.multiline)
+            .map_err(|e| format!("Invalid pattern in [[multiline.rules]]: {}", e))?;
+        let levels = LevelRules::new(&config.levels)
+            .map_err(|e| format!("Invalid pattern in [[levels.rules]]: {}", e))?;
+        let filter = LogFilter::new(&config.filters)
+            .map_err(|e| format!("Invalid pattern in [filters] ignore_patterns: {}", e))?;
+
+        Ok(Self {
+            multiline,
+            parser: LineParser::new(&config.parsing),
+            levels,
+            filter,
+            max_line_bytes: config.agent.max_line_bytes,
+            dropped: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    /// Applique les étapes au log, retourne None s'il doit être ignoré
+    ///
+    /// Les codes ANSI sont retirés du message avant les autres étapes (la version
+    /// colorée reste dans `styled`), la ligne est analysée avant d'être coupée
+    /// (max_line_bytes), et son niveau est déterminé avant les filtres (min_level en dépend).
+    pub fn process(&self, log: LogMessage) -> Option<LogMessage> {
+        let log = self.classify(log);
+        if !self.filter.accepts(&log) {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+
+        Some(log)
+    }
+
+    /// Comme `process`, sans compter le log s'il est écarté (mises à jour de progression)
+    pub fn preview(&self, log: LogMessage) -> Option<LogMessage> {
+        let log = self.classify(log);
+        self.filter.accepts(&log).then_some(log)
+    }
+
+    /// Message sans codes ANSI, champs selon [parsing], niveau lu dans la ligne ou selon [levels]
+    fn classify(&self, mut log: LogMessage) -> LogMessage {
+        if let std::borrow::Cow::Owned(clean) = ansi::strip(&log.data.message) {
+            log.data.styled = Some(std::mem::replace(&mut log.data.message, clean));
+        }
+        log.data.level = match self.parser.apply(&mut log.data) {
+            Some(level) => level,
+            None => self.levels.infer(&log.data.message, &log.data.source),
+        };
+        log.limit_length(self.max_line_bytes)
+    }
+
+    /// Règles de regroupement des lignes (None si [multiline] est désactivé)
+    ///
+    /// Le regroupement se fait à la capture, avant `process`: un événement
+    /// multiligne passe dans le pipeline comme une seule ligne.
+    pub fn multiline(&self) -> Option<&Multiline> {
+        self.multiline.as_ref()
+    }
+
+    /// Nombre de lignes écartées par les filtres depuis le démarrage
+    pub fn dropped_count(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{LogLevel, LogSource};
+
+    fn process(line: &str) -> LogMessage {
+        let pipeline = Pipeline::new(&Config::default()).unwrap();
+        let log = LogMessage::new("test".to_string(), line.to_string(), LogSource::Stdout, 1);
+        pipeline.process(log).unwrap()
+    }
+
+    #[test]
+    fn structured_level_is_not_overridden_by_the_message() {
+        let log = process(r#"level=info msg="upstream replied [ERROR] quota""#);
+        assert!(matches!(log.data.level, LogLevel::Info));
+        assert_eq!(log.data.message, "upstream replied [ERROR] quota");
+
+        // Sans niveau dans la ligne, le message passe par [levels]
+        let log = process(r#"msg="upstream replied [ERROR] quota""#);
+        assert!(matches!(log.data.level, LogLevel::Error));
+    }
+
+    #[test]
+    fn progress_updates_are_stripped() {
+        let pipeline = Pipeline::new(&Config::default()).unwrap();
+        let update = LogMessage::progress("test".to_string(), "\x1b[32m42%\x1b[0m".to_string(), LogSource::Stdout, 1);
+        let update = pipeline.preview(update).unwrap();
+        assert_eq!(update.data.message, "42%");
+        assert_eq!(pipeline.dropped_count(), 0);
+    }
+
+    #[test]
+    fn dropped_lines_are_counted_across_clones() {
+        let mut config = Config::default();
+        config.filters.ignore_patterns = vec!["tick".to_string()];
+        let pipeline = Pipeline::new(&config).unwrap();
+        // Un clone par processus ou entrée suivie
+        for clone in [pipeline.clone(), pipeline.clone()] {
+            let log = LogMessage::new("test".to_string(), "debug: tick".to_string(), LogSource::Stdout, 1);
+            assert!(clone.process(log).is_none());
+        }
+        assert_eq!(pipeline.dropped_count(), 2);
+    }
+}
@@ -0,0 +1,192 @@
+use super::ansi;
+use crate::config::MultilineConfig;
+use regex::Regex;
+use std::time::Duration;
+
+/// Lignes de continuation reconnues par défaut ([multiline] presets): (ligne, ligne précédente)
+const PRESETS: &[(&str, Option<&str>)] = &[
+    // Frames Node/Java ("    at ..."), Python ("  File ..."), Rust ("   0: ...")
+    (r#"^\s+(?:at |File "|\d+: )"#, None),
+    // Autre ligne indentée (code d'une frame Python, message...): seulement après un
+    // en-tête ou une frame, pas après un log quelconque (statuts alignés de cargo)
+    (
+        r"^\s+\S",
+        Some(
+            r#"^\s+(?:at |File "|\d+: )|^(?:Caused by: |Exception in thread |stack backtrace:)|panicked at|^Traceback \(most recent call last\):|^[\w.$]*(?:Error|Exception)(?::|$)"#,
+        ),
+    ),
+    // Java: cause chaînée et frames omises
+    (r"^Caused by: |^Suppressed: |^\s*\.\.\. \d+ (?:more|common frames omitted)", None),
+    // Rust: message du panic (ligne après l'en-tête), backtrace et note finale
+    (r".", Some(r"panicked at .*:$")),
+    (r"^stack backtrace:|^note: ", None),
+    // Python: exception finale après les frames, et exceptions chaînées
+    (r"^[A-Za-z_][\w.]*(?:Error|Exception|Exit|Interrupt|Warning)\b", Some(r"^\s")),
+    (r"^During handling of the above exception|^The above exception was the direct cause", None),
+    // Traceback: rattaché à un message d'erreur (logging.exception: "ERROR:root:message"
+    // puis la trace) ou à l'exception précédente, sinon début d'un nouvel événement
+    (
+        r"^Traceback \(most recent call last\):",
+        Some(r"(?i)\b(?:error|critical|fatal|exception)\b|(?:exception occurred|following exception):$"),
+    ),
+    // Node: version affichée après une exception non interceptée
+    (r"^Node\.js v\d+", Some(r"^\s+at ")),
+];
+
+/// Règle compilée
+#[derive(Debug, Clone)]
+struct Rule {
+    pattern: Regex,
+    after: Option<Regex>,
+}
+
+/// Regroupement des lignes compilé depuis la section [multiline] de la config
+#[derive(Debug, Clone)]
+pub struct Multiline {
+    rules: Vec<Rule>,
+    /// Délai sans continuation avant d'envoyer l'événement
+    pub flush: Duration,
+    pub max_lines: usize,
+}
+
+impl Multiline {
+    /// Compile les règles de la config puis les presets (None si le regroupement est désactivé)
+    pub fn new(config: &MultilineConfig) -> Result<Option<Self>, regex::Error> {
+        if !config.enabled {
+            return Ok(None);
+        }
+
+        let mut rules = config
+            .rules
+            .iter()
+            .map(|rule| {
+                Ok(Rule {
+                    pattern: Regex::new(&rule.pattern)?,
+                    after: rule.after.as_deref().map(Regex::new).transpose()?,
+                })
+            })
+            .collect::<Result<Vec<_>, regex::Error>>()?;
+
+        if config.presets {
+            rules.extend(PRESETS.iter().map(|(pattern, after)| Rule {
+                pattern: Regex::new(pattern).expect("preset regex"),
+                after: after.map(|after| Regex::new(after).expect("preset regex")),
+            }));
+        }
+
+        Ok(Some(Self {
+            rules,
+            flush: Duration::from_millis(config.flush_ms),
+            max_lines: config.max_lines.max(1),
+        }))
+    }
+
+    /// La ligne continue-t-elle l'événement dont `previous` est la dernière ligne ?
+    ///
+    /// Les codes ANSI sont ignorés (une frame colorée reste une ligne indentée).
+    pub fn continues(&self, previous: &str, line: &str) -> bool {
+        let (previous, line) = (ansi::strip(previous), ansi::strip(line));
+        self.rules.iter().any(|rule| {
+            rule.pattern.is_match(&line) && rule.after.as_ref().is_none_or(|after| after.is_match(&previous))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Regroupe les lignes comme la capture: les lignes vides sont ignorées,
+    /// une ligne qui ne continue pas commence un événement
+    fn group(lines: &[&str]) -> Vec<Vec<String>> {
+        let multiline = Multiline::new(&MultilineConfig::default()).unwrap().unwrap();
+        let mut events: Vec<Vec<String>> = Vec::new();
+        for line in lines.iter().filter(|line| !line.is_empty()) {
+            match events.last_mut() {
+                Some(event) if multiline.continues(event.last().unwrap(), line) => {
+                    event.push(line.to_string())
+                }
+                _ => events.push(vec![line.to_string()]),
+            }
+        }
+        events
+    }
+
+    #[test]
+    fn logging_exception_keeps_message_and_traceback_together() {
+        let events = group(&[
+            "ERROR:root:payment failed",
+            "Traceback (most recent call last):",
+            "  File \"app.py\", line 3, in <module>",
+            "    pay()",
+            "ZeroDivisionError: division by zero",
+            "INFO:root:next request",
+        ]);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].len(), 5);
+        assert_eq!(events[1], ["INFO:root:next request"]);
+    }
+
+    #[test]
+    fn traceback_after_an_unrelated_line_starts_an_event() {
+        let events = group(&[
+            "INFO: request served",
+            "Traceback (most recent call last):",
+            "  File \"app.py\", line 3, in <module>",
+            "ValueError: bad",
+        ]);
+        assert_eq!(events.iter().map(Vec::len).collect::<Vec<_>>(), [1, 3]);
+    }
+
+    #[test]
+    fn aligned_cargo_output_is_not_a_stack_trace() {
+        let events = group(&[
+            "warning: unused variable x",
+            "  --> src/main.rs:2",
+            "   Compiling a v0.1",
+            "   Compiling b v0.1",
+            "    Finished dev",
+        ]);
+        assert_eq!(events.len(), 5, "{:?}", events);
+    }
+
+    #[test]
+    fn chained_python_exceptions_stay_in_one_event() {
+        let events = group(&[
+            "Traceback (most recent call last):",
+            "  File \"a.py\", line 1, in <module>",
+            "KeyError: 'x'",
+            "",
+            "During handling of the above exception, another exception occurred:",
+            "",
+            "Traceback (most recent call last):",
+            "  File \"a.py\", line 3, in <module>",
+            "ValueError: bad",
+        ]);
+        assert_eq!(events.len(), 1, "{:?}", events);
+    }
+
+    #[test]
+    fn rust_panic_and_java_frames() {
+        let events = group(&[
+            "thread 'main' panicked at src/main.rs:4:5:",
+            "index out of bounds",
+            "stack backtrace:",
+            "   0: app::main",
+            "             at ./src/main.rs:4:5",
+            "note: run with `RUST_BACKTRACE=full` for a verbose backtrace.",
+            "Exception in thread \"main\" java.lang.IllegalStateException: boom",
+            "\tat com.example.App.main(App.java:5)",
+            "Caused by: java.io.IOException: disk",
+            "\t... 3 more",
+            "server ready",
+        ]);
+        assert_eq!(events.iter().map(Vec::len).collect::<Vec<_>>(), [6, 4, 1]);
+    }
+
+    #[test]
+    fn colored_frames_are_still_indented() {
+        let events = group(&["Error: boom", "\x1b[90m    at main (index.js:1:1)\x1b[0m"]);
+        assert_eq!(events.len(), 1);
+    }
+}
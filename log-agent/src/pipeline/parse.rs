@@ -0,0 +1,292 @@
+use crate::config::ParsingConfig;
+use crate::types::{LogData, LogLevel};
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value};
+
+/// Lecture des lignes structurées (JSON, logfmt) selon la section [parsing] de la config
+#[derive(Debug, Clone)]
+pub struct LineParser {
+    json: bool,
+    logfmt: bool,
+    level_keys: Vec<String>,
+    message_keys: Vec<String>,
+    timestamp_keys: Vec<String>,
+}
+
+impl LineParser {
+    pub fn new(config: &ParsingConfig) -> Self {
+        Self {
+            json: config.json,
+            logfmt: config.logfmt,
+            level_keys: config.level_keys.clone(),
+            message_keys: config.message_keys.clone(),
+            timestamp_keys: config.timestamp_keys.clone(),
+        }
+    }
+
+    /// Remplace la ligne par son message et range les autres clés dans `fields`
+    ///
+    /// Retourne le niveau lu dans la ligne (None: ligne non structurée ou sans niveau
+    /// reconnu, il est alors déduit par [levels]).
+    pub fn apply(&self, data: &mut LogData) -> Option<LogLevel> {
+        let mut object = self.parse(&data.message)?;
+
+        let level = take_first(&mut object, &self.level_keys, parse_level);
+        if let Some(timestamp) = take_first(&mut object, &self.timestamp_keys, parse_timestamp) {
+            data.timestamp = timestamp;
+        }
+        // Sans message, la ligne reste affichée telle quelle (rien n'est perdu)
+        if let Some(message) = take_first(&mut object, &self.message_keys, |value| {
+            value.as_str().map(str::to_string)
+        }) {
+            // Ligne d'origine conservée pour les sauvegardes fichier
+            if data.raw.is_none() {
+                data.raw = Some(std::mem::take(&mut data.message).into_bytes());
+            }
+            data.message = message;
+            data.styled = None;
+            data.fields = object;
+        }
+        level
+    }
+
+    /// Clés d'une ligne JSON ou logfmt (None: ligne non structurée)
+    fn parse(&self, line: &str) -> Option<Map<String, Value>> {
+        let line = line.trim();
+        if self.json && line.starts_with('{') && line.ends_with('}') {
+            return match serde_json::from_str(line) {
+                Ok(Value::Object(object)) => Some(object),
+                _ => None,
+            };
+        }
+        if self.logfmt {
+            let object = parse_logfmt(line)?;
+            // "a=1 b=2" n'est du logfmt que s'il contient un niveau ou un message
+            let known = self
+                .level_keys
+                .iter()
+                .chain(&self.message_keys)
+                .any(|key| object.contains_key(key));
+            return known.then_some(object);
+        }
+        None
+    }
+}
+
+/// Paires key=value d'une ligne logfmt (valeurs entre guillemets possibles)
+///
+/// None si un mot n'est pas de la forme key=value: la ligne est alors du texte libre.
+fn parse_logfmt(line: &str) -> Option<Map<String, Value>> {
+    let mut object = Map::new();
+    let mut rest = line;
+    while !rest.is_empty() {
+        let end = rest.find(|c: char| c == '=' || c.is_whitespace())?;
+        let key = &rest[..end];
+        if end == 0 || !rest[end..].starts_with('=') || key.contains('"') {
+            return None;
+        }
+        rest = &rest[end + 1..];
+
+        let value = if let Some(quoted) = rest.strip_prefix('"') {
+            let close = closing_quote(quoted)?;
+            rest = &quoted[close + 1..];
+            if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+                return None;
+            }
+            unescape(&quoted[..close])
+        } else {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let value = &rest[..end];
+            rest = &rest[end..];
+            value.to_string()
+        };
+
+        object.insert(key.to_string(), Value::String(value));
+        rest = rest.trim_start();
+    }
+    Some(object)
+}
+
+/// Position du guillemet fermant (les \" sont échappés)
+fn closing_quote(text: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// Retire et convertit la valeur de la première clé présente dont la conversion réussit
+fn take_first<T>(
+    object: &mut Map<String, Value>,
+    keys: &[String],
+    convert: impl Fn(&Value) -> Option<T>,
+) -> Option<T> {
+    keys.iter().find_map(|key| {
+        let converted = lookup(object, key).and_then(&convert)?;
+        remove(object, key);
+        Some(converted)
+    })
+}
+
+/// Valeur d'une clé, littérale ("log.level") ou imbriquée (fields.message)
+fn lookup<'a>(object: &'a Map<String, Value>, key: &str) -> Option<&'a Value> {
+    if let Some(value) = object.get(key) {
+        return Some(value);
+    }
+    let (parent, rest) = key.split_once('.')?;
+    lookup(object.get(parent)?.as_object()?, rest)
+}
+
+/// Retire une clé trouvée par `lookup` (et l'objet parent s'il devient vide)
+fn remove(object: &mut Map<String, Value>, key: &str) {
+    if object.remove(key).is_some() {
+        return;
+    }
+    let Some((parent, rest)) = key.split_once('.') else {
+        return;
+    };
+    if let Some(Value::Object(child)) = object.get_mut(parent) {
+        remove(child, rest);
+        if child.is_empty() {
+            object.remove(parent);
+        }
+    }
+}
+
+/// Niveau texte ("warn", "ERROR") ou numérique (pino, bunyan: 10 trace ... 60 fatal)
+fn parse_level(value: &Value) -> Option<LogLevel> {
+    if let Some(number) = value.as_u64() {
+        return Some(match number {
+            0..=20 => LogLevel::Debug,
+            21..=30 => LogLevel::Info,
+            31..=40 => LogLevel::Warn,
+            _ => LogLevel::Error,
+        });
+    }
+    match value.as_str()?.to_lowercase().as_str() {
+        "trace" | "debug" | "verbose" => Some(LogLevel::Debug),
+        "info" | "information" | "notice" => Some(LogLevel::Info),
+        "warn" | "warning" => Some(LogLevel::Warn),
+        "error" | "err" | "fatal" | "panic" | "dpanic" | "crit" | "critical" | "alert" | "emerg" => {
+            Some(LogLevel::Error)
+        }
+        _ => None,
+    }
+}
+
+/// Horodatage RFC 3339, ou epoch en millisecondes (pino) ou en secondes (zap)
+///
+/// En logfmt toutes les valeurs sont du texte: "1767225600" est aussi un epoch.
+fn parse_timestamp(value: &Value) -> Option<String> {
+    let epoch = match value {
+        Value::String(text) => match DateTime::parse_from_rfc3339(text) {
+            Ok(time) => return Some(time.with_timezone(&Utc).to_rfc3339()),
+            Err(_) => text.parse::<f64>().ok()?,
+        },
+        Value::Number(number) => number.as_f64()?,
+        _ => return None,
+    };
+    let time = if epoch >= 1e11 {
+        DateTime::from_timestamp_millis(epoch as i64)?
+    } else {
+        DateTime::from_timestamp(epoch.trunc() as i64, (epoch.fract() * 1e9) as u32)?
+    };
+    Some(time.to_rfc3339())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{LogMessage, LogSource};
+
+    fn parse(line: &str) -> (Option<LogLevel>, LogData) {
+        let mut data = LogMessage::new("test".to_string(), line.to_string(), LogSource::Stdout, 1).data;
+        let level = LineParser::new(&ParsingConfig::default()).apply(&mut data);
+        (level, data)
+    }
+
+    #[test]
+    fn json_line_gives_message_level_timestamp_and_fields() {
+        let line = r#"{"level":30,"time":1767225600000,"msg":"listening","port":3000}"#;
+        let (level, data) = parse(line);
+        assert!(matches!(level, Some(LogLevel::Info)));
+        assert_eq!(data.message, "listening");
+        assert_eq!(data.timestamp, "2026-01-01T00:00:00+00:00");
+        assert_eq!(data.fields.get("port"), Some(&Value::from(3000)));
+        assert_eq!(data.fields.len(), 1);
+        // Ligne d'origine gardée pour les sauvegardes
+        assert_eq!(data.raw.as_deref(), Some(line.as_bytes()));
+    }
+
+    #[test]
+    fn json_nested_keys_are_removed_with_their_empty_parent() {
+        let (level, data) = parse(r#"{"log.level":"WARN","fields":{"message":"slow"},"span":{"id":7}}"#);
+        assert!(matches!(level, Some(LogLevel::Warn)));
+        assert_eq!(data.message, "slow");
+        assert!(!data.fields.contains_key("fields"));
+        assert!(data.fields.contains_key("span"));
+    }
+
+    #[test]
+    fn json_without_message_or_invalid_is_left_as_is() {
+        let (level, data) = parse(r#"{"level":"error","code":42}"#);
+        assert!(matches!(level, Some(LogLevel::Error)));
+        assert_eq!(data.message, r#"{"level":"error","code":42}"#);
+        assert!(data.fields.is_empty());
+
+        let (level, data) = parse(r#"{"msg": unquoted}"#);
+        assert!(level.is_none());
+        assert_eq!(data.message, r#"{"msg": unquoted}"#);
+    }
+
+    #[test]
+    fn logfmt_line_with_quoted_values() {
+        let (level, data) = parse(r#"time=1767225600 level=warn msg="retry \"db\" in 2s" attempt=3"#);
+        assert!(matches!(level, Some(LogLevel::Warn)));
+        assert_eq!(data.message, r#"retry "db" in 2s"#);
+        assert_eq!(data.timestamp, "2026-01-01T00:00:00+00:00");
+        assert_eq!(data.fields.get("attempt"), Some(&Value::from("3")));
+    }
+
+    #[test]
+    fn logfmt_level_key_wins_over_brackets_in_msg() {
+        let (level, data) = parse(r#"level=info msg="upstream replied [ERROR] quota" user=42"#);
+        assert!(matches!(level, Some(LogLevel::Info)));
+        assert_eq!(data.message, "upstream replied [ERROR] quota");
+    }
+
+    #[test]
+    fn free_text_is_not_logfmt() {
+        // Pas de clé connue, ou un mot qui n'est pas key=value
+        for line in ["a=1 b=2", "Server started on port=3000", r#"msg="unterminated"#] {
+            let (level, data) = parse(line);
+            assert!(level.is_none(), "{}", line);
+            assert_eq!(data.message, line);
+            assert!(data.fields.is_empty());
+        }
+    }
+}
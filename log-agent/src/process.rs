@@ -0,0 +1,105 @@
+use crate::config::{CommandConfig, Config, EnvConfig, ReadyWhen};
+use crate::deps::{self, Dependency};
+use owo_colors::{AnsiColors, OwoColorize};
+
+/// Couleurs des préfixes en mode multi-process (attribuées dans l'ordre)
+const PREFIX_COLORS: [AnsiColors; 6] = [
+    AnsiColors::Cyan,
+    AnsiColors::Magenta,
+    AnsiColors::Yellow,
+    AnsiColors::Green,
+    AnsiColors::Blue,
+    AnsiColors::BrightRed,
+];
+
+/// Processus à lancer, résolu depuis la CLI ou [agent.commands]
+#[derive(Debug, Clone)]
+pub struct ProcessSpec {
+    /// Nom de la commande prédéfinie (None pour une commande passée en CLI)
+    pub name: Option<String>,
+    pub command: Vec<String>,
+    /// Mode watch propre à la commande (None = réglage global)
+    pub watch: Option<bool>,
+    /// Environnement et dossier de travail
+    pub env: EnvConfig,
+    /// Chemins surveillés (relatifs au cwd de la commande)
+    pub watch_paths: Vec<String>,
+    /// Globs des changements ignorés
+    pub ignore_globs: Vec<String>,
+    /// Condition de disponibilité (None = prêt dès le lancement)
+    pub ready_when: Option<ReadyWhen>,
+    /// Commandes à attendre avant de lancer celle-ci
+    pub depends_on: Vec<Dependency>,
+    /// Config du processus, avec les overrides de la commande appliqués
+    pub config: Config,
+}
+
+impl ProcessSpec {
+    /// Commande passée directement en CLI
+    pub fn inline(command: Vec<String>, config: &Config) -> Self {
+        Self {
+            name: None,
+            command,
+            watch: None,
+            env: EnvConfig::default(),
+            watch_paths: Vec::new(),
+            ignore_globs: Vec::new(),
+            ready_when: None,
+            depends_on: Vec::new(),
+            config: config.clone(),
+        }
+    }
+
+    /// Commande prédéfinie de [agent.commands]
+    pub fn predefined(name: &str, cmd_config: &CommandConfig, config: &Config) -> Self {
+        let mut config = config.clone();
+        config.agent.pty = cmd_config.pty().unwrap_or(config.agent.pty);
+        if let Some(options) = cmd_config.options() {
+            if let Some(signal) = &options.stop_signal {
+                config.agent.stop_signal = signal.clone();
+            }
+            if let Some(timeout) = options.stop_timeout {
+                config.agent.stop_timeout = timeout;
+            }
+            if let Some(restart) = options.restart {
+                config.agent.restart = restart;
+            }
+            if let Some(max) = options.max_restarts {
+                config.agent.max_restarts = max;
+            }
+            if let Some(encoding) = options.encoding {
+                config.agent.encoding = encoding;
+            }
+        }
+
+        Self {
+            name: Some(name.to_string()),
+            command: cmd_config.command().to_vec(),
+            watch: cmd_config.watch(),
+            env: cmd_config.options().map(|o| o.env.clone()).unwrap_or_default(),
+            watch_paths: cmd_config.options().map(|o| o.watch_paths.clone()).unwrap_or_default(),
+            ignore_globs: cmd_config.options().map(|o| o.ignore_globs.clone()).unwrap_or_default(),
+            ready_when: cmd_config.options().and_then(|o| o.ready_when.clone()),
+            depends_on: deps::dependencies(cmd_config),
+            config,
+        }
+    }
+
+    /// Nom affiché: nom de la commande ou programme lancé
+    pub fn label(&self) -> String {
+        self.name
+            .clone()
+            .or_else(|| self.command.first().cloned())
+            .unwrap_or_default()
+    }
+}
+
+/// Préfixe coloré "name │ " aligné sur le nom le plus long (mode one-shot)
+pub fn prefix(name: &str, index: usize, width: usize, colors: bool) -> String {
+    let padded = format!("{:width$} │ ", name, width = width);
+    if colors {
+        padded.color(PREFIX_COLORS[index % PREFIX_COLORS.len()]).to_string()
+    } else {
+        padded
+    }
+}
@@ -0,0 +1,158 @@
+use crate::config::{AgentConfig, RestartPolicy};
+use std::collections::VecDeque;
+use std::process::ExitStatus;
+use std::time::{Duration, Instant};
+
+/// Délai maximal entre deux redémarrages automatiques
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Suite à donner à la sortie d'un processus
+#[derive(Debug, Clone, PartialEq)]
+pub enum RestartDecision {
+    /// La politique ne demande pas de redémarrage
+    Stay,
+    /// Redémarrer après `delay` (attempt = n-ième redémarrage dans la fenêtre)
+    Restart { attempt: u32, delay: Duration },
+    /// Trop de redémarrages dans la fenêtre: on abandonne
+    CrashLoop { restarts: u32, window: Duration },
+}
+
+/// Compte les redémarrages automatiques d'un processus
+///
+/// Le délai double à chaque redémarrage de la fenêtre (backoff exponentiel,
+/// plafonné à 30s). Les redémarrages sortis de la fenêtre ne comptent plus:
+/// un processus resté stable assez longtemps repart avec le délai initial.
+#[derive(Debug, Clone)]
+pub struct RestartTracker {
+    policy: RestartPolicy,
+    max_restarts: u32,
+    window: Duration,
+    backoff: Duration,
+    history: VecDeque<Instant>,
+}
+
+impl RestartTracker {
+    /// Politique définie par agent.restart, max_restarts, restart_window et restart_backoff_ms
+    pub fn from_config(agent: &AgentConfig) -> Self {
+        Self {
+            policy: agent.restart,
+            max_restarts: agent.max_restarts,
+            window: Duration::from_secs(agent.restart_window),
+            backoff: Duration::from_millis(agent.restart_backoff_ms),
+            history: VecDeque::new(),
+        }
+    }
+
+    /// Décide s'il faut redémarrer un processus sorti avec `status`
+    pub fn on_exit(&mut self, status: &ExitStatus) -> RestartDecision {
+        let wanted = match self.policy {
+            RestartPolicy::Never => false,
+            RestartPolicy::OnFailure => !status.success(),
+            RestartPolicy::Always => true,
+        };
+        if !wanted {
+            return RestartDecision::Stay;
+        }
+
+        let now = Instant::now();
+        while let Some(first) = self.history.front() {
+            if now.duration_since(*first) > self.window {
+                self.history.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        let restarts = self.history.len() as u32;
+        if restarts >= self.max_restarts {
+            return RestartDecision::CrashLoop { restarts, window: self.window };
+        }
+
+        self.history.push_back(now);
+        let delay = self
+            .backoff
+            .checked_mul(1u32.checked_shl(restarts).unwrap_or(u32::MAX))
+            .unwrap_or(MAX_BACKOFF)
+            .min(MAX_BACKOFF);
+
+        RestartDecision::Restart { attempt: restarts + 1, delay }
+    }
+
+    /// Oublie les redémarrages passés (redémarrage manuel)
+    pub fn reset(&mut self) {
+        self.history.clear();
+    }
+
+    /// Nombre max de redémarrages dans la fenêtre
+    pub fn max_restarts(&self) -> u32 {
+        self.max_restarts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::process::ExitStatusExt;
+
+    fn tracker(policy: RestartPolicy, max_restarts: u32, window_secs: u64) -> RestartTracker {
+        let agent = AgentConfig {
+            restart: policy,
+            max_restarts,
+            restart_window: window_secs,
+            restart_backoff_ms: 500,
+            ..Default::default()
+        };
+        RestartTracker::from_config(&agent)
+    }
+
+    fn exit(code: i32) -> ExitStatus {
+        ExitStatus::from_raw(code << 8)
+    }
+
+    fn restart(attempt: u32, delay_ms: u64) -> RestartDecision {
+        RestartDecision::Restart { attempt, delay: Duration::from_millis(delay_ms) }
+    }
+
+    #[test]
+    fn policy_decides_which_exits_restart() {
+        assert_eq!(tracker(RestartPolicy::Never, 5, 60).on_exit(&exit(1)), RestartDecision::Stay);
+        assert_eq!(tracker(RestartPolicy::OnFailure, 5, 60).on_exit(&exit(0)), RestartDecision::Stay);
+        assert_eq!(tracker(RestartPolicy::OnFailure, 5, 60).on_exit(&exit(1)), restart(1, 500));
+        // Tué par un signal: compte comme un échec
+        let killed = ExitStatus::from_raw(libc::SIGKILL);
+        assert_eq!(tracker(RestartPolicy::OnFailure, 5, 60).on_exit(&killed), restart(1, 500));
+        assert_eq!(tracker(RestartPolicy::Always, 5, 60).on_exit(&exit(0)), restart(1, 500));
+    }
+
+    #[test]
+    fn backoff_doubles_then_crash_loop() {
+        let mut tracker = tracker(RestartPolicy::Always, 3, 60);
+        assert_eq!(tracker.on_exit(&exit(1)), restart(1, 500));
+        assert_eq!(tracker.on_exit(&exit(1)), restart(2, 1000));
+        assert_eq!(tracker.on_exit(&exit(1)), restart(3, 2000));
+        assert_eq!(
+            tracker.on_exit(&exit(1)),
+            RestartDecision::CrashLoop { restarts: 3, window: Duration::from_secs(60) }
+        );
+
+        // Redémarrage manuel: on repart de zéro
+        tracker.reset();
+        assert_eq!(tracker.on_exit(&exit(1)), restart(1, 500));
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        let mut tracker = tracker(RestartPolicy::Always, 100, 60);
+        let last = (0..20).map(|_| tracker.on_exit(&exit(1))).last().unwrap();
+        assert_eq!(last, RestartDecision::Restart { attempt: 20, delay: MAX_BACKOFF });
+    }
+
+    #[test]
+    fn restarts_leave_the_window() {
+        let mut tracker = tracker(RestartPolicy::Always, 1, 0);
+        assert_eq!(tracker.on_exit(&exit(1)), restart(1, 500));
+        std::thread::sleep(Duration::from_millis(5));
+        // Le redémarrage précédent est sorti de la fenêtre (0s): pas de crash loop
+        assert_eq!(tracker.on_exit(&exit(1)), restart(1, 500));
+    }
+}
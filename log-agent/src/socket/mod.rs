@@ -0,0 +1,451 @@
+mod spool;
+
+pub use spool::Spool;
+
+use crate::config::{AnsiMode, Config, LongLines};
+use crate::types::{LogLevel, LogMessage};
+use std::fmt;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::net::UnixStream;
+use tokio::sync::{mpsc, watch};
+use tokio::time::Instant;
+
+pub const SOCKET_PATH: &str = "/tmp/log-agent.sock";
+
+/// Délai initial entre deux tentatives de reconnexion (doublé à chaque échec)
+const INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+
+/// État de la connexion au serveur MCP
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectionState {
+    /// Aucune tentative de connexion pour l'instant
+    Idle,
+    /// Première tentative de connexion en cours
+    Connecting,
+    /// Connexion persistante établie
+    Connected,
+    /// Connexion impossible ou perdue, tentative n sur max
+    Reconnecting { attempt: u32, max: u32 },
+    /// Toutes les tentatives ont échoué, nouvel essai après le délai maximal
+    Disconnected(String),
+}
+
+impl fmt::Display for ConnectionState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConnectionState::Idle => write!(f, "idle"),
+            ConnectionState::Connecting => write!(f, "connecting"),
+            ConnectionState::Connected => write!(f, "connected"),
+            ConnectionState::Reconnecting { attempt, max } => {
+                write!(f, "retrying ({}/{})", attempt, max)
+            }
+            ConnectionState::Disconnected(reason) => write!(f, "disconnected: {}", reason),
+        }
+    }
+}
+
+/// Client Unix socket pour envoyer les logs au serveur MCP
+pub struct SocketClient {
+    socket_path: String,
+    connection_timeout: Duration,
+    retry_attempts: u32,
+    state_tx: watch::Sender<ConnectionState>,
+    /// Logs non délivrés, rejoués à la reconnexion (None si désactivé)
+    spool: Option<Spool>,
+    /// Délai max avant l'envoi d'un lot (zéro = envoi immédiat)
+    flush_interval: Duration,
+    batch_size: usize,
+    flush_on_error: bool,
+    /// Taille des morceaux des lignes coupées (long_lines = "split"), None pour les tronquer
+    split_long_lines: Option<usize>,
+    /// Messages envoyés avec leurs codes ANSI (ansi.socket = "styled")
+    styled_messages: bool,
+}
+
+/// Lot de lignes JSON en attente d'une seule écriture
+#[derive(Default)]
+struct Batch {
+    payload: String,
+    /// Pour chaque ligne du lot: à garder dans le spool si le serveur est indisponible
+    /// (tout sauf les mesures de ressources, périmées au moment du rejeu)
+    spooled: Vec<bool>,
+    count: usize,
+    /// Échéance de flush, fixée à l'arrivée du premier log du lot
+    deadline: Option<Instant>,
+}
+
+/// Connexion persistante et son planning de reconnexion
+struct Connection {
+    stream: Option<UnixStream>,
+    /// Échecs consécutifs depuis la dernière connexion réussie
+    failures: u32,
+    /// Prochaine tentative autorisée
+    next_attempt: Instant,
+}
+
+impl SocketClient {
+    pub fn new(socket_path: Option<String>) -> Self {
+        let (state_tx, _) = watch::channel(ConnectionState::Idle);
+        Self {
+            socket_path: socket_path.unwrap_or_else(|| SOCKET_PATH.to_string()),
+            connection_timeout: Duration::from_secs(5),
+            retry_attempts: 3,
+            state_tx,
+            spool: None,
+            flush_interval: Duration::from_millis(100),
+            batch_size: 500,
+            flush_on_error: true,
+            split_long_lines: None,
+            styled_messages: false,
+        }
+    }
+
+    /// Crée un client avec socket_path, connection_timeout et retry_attempts de la config
+    pub fn from_config(config: &Config) -> Self {
+        let mut client = Self::new(Some(config.agent.socket_path.clone()));
+        client.connection_timeout = Duration::from_secs(config.agent.connection_timeout.max(1));
+        client.retry_attempts = config.agent.retry_attempts.max(1);
+        client.spool = Spool::from_config(config);
+        client.flush_interval = Duration::from_millis(config.performance.flush_interval);
+        client.batch_size = config.performance.batch_size.max(1);
+        client.flush_on_error = config.performance.flush_on_error;
+        client.split_long_lines = (config.agent.long_lines == LongLines::Split && config.agent.max_line_bytes > 0)
+            .then_some(config.agent.max_line_bytes);
+        client.styled_messages = config.ansi.socket == AnsiMode::Styled;
+        client
+    }
+
+    /// Permet de suivre l'état de la connexion (TUI, affichage terminal)
+    pub fn subscribe(&self) -> watch::Receiver<ConnectionState> {
+        self.state_tx.subscribe()
+    }
+
+    /// Démarre le worker qui envoie les logs depuis le channel vers le socket
+    ///
+    /// Une seule connexion est ouverte et réutilisée. En cas d'échec, les
+    /// reconnexions suivent un backoff exponentiel: retry_attempts essais
+    /// rapprochés, puis un essai toutes les connection_timeout secondes.
+    /// Les logs non délivrés entre-temps vont dans le spool disque.
+    ///
+    /// Les logs sont regroupés en une écriture par flush_interval ou par
+    /// batch_size logs, au premier des deux atteint.
+    pub async fn start_worker(
+        &self,
+        mut rx: mpsc::Receiver<LogMessage>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut conn = Connection {
+            stream: None,
+            failures: 0,
+            next_attempt: Instant::now(),
+        };
+        let mut batch = Batch::default();
+
+        self.set_state(ConnectionState::Connecting);
+        self.try_connect(&mut conn).await;
+
+        loop {
+            tokio::select! {
+                maybe_log = rx.recv() => {
+                    let Some(log) = maybe_log else {
+                        break;
+                    };
+
+                    let mut urgent = self.push(&mut batch, &log);
+                    // Vider ce qui est déjà disponible sans repasser par le select
+                    while batch.count < self.batch_size {
+                        match rx.try_recv() {
+                            Ok(log) => urgent |= self.push(&mut batch, &log),
+                            Err(_) => break,
+                        }
+                    }
+
+                    if urgent || batch.count >= self.batch_size || self.flush_interval.is_zero() {
+                        self.flush(&mut conn, &mut batch).await;
+                    }
+                }
+                // Échéance du lot en cours
+                _ = sleep_until_deadline(batch.deadline), if batch.deadline.is_some() => {
+                    self.flush(&mut conn, &mut batch).await;
+                }
+                // Reconnexion proactive, même si aucun log n'arrive
+                _ = tokio::time::sleep_until(conn.next_attempt), if conn.stream.is_none() => {
+                    self.try_connect(&mut conn).await;
+                }
+            }
+        }
+
+        // Channel fermé: envoyer le dernier lot avant de quitter
+        self.flush(&mut conn, &mut batch).await;
+
+        if let Some(mut stream) = conn.stream.take() {
+            let _ = stream.shutdown().await;
+        }
+
+        Ok(())
+    }
+
+    /// Ajoute un log au lot, retourne true s'il doit partir sans attendre
+    fn push(&self, batch: &mut Batch, log: &LogMessage) -> bool {
+        // Ligne coupée: envoyée entière en morceaux liés si long_lines = "split"
+        let lines: Vec<String> = match self.split_long_lines {
+            Some(max_bytes) if log.data.full.is_some() => {
+                log.clone().into_chunks(max_bytes).iter().filter_map(|chunk| self.serialize(chunk)).collect()
+            }
+            _ => self.serialize(log).into_iter().collect(),
+        };
+        if lines.is_empty() {
+            return false;
+        }
+
+        for line in &lines {
+            batch.payload.push_str(line);
+            batch.spooled.push(!log.is_metrics());
+            batch.count += 1;
+        }
+        if batch.deadline.is_none() {
+            batch.deadline = Some(Instant::now() + self.flush_interval);
+        }
+
+        self.flush_on_error && matches!(log.data.level, LogLevel::Error)
+    }
+
+    /// Ligne JSON d'un log, avec la version colorée du message si ansi.socket = "styled"
+    fn serialize(&self, log: &LogMessage) -> Option<String> {
+        match &log.data.styled {
+            Some(styled) if self.styled_messages => {
+                let mut log = log.clone();
+                log.data.message = styled.clone();
+                serialize(&log).ok()
+            }
+            _ => serialize(log).ok(),
+        }
+    }
+
+    /// Envoie le lot en une seule écriture et le réinitialise
+    async fn flush(&self, conn: &mut Connection, batch: &mut Batch) {
+        if batch.count == 0 {
+            return;
+        }
+
+        let batch = std::mem::take(batch);
+        self.deliver(conn, &batch.payload, &batch.spooled).await;
+    }
+
+    /// Écrit des lignes JSON sur la connexion persistante (une reconnexion immédiate si elle a été coupée)
+    ///
+    /// Les lignes reçues en entier par le serveur ne sont pas renvoyées. En cas
+    /// d'échec, seules les autres lignes marquées dans `spooled` sont gardées pour plus tard.
+    async fn deliver(&self, conn: &mut Connection, payload: &str, spooled: &[bool]) {
+        // Octets des lignes complètes déjà reçues
+        let mut sent = 0;
+
+        if let Some(stream) = conn.stream.as_mut() {
+            match write_lines(stream, payload).await {
+                Ok(()) => return,
+                Err(written) => sent = complete_lines(payload, written),
+            }
+
+            // Le serveur a fermé la connexion (redémarrage, arrêt...). Une ligne
+            // coupée en cours d'envoi est écartée par le serveur avec la connexion.
+            conn.stream = None;
+            conn.failures = 0;
+            conn.next_attempt = Instant::now();
+            self.set_state(ConnectionState::Reconnecting {
+                attempt: 1,
+                max: self.retry_attempts,
+            });
+        }
+
+        if Instant::now() >= conn.next_attempt {
+            self.try_connect(conn).await;
+            if let Some(stream) = conn.stream.as_mut() {
+                match write_lines(stream, &payload[sent..]).await {
+                    Ok(()) => return,
+                    Err(written) => sent += complete_lines(&payload[sent..], written),
+                }
+                conn.stream = None;
+            }
+        }
+
+        // Serveur indisponible: garder les logs pour les rejouer plus tard
+        // (ligne par ligne: la limite du spool s'applique à chaque log, pas au lot)
+        if let Some(spool) = &self.spool {
+            for line in unsent_lines(payload, spooled, sent) {
+                let _ = spool.append(line);
+            }
+        }
+    }
+
+    /// Rejoue le spool dans l'ordre sur une connexion fraîchement établie
+    async fn replay_spool(&self, stream: &mut UnixStream) -> std::io::Result<()> {
+        let Some(spool) = &self.spool else {
+            return Ok(());
+        };
+
+        // Un autre agent du projet rejoue déjà le spool: ses lignes partent avec lui
+        let Some(_lock) = spool.try_lock()? else {
+            return Ok(());
+        };
+        let lines = spool.load()?;
+        if lines.is_empty() {
+            return Ok(());
+        }
+        for (i, line) in lines.iter().enumerate() {
+            if let Err(e) = stream.write_all(line.as_bytes()).await {
+                // Seules les lignes déjà envoyées quittent le spool
+                let _ = spool.keep(&lines[i..]);
+                return Err(e);
+            }
+        }
+
+        spool.keep(&[])
+    }
+
+    /// Tente une connexion et planifie la suivante en cas d'échec
+    async fn try_connect(&self, conn: &mut Connection) {
+        match self.connect().await {
+            Ok(mut stream) => {
+                // Les logs en attente passent avant les nouveaux
+                if let Err(e) = self.replay_spool(&mut stream).await {
+                    conn.failures += 1;
+                    conn.next_attempt = Instant::now() + INITIAL_BACKOFF;
+                    self.set_state(ConnectionState::Disconnected(e.to_string()));
+                    return;
+                }
+
+                conn.stream = Some(stream);
+                conn.failures = 0;
+                self.set_state(ConnectionState::Connected);
+            }
+            Err(e) => {
+                conn.failures += 1;
+
+                if conn.failures >= self.retry_attempts {
+                    // Plus d'essais rapprochés: on réessaie au rythme du timeout
+                    conn.failures = self.retry_attempts;
+                    conn.next_attempt = Instant::now() + self.connection_timeout;
+                    self.set_state(ConnectionState::Disconnected(e.to_string()));
+                } else {
+                    let backoff = INITIAL_BACKOFF * 2u32.pow(conn.failures - 1);
+                    conn.next_attempt = Instant::now() + backoff.min(self.connection_timeout);
+                    self.set_state(ConnectionState::Reconnecting {
+                        attempt: conn.failures,
+                        max: self.retry_attempts,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Ouvre une connexion en respectant connection_timeout
+    async fn connect(&self) -> std::io::Result<UnixStream> {
+        match tokio::time::timeout(self.connection_timeout, UnixStream::connect(&self.socket_path)).await {
+            Ok(result) => result,
+            Err(_) => Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                format!("timed out after {}s", self.connection_timeout.as_secs()),
+            )),
+        }
+    }
+
+    /// Publie un nouvel état (uniquement s'il change)
+    fn set_state(&self, state: ConnectionState) {
+        self.state_tx.send_if_modified(|current| {
+            if *current == state {
+                false
+            } else {
+                *current = state;
+                true
+            }
+        });
+    }
+
+    /// Test la connexion au socket
+    pub async fn test_connection(
+        &self,
+        message: Option<String>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let test_log = LogMessage::new(
+            "test".to_string(),
+            message.unwrap_or_else(|| "Test message from log-agent".to_string()),
+            crate::types::LogSource::Stdout,
+            std::process::id(),
+        );
+
+        let mut stream = self.connect().await?;
+        stream.write_all(serialize(&test_log)?.as_bytes()).await?;
+
+        Ok(())
+    }
+}
+
+/// Attend l'échéance du lot (jamais si aucun lot en cours)
+async fn sleep_until_deadline(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Écrit tout le texte, ou retourne le nombre d'octets écrits avant l'échec
+async fn write_lines(stream: &mut UnixStream, text: &str) -> Result<(), usize> {
+    let bytes = text.as_bytes();
+    let mut written = 0;
+    while written < bytes.len() {
+        match stream.write(&bytes[written..]).await {
+            Ok(0) => return Err(written),
+            Ok(n) => written += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(_) => return Err(written),
+        }
+    }
+    Ok(())
+}
+
+/// Octets des lignes complètes parmi les `written` premiers octets
+fn complete_lines(text: &str, written: usize) -> usize {
+    text[..written].rfind('\n').map_or(0, |i| i + 1)
+}
+
+/// Lignes du lot à garder dans le spool: pas encore reçues (à partir de l'octet `sent`) et marquées
+fn unsent_lines<'a>(payload: &'a str, spooled: &'a [bool], sent: usize) -> impl Iterator<Item = &'a str> {
+    let skipped = payload[..sent].matches('\n').count();
+    payload[sent..]
+        .split_inclusive('\n')
+        .zip(&spooled[skipped..])
+        .filter_map(|(line, &keep)| keep.then_some(line))
+}
+
+/// Sérialisation en JSON + nouvelle ligne
+fn serialize(log: &LogMessage) -> Result<String, serde_json::Error> {
+    Ok(format!("{}\n", serde_json::to_string(log)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_received_before_a_failure_are_not_spooled() {
+        let payload = "{\"a\":1}\n{\"metrics\":2}\n{\"b\":3}\n{\"c\":4}\n";
+        let spooled = [true, false, true, true];
+
+        // Coupé au milieu de la 3e ligne: elle repart en entier, la mesure est écartée
+        let sent = complete_lines(payload, payload.find("3}").unwrap());
+        assert_eq!(&payload[..sent], "{\"a\":1}\n{\"metrics\":2}\n");
+        let lines: Vec<&str> = unsent_lines(payload, &spooled, sent).collect();
+        assert_eq!(lines, ["{\"b\":3}\n", "{\"c\":4}\n"]);
+
+        // Rien reçu: tout ce qui est marqué
+        assert_eq!(unsent_lines(payload, &spooled, complete_lines(payload, 3)).count(), 3);
+    }
+
+    #[tokio::test]
+    async fn partial_write_reports_bytes_received() {
+        let (mut writer, reader) = UnixStream::pair().unwrap();
+        drop(reader);
+        assert_eq!(write_lines(&mut writer, "{}\n").await, Err(0));
+    }
+}
+
@@ -0,0 +1,400 @@
+use crate::config::Config;
+use crate::types::LogMessage;
+use chrono::{DateTime, Utc};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::os::fd::AsRawFd;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Spool disque des logs qui n'ont pas pu être délivrés au serveur MCP
+///
+/// Un fichier JSONL par projet, rejoué dans l'ordre à la reconnexion.
+/// La taille est bornée (les plus anciens sont écartés) et les entrées
+/// plus vieilles que max_age ne sont pas rejouées. Les lignes illisibles
+/// sont déplacées dans un fichier à part (`<projet>.rejected.jsonl`).
+///
+/// Plusieurs agents peuvent partager un projet (`run` et `tail`): ajouts,
+/// réécritures et rejeu se font sous un verrou `flock` (`<projet>.jsonl.lock`).
+pub struct Spool {
+    path: PathBuf,
+    max_bytes: u64,
+    max_age: Duration,
+    /// Fichier ouvert en ajout, gardé entre deux lignes
+    writer: Mutex<Option<Writer>>,
+}
+
+/// Fichier du spool ouvert en ajout et sa taille
+struct Writer {
+    file: File,
+    size: u64,
+}
+
+impl Writer {
+    /// Le handle désigne-t-il encore le spool ? (un autre agent a pu le réécrire)
+    ///
+    /// Met aussi à jour la taille, que les autres agents ont pu faire grandir.
+    fn refresh(&mut self, path: &Path) -> bool {
+        let (Ok(open), Ok(current)) = (self.file.metadata(), fs::metadata(path)) else {
+            return false;
+        };
+        self.size = open.len();
+        open.dev() == current.dev() && open.ino() == current.ino()
+    }
+}
+
+/// Verrou exclusif sur le spool d'un projet, relâché à la fermeture du fichier (drop)
+pub struct SpoolLock {
+    _file: File,
+}
+
+impl Spool {
+    /// Crée le spool du projet courant, None si désactivé dans [spool]
+    pub fn from_config(config: &Config) -> Option<Self> {
+        if !config.spool.enabled {
+            return None;
+        }
+
+        let dir = config
+            .spool
+            .dir
+            .as_ref()
+            .map(PathBuf::from)
+            .or_else(default_spool_dir)?;
+
+        Some(Self::new(
+            dir.join(format!("{}.jsonl", sanitize(&config.agent.default_project))),
+            config.spool.max_size_mb.saturating_mul(1024 * 1024),
+            Duration::from_secs(config.spool.max_age_secs),
+        ))
+    }
+
+    fn new(path: PathBuf, max_bytes: u64, max_age: Duration) -> Self {
+        Self { path, max_bytes, max_age, writer: Mutex::new(None) }
+    }
+
+    /// Chemin du fichier de spool
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// Fichier des lignes illisibles, écartées du rejeu
+    pub fn rejected_path(&self) -> PathBuf {
+        self.path.with_extension("rejected.jsonl")
+    }
+
+    /// Nombre de logs en attente dans le spool
+    pub fn pending_count(&self) -> usize {
+        count_lines(&self.path)
+    }
+
+    /// Nombre de lignes illisibles mises de côté
+    pub fn rejected_count(&self) -> usize {
+        count_lines(&self.rejected_path())
+    }
+
+    /// Ajoute une ligne JSON (terminée par \n) à la fin du spool
+    pub fn append(&self, line: &str) -> io::Result<()> {
+        // Une ligne seule plus grande que la limite n'est pas conservée
+        if line.len() as u64 > self.max_bytes {
+            return Ok(());
+        }
+
+        let _lock = self.lock(true)?;
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        let current = writer.take().and_then(|mut w| w.refresh(&self.path).then_some(w));
+        let mut w = match current {
+            Some(w) => w,
+            None => self.open()?,
+        };
+        if w.size + line.len() as u64 > self.max_bytes {
+            // Le fichier est réécrit: le handle est rouvert sur le nouveau
+            drop(w);
+            self.make_room(line.len() as u64)?;
+            w = self.open()?;
+        }
+
+        // En cas d'erreur le handle est abandonné, rouvert à la ligne suivante
+        w.file.write_all(line.as_bytes())?;
+        w.size += line.len() as u64;
+        *writer = Some(w);
+        Ok(())
+    }
+
+    /// Verrou du rejeu, None si un autre agent du projet le détient déjà
+    ///
+    /// À garder de `load` jusqu'au `keep` qui suit: les ajouts des autres
+    /// agents attendent, ils ne sont ni perdus par la réécriture ni rejoués deux fois.
+    pub fn try_lock(&self) -> io::Result<Option<SpoolLock>> {
+        self.lock(false)
+    }
+
+    /// Lignes encore valides, dans l'ordre, sans les retirer du spool
+    ///
+    /// Le spool n'est vidé (`keep`) qu'une fois les lignes envoyées: un arrêt
+    /// pendant le rejeu ne perd rien. Les lignes illisibles partent dans
+    /// `rejected_path()`. Appelé sous `try_lock`.
+    pub fn load(&self) -> io::Result<Vec<String>> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let cutoff = Utc::now() - chrono::Duration::from_std(self.max_age).unwrap_or_default();
+        let mut lines = Vec::new();
+        let mut rejected = String::new();
+        for line in content.lines().filter(|line| !line.is_empty()) {
+            match timestamp(line) {
+                Some(timestamp) if timestamp < cutoff => {}
+                Some(_) => lines.push(format!("{}\n", line)),
+                None => {
+                    rejected.push_str(line);
+                    rejected.push('\n');
+                }
+            }
+        }
+
+        if !rejected.is_empty() {
+            let mut file = OpenOptions::new().create(true).append(true).open(self.rejected_path())?;
+            file.write_all(rejected.as_bytes())?;
+        }
+        Ok(lines)
+    }
+
+    /// Remplace le contenu du spool par les lignes pas encore rejouées (supprimé si vide, sous `try_lock`)
+    pub fn keep(&self, lines: &[String]) -> io::Result<()> {
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        *writer = None;
+
+        if lines.is_empty() {
+            return match fs::remove_file(&self.path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            };
+        }
+        self.replace(&lines.concat())
+    }
+
+    /// Pose le verrou du projet (None si `wait` est faux et qu'un autre agent le détient)
+    fn lock(&self, wait: bool) -> io::Result<Option<SpoolLock>> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.path.with_extension("jsonl.lock"))?;
+
+        let operation = if wait { libc::LOCK_EX } else { libc::LOCK_EX | libc::LOCK_NB };
+        loop {
+            // SAFETY: le descripteur reste valide tant que `file` existe
+            if unsafe { libc::flock(file.as_raw_fd(), operation) } == 0 {
+                return Ok(Some(SpoolLock { _file: file }));
+            }
+            let error = io::Error::last_os_error();
+            match error.kind() {
+                io::ErrorKind::Interrupted => continue,
+                io::ErrorKind::WouldBlock => return Ok(None),
+                _ => return Err(error),
+            }
+        }
+    }
+
+    /// Ouvre le fichier en ajout (en terminant une ligne interrompue par un arrêt brutal)
+    fn open(&self) -> io::Result<Writer> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = OpenOptions::new().create(true).read(true).append(true).open(&self.path)?;
+        let mut size = file.metadata()?.len();
+        if size > 0 {
+            let mut last = [0u8];
+            file.seek(SeekFrom::End(-1))?;
+            file.read_exact(&mut last)?;
+            if last[0] != b'\n' {
+                file.write_all(b"\n")?;
+                size += 1;
+            }
+        }
+        Ok(Writer { file, size })
+    }
+
+    /// Écarte les lignes les plus anciennes pour laisser la place à `needed` octets
+    ///
+    /// On redescend à 75% de la limite pour ne pas réécrire le fichier à chaque ajout.
+    fn make_room(&self, needed: u64) -> io::Result<()> {
+        let content = fs::read_to_string(&self.path).unwrap_or_default();
+        let target = (self.max_bytes / 4 * 3).saturating_sub(needed);
+
+        let mut size = content.len() as u64;
+        let mut start = 0;
+        for line in content.split_inclusive('\n') {
+            if size <= target {
+                break;
+            }
+            size -= line.len() as u64;
+            start += line.len();
+        }
+
+        self.replace(&content[start..])
+    }
+
+    /// Réécrit le spool via un fichier temporaire renommé (jamais à moitié écrit)
+    fn replace(&self, content: &str) -> io::Result<()> {
+        let tmp = self.path.with_extension("jsonl.tmp");
+        fs::write(&tmp, content)?;
+        fs::rename(&tmp, &self.path)
+    }
+}
+
+/// Dossier par défaut: ~/.cache/mcp-log-agent/spool
+fn default_spool_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|mut path| {
+        path.push("mcp-log-agent");
+        path.push("spool");
+        path
+    })
+}
+
+/// Nom de fichier sûr pour un nom de projet
+fn sanitize(project: &str) -> String {
+    project
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '_' })
+        .collect()
+}
+
+/// Horodatage d'une ligne du spool (None si elle est illisible)
+fn timestamp(line: &str) -> Option<DateTime<Utc>> {
+    let log = serde_json::from_str::<LogMessage>(line).ok()?;
+    DateTime::parse_from_rfc3339(&log.data.timestamp)
+        .ok()
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+}
+
+fn count_lines(path: &Path) -> usize {
+    fs::read_to_string(path)
+        .map(|content| content.lines().filter(|l| !l.is_empty()).count())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::LogSource;
+
+    /// Spool dans un dossier temporaire propre au test
+    fn spool(name: &str, max_bytes: u64) -> Spool {
+        let dir = std::env::temp_dir().join(format!("mcp-log-agent-spool-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        Spool::new(dir.join("test.jsonl"), max_bytes, Duration::from_secs(3600))
+    }
+
+    /// Horodatage commun: une même ligne sérialisée deux fois est identique
+    static NOW: std::sync::LazyLock<String> = std::sync::LazyLock::new(|| Utc::now().to_rfc3339());
+
+    fn line(message: &str) -> String {
+        let mut log = LogMessage::new("test".to_string(), message.to_string(), LogSource::Stdout, 1);
+        log.data.timestamp = NOW.clone();
+        format!("{}\n", serde_json::to_string(&log).unwrap())
+    }
+
+    #[test]
+    fn lines_stay_until_kept() {
+        let spool = spool("keep", 1024 * 1024);
+        spool.append(&line("a")).unwrap();
+        spool.append(&line("b")).unwrap();
+
+        let lines = spool.load().unwrap();
+        assert_eq!(lines, vec![line("a"), line("b")]);
+        // Rien n'est retiré tant que le rejeu n'a pas confirmé l'envoi
+        assert_eq!(spool.pending_count(), 2);
+
+        spool.keep(&lines[1..]).unwrap();
+        assert_eq!(spool.load().unwrap(), vec![line("b")]);
+
+        spool.keep(&[]).unwrap();
+        assert!(!spool.path().exists());
+    }
+
+    #[test]
+    fn append_after_keep_reopens_file() {
+        let spool = spool("reopen", 1024 * 1024);
+        spool.append(&line("a")).unwrap();
+        spool.keep(&[]).unwrap();
+        spool.append(&line("b")).unwrap();
+        assert_eq!(spool.load().unwrap(), vec![line("b")]);
+    }
+
+    #[test]
+    fn unreadable_lines_are_set_aside() {
+        let spool = spool("rejected", 1024 * 1024);
+        // Ligne interrompue par un arrêt brutal, sans \n final
+        fs::write(spool.path(), format!("{}not json\n{{\"trunc", line("a"))).unwrap();
+        spool.append(&line("b")).unwrap();
+
+        assert_eq!(spool.load().unwrap(), vec![line("a"), line("b")]);
+        assert_eq!(spool.rejected_count(), 2);
+        let rejected = fs::read_to_string(spool.rejected_path()).unwrap();
+        assert_eq!(rejected, "not json\n{\"trunc\n");
+    }
+
+    #[test]
+    fn expired_lines_are_not_replayed() {
+        let spool = spool("expired", 1024 * 1024);
+        let mut old = LogMessage::new("test".to_string(), "old".to_string(), LogSource::Stdout, 1);
+        old.data.timestamp = (Utc::now() - chrono::Duration::hours(2)).to_rfc3339();
+        spool.append(&format!("{}\n", serde_json::to_string(&old).unwrap())).unwrap();
+        spool.append(&line("new")).unwrap();
+
+        assert_eq!(spool.load().unwrap(), vec![line("new")]);
+        assert_eq!(spool.rejected_count(), 0);
+    }
+
+    #[test]
+    fn oldest_lines_make_room() {
+        let size = line("0").len() as u64;
+        let spool = spool("room", size * 4);
+        for i in 0..6 {
+            spool.append(&line(&i.to_string())).unwrap();
+        }
+
+        let lines = spool.load().unwrap();
+        assert!(fs::metadata(spool.path()).unwrap().len() <= size * 4);
+        assert_eq!(lines.last(), Some(&line("5")));
+        assert!(!lines.contains(&line("0")));
+    }
+
+    #[test]
+    fn line_larger_than_limit_is_dropped() {
+        let spool = spool("large", 10);
+        spool.append(&line("too big")).unwrap();
+        assert_eq!(spool.pending_count(), 0);
+    }
+
+    #[test]
+    fn agents_sharing_a_project_keep_each_others_lines() {
+        let run = spool("shared", 1024 * 1024);
+        let tail = Spool::new(run.path().clone(), 1024 * 1024, Duration::from_secs(3600));
+        run.append(&line("a")).unwrap();
+        tail.append(&line("b")).unwrap();
+
+        // `run` rejoue: `tail` ne peut pas rejouer les mêmes lignes en même temps
+        let lock = run.try_lock().unwrap().expect("free lock");
+        assert!(tail.try_lock().unwrap().is_none());
+        assert_eq!(run.load().unwrap(), vec![line("a"), line("b")]);
+        run.keep(&[]).unwrap();
+        drop(lock);
+
+        // Le fichier a été remplacé: `tail` écrit dans le nouveau, pas dans l'ancien
+        tail.append(&line("c")).unwrap();
+        assert_eq!(run.load().unwrap(), vec![line("c")]);
+    }
+}
+
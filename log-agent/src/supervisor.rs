@@ -1,99 +1,170 @@
-use crate::config::Config;
-use crate::types::{LogMessage, LogSource};
+use crate::capture::stop::{terminate, StopOutcome, StopPolicy};
+use crate::capture::{describe_exit, exit_code, spawn_captured, CaptureContext};
+use crate::deps::{DependencyStatus, LifecycleHandle, Lifecycles};
+use crate::metrics::TreeSampler;
+use crate::pipeline::Pipeline;
+use crate::process::ProcessSpec;
+use crate::restart::{RestartDecision, RestartTracker};
+use crate::types::{LogMessage, ResourceUsage};
 use std::process::ExitStatus;
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::{Child, Command};
+use std::time::{Duration, Instant};
+use tokio::process::Child;
 use tokio::sync::mpsc;
-use std::process::Stdio;
 
 /// Superviseur de processus pour la TUI
 pub struct Supervisor {
     project: String,
-    command: Vec<String>,
-    config: Config,
+    spec: ProcessSpec,
+    pipeline: Pipeline,
+    /// Nom ajouté aux logs (mode multi-process)
+    process: Option<String>,
     child: Option<Child>,
+    /// Groupe de processus du dernier lancement (peut survivre à l'enfant direct)
+    pgid: Option<u32>,
+    stop_policy: StopPolicy,
+    /// Redémarrages automatiques (politique restart)
+    restarts: RestartTracker,
+    /// Redémarrage automatique programmé: échéance et numéro de tentative
+    pending_restart: Option<(Instant, u32)>,
     stdout_task: Option<tokio::task::JoinHandle<()>>,
     stderr_task: Option<tokio::task::JoinHandle<()>>,
+    resize_task: Option<tokio::task::JoinHandle<()>>,
+    ready_task: Option<tokio::task::JoinHandle<()>>,
+    /// Avancement partagé avec les autres processus (depends_on)
+    lifecycles: Option<Lifecycles>,
+    lifecycle: Option<LifecycleHandle>,
+    /// Mesure des ressources du lancement en cours
+    sampler: Option<TreeSampler>,
+    /// Premier lancement pas encore fait (dépendances non remplies)
+    waiting_deps: bool,
 }
 
 impl Supervisor {
-    pub fn new(project: String, command: Vec<String>, config: Config) -> Self {
+    pub fn new(project: String, spec: ProcessSpec, pipeline: Pipeline) -> Self {
+        let stop_policy = StopPolicy::from_config(&spec.config.agent).unwrap_or_default();
+        let restarts = RestartTracker::from_config(&spec.config.agent);
         Self {
             project,
-            command,
-            config,
+            spec,
+            pipeline,
+            process: None,
             child: None,
+            pgid: None,
+            stop_policy,
+            restarts,
+            pending_restart: None,
             stdout_task: None,
             stderr_task: None,
+            resize_task: None,
+            ready_task: None,
+            lifecycles: None,
+            lifecycle: None,
+            sampler: None,
+            waiting_deps: true,
         }
     }
 
+    /// Processus supervisé
+    pub fn spec(&self) -> &ProcessSpec {
+        &self.spec
+    }
+
+    /// Tague les logs avec le nom du processus (mode multi-process)
+    pub fn with_process_tag(mut self) -> Self {
+        self.process = Some(self.spec.label());
+        self
+    }
+
+    /// Partage l'avancement avec les autres processus (attente des depends_on)
+    pub fn with_lifecycles(mut self, lifecycles: Lifecycles) -> Self {
+        self.lifecycle = Some(lifecycles.handle(&self.spec.label()));
+        self.lifecycles = Some(lifecycles);
+        self
+    }
+
+    /// Démarre le processus dès que ses dépendances sont remplies
+    ///
+    /// Retourne None tant qu'il faut attendre (à rappeler plus tard), sinon le
+    /// résultat du lancement. Une dépendance en échec annule le lancement.
+    pub async fn start_when_ready(
+        &mut self,
+        tx: mpsc::Sender<LogMessage>,
+    ) -> Option<Result<u32, Box<dyn std::error::Error + Send + Sync>>> {
+        if let Some(lifecycles) = &self.lifecycles {
+            match lifecycles.check(&self.spec.depends_on) {
+                DependencyStatus::Waiting => return None,
+                DependencyStatus::Failed(reason) => {
+                    self.waiting_deps = false;
+                    if let Some(lifecycle) = &self.lifecycle {
+                        lifecycle.not_started(reason.clone());
+                    }
+                    return Some(Err(reason.into()));
+                }
+                DependencyStatus::Satisfied => {}
+            }
+        }
+        Some(self.start(tx).await)
+    }
+
+    /// Vrai si le premier lancement n'a pas encore eu lieu
+    pub fn waiting_deps(&self) -> bool {
+        self.waiting_deps
+    }
+
     /// Démarre le processus et retourne son PID
     pub async fn start(
         &mut self,
         tx: mpsc::Sender<LogMessage>,
     ) -> Result<u32, Box<dyn std::error::Error + Send + Sync>> {
-        if self.command.is_empty() {
-            return Err("No command provided".into());
-        }
+        self.waiting_deps = false;
+        let ctx = CaptureContext {
+            project: self.project.clone(),
+            pid: 0,
+            config: self.spec.config.clone(),
+            pipeline: self.pipeline.clone(),
+            echo: false,
+            process: self.process.clone(),
+            prefix: None,
+            ready: None,
+            lifecycle: self.lifecycle.clone(),
+        };
 
-        let program = &self.command[0];
-        let args = &self.command[1..];
-
-        // Spawn le processus
-        let mut child = Command::new(program)
-            .args(args)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()?;
-
-        let pid = child.id().ok_or("Failed to get PID")?;
-
-        // Prendre stdout et stderr
-        let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
-        let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
-
-        // Spawn les tâches de capture
-        let project_stdout = self.project.clone();
-        let config_stdout = self.config.clone();
-        let tx_stdout = tx.clone();
-        self.stdout_task = Some(tokio::spawn(async move {
-            capture_stream(
-                BufReader::new(stdout),
-                project_stdout,
-                LogSource::Stdout,
-                pid,
-                tx_stdout,
-                config_stdout,
-            )
-            .await;
-        }));
-
-        let project_stderr = self.project.clone();
-        let config_stderr = self.config.clone();
-        let tx_stderr = tx;
-        self.stderr_task = Some(tokio::spawn(async move {
-            capture_stream(
-                BufReader::new(stderr),
-                project_stderr,
-                LogSource::Stderr,
-                pid,
-                tx_stderr,
-                config_stderr,
-            )
-            .await;
-        }));
-
-        self.child = Some(child);
+        let process = match spawn_captured(&self.spec, ctx, tx) {
+            Ok(process) => process,
+            Err(e) => {
+                if let Some(lifecycle) = &self.lifecycle {
+                    lifecycle.not_started(e.to_string());
+                }
+                return Err(e);
+            }
+        };
+        let pid = process.pid;
+
+        self.child = Some(process.child);
+        self.pgid = Some(pid);
+        self.stdout_task = Some(process.stdout_task);
+        self.stderr_task = Some(process.stderr_task);
+        self.resize_task = process.resize_task;
+        self.ready_task = process.ready_task;
+        self.sampler = Some(TreeSampler::new(pid));
 
         Ok(pid)
     }
 
-    /// Arrête le processus
-    pub async fn stop(&mut self) {
-        if let Some(ref mut child) = self.child {
-            let _ = child.kill().await;
-            let _ = child.wait().await;
+    /// Arrête le processus et tout son groupe
+    ///
+    /// Envoie stop_signal, attend stop_timeout puis SIGKILL sur le groupe.
+    /// Retourne l'étape qui a mis fin au processus.
+    pub async fn stop(&mut self) -> StopOutcome {
+        let outcome = match self.pgid.take() {
+            Some(pgid) => terminate(self.child.as_mut(), pgid, self.stop_policy).await,
+            None => StopOutcome::NotRunning,
+        };
+        if let Some(task) = self.resize_task.take() {
+            task.abort();
+        }
+        if let Some(task) = self.ready_task.take() {
+            task.abort();
         }
         
         // Attendre que les tâches de capture se terminent AVEC TIMEOUT
@@ -112,17 +183,90 @@ impl Supervisor {
         }
         
         self.child = None;
+        outcome
     }
 
-    /// Redémarre le processus
+    /// Redémarre le processus (retourne aussi comment l'ancien s'est arrêté)
+    ///
+    /// Redémarrage manuel: le compteur de redémarrages automatiques repart
+    /// de zéro, ce qui sort aussi du crash loop.
     pub async fn restart(
         &mut self,
         tx: mpsc::Sender<LogMessage>,
-    ) -> Result<u32, Box<dyn std::error::Error + Send + Sync>> {
-        self.stop().await;
+    ) -> (StopOutcome, Result<u32, Box<dyn std::error::Error + Send + Sync>>) {
+        self.restarts.reset();
+        self.pending_restart = None;
+        self.relaunch(tx).await
+    }
+
+    /// Remet le processus (arrêté) en attente de ses dépendances
+    ///
+    /// Utilisé pour un redémarrage complet: le lancement repasse par
+    /// `start_when_ready`, dans l'ordre et selon les conditions des dépendances.
+    pub fn rearm(&mut self) {
+        self.restarts.reset();
+        self.pending_restart = None;
+        self.waiting_deps = true;
+        if let Some(lifecycle) = &self.lifecycle {
+            lifecycle.reset();
+        }
+    }
+
+    /// Programme un redémarrage automatique selon la politique restart
+    pub fn schedule_restart(&mut self, status: &ExitStatus) -> RestartDecision {
+        let decision = self.restarts.on_exit(status);
+        if let RestartDecision::Restart { attempt, delay } = decision {
+            self.pending_restart = Some((Instant::now() + delay, attempt));
+        }
+        decision
+    }
+
+    /// Vrai si un redémarrage automatique est programmé
+    pub fn restart_pending(&self) -> bool {
+        self.pending_restart.is_some()
+    }
+
+    /// Échéance du redémarrage programmé
+    pub fn restart_at(&self) -> Option<Instant> {
+        self.pending_restart.map(|(at, _)| at)
+    }
+
+    /// Lance le redémarrage programmé si son délai est écoulé
+    ///
+    /// Retourne None si rien n'est dû, sinon le numéro de tentative et le
+    /// résultat du lancement.
+    pub async fn restart_if_due(
+        &mut self,
+        tx: mpsc::Sender<LogMessage>,
+    ) -> Option<(u32, Result<u32, Box<dyn std::error::Error + Send + Sync>>)> {
+        let (at, attempt) = self.pending_restart?;
+        if Instant::now() < at {
+            return None;
+        }
+        self.pending_restart = None;
+        let (_, result) = self.relaunch(tx).await;
+        Some((attempt, result))
+    }
+
+    /// Nombre max de redémarrages automatiques dans la fenêtre
+    pub fn max_restarts(&self) -> u32 {
+        self.restarts.max_restarts()
+    }
+
+    /// Arrête ce qui reste de l'ancien lancement puis relance
+    async fn relaunch(
+        &mut self,
+        tx: mpsc::Sender<LogMessage>,
+    ) -> (StopOutcome, Result<u32, Box<dyn std::error::Error + Send + Sync>>) {
+        let outcome = self.stop().await;
         // Petit délai pour s'assurer que tout est clean
         tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-        self.start(tx).await
+        (outcome, self.start(tx).await)
+    }
+
+    /// Délai de grâce maximal d'un arrêt
+    pub fn stop_grace(&self) -> std::time::Duration {
+        self.stop_policy.grace
     }
 
     /// Vérifie si le processus est terminé (non-bloquant)
@@ -132,10 +276,21 @@ impl Supervisor {
                 Ok(Some(status)) => {
                     // Processus terminé, nettoyer l'état
                     self.child = None;
+                    if let Some(task) = self.resize_task.take() {
+                        task.abort();
+                    }
+                    if let Some(task) = self.ready_task.take() {
+                        task.abort();
+                    }
+                    if let Some(lifecycle) = &self.lifecycle {
+                        lifecycle.exited(exit_code(&status));
+                    }
                     
                     // Les tâches stdout/stderr vont se terminer naturellement à EOF
                     // On les garde pour finir de capturer les derniers logs
                     // Elles seront nettoyées au prochain restart() ou stop()
+                    // Le groupe est conservé: des descendants encore vivants
+                    // seront arrêtés au prochain restart() ou stop()
                     
                     Some(status)
                 }
@@ -146,40 +301,68 @@ impl Supervisor {
         }
     }
 
+    /// Événement "process exited" à envoyer au serveur MCP
+    pub fn exit_event(&self, status: &ExitStatus) -> LogMessage {
+        LogMessage::exited(
+            self.project.clone(),
+            format!("Process {}", describe_exit(status)),
+            self.pgid.unwrap_or_default(),
+            exit_code(status),
+        )
+        .with_process(self.process.clone())
+    }
+
+    /// Événement "process restarted" à envoyer au serveur MCP
+    pub fn restart_event(&self, attempt: u32) -> LogMessage {
+        LogMessage::restarted(
+            self.project.clone(),
+            format!("Process restarted (attempt {}/{})", attempt, self.max_restarts()),
+            self.pgid.unwrap_or_default(),
+            attempt,
+        )
+        .with_process(self.process.clone())
+    }
+
+    /// Événement "crash loop" à envoyer au serveur MCP
+    pub fn crash_loop_event(&self, restarts: u32, window: Duration) -> LogMessage {
+        LogMessage::crash_loop(
+            self.project.clone(),
+            format!(
+                "Crash loop: restarted {} times in {}s, giving up",
+                restarts,
+                window.as_secs()
+            ),
+            self.pgid.unwrap_or_default(),
+        )
+        .with_process(self.process.clone())
+    }
+
+    /// Échantillonneur des ressources du processus et de ses descendants
+    ///
+    /// Retiré le temps d'une mesure (faite hors de la boucle async, voir
+    /// `metrics::sample_all`), puis rendu avec `return_sampler`. None si le
+    /// processus ne tourne pas.
+    pub fn take_sampler(&mut self) -> Option<TreeSampler> {
+        self.child.as_ref()?;
+        self.sampler.take()
+    }
+
+    /// Rend l'échantillonneur après une mesure (ignoré si le processus a été relancé entre-temps)
+    pub fn return_sampler(&mut self, sampler: TreeSampler) {
+        if self.sampler.is_none() && self.pgid == Some(sampler.pgid()) {
+            self.sampler = Some(sampler);
+        }
+    }
+
+    /// Message "metrics" à envoyer au serveur MCP
+    pub fn metrics_event(&self, usage: ResourceUsage) -> LogMessage {
+        LogMessage::metrics(self.project.clone(), usage, self.pgid.unwrap_or_default())
+            .with_process(self.process.clone())
+    }
+
     /// Vérifie si le processus est en cours d'exécution
     #[allow(dead_code)]
     pub fn is_running(&self) -> bool {
         self.child.is_some()
     }
 }
-
-/// Capture un stream ligne par ligne et envoie les logs
-async fn capture_stream<R>(
-    mut reader: BufReader<R>,
-    project: String,
-    source: LogSource,
-    pid: u32,
-    tx: mpsc::Sender<LogMessage>,
-    _config: Config,
-) where
-    R: tokio::io::AsyncRead + Unpin,
-{
-    let mut line = String::new();
-
-    loop {
-        line.clear();
-        match reader.read_line(&mut line).await {
-            Ok(0) => break, // EOF
-            Ok(_) => {
-                let message = line.trim_end().to_string();
-                if !message.is_empty() {
-                    let log = LogMessage::new(project.clone(), message, source.clone(), pid);
-                    if tx.send(log).await.is_err() {
-                        break;
-                    }
-                }
-            }
-            Err(_) => break,
-        }
-    }
-}
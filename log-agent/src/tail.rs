@@ -0,0 +1,423 @@
+use crate::capture::{capture_stream, CaptureContext};
+use crate::config::Config;
+use crate::pipeline::Pipeline;
+use crate::process;
+use crate::types::{LogMessage, LogSource};
+use clap::ValueEnum;
+use owo_colors::OwoColorize;
+use std::collections::{HashMap, HashSet};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWrite, AsyncWriteExt, BufReader, SeekFrom};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Intervalle entre deux lectures d'un fichier suivi
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Intervalle entre deux recherches de nouveaux fichiers correspondant aux globs
+const RESCAN_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Taille des blocs lus (et relus à rebours pour --lines)
+const CHUNK_SIZE: usize = 8192;
+
+/// Comment un fichier suivi apparaît dans les logs
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum TailTag {
+    /// Nom du fichier comme nom de processus (comme en mode multi-process)
+    #[default]
+    Source,
+    /// Nom du fichier comme projet (un projet par fichier côté serveur MCP)
+    Project,
+}
+
+/// Position de départ dans les fichiers présents au lancement
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StartAt {
+    /// Seulement les nouvelles lignes
+    End,
+    /// Tout le contenu existant
+    Beginning,
+    /// Les N dernières lignes
+    LastLines(usize),
+}
+
+/// Fichiers suivis par `mcp-log-agent tail`
+#[derive(Debug, Clone)]
+pub struct TailSet {
+    patterns: Vec<String>,
+    files: Vec<PathBuf>,
+    start: StartAt,
+    tag: TailTag,
+    /// Nom et "commande" des fichiers trouvés après le lancement (TUI)
+    new_files: Option<mpsc::UnboundedSender<(String, Vec<String>)>>,
+}
+
+impl TailSet {
+    /// Résout les globs (erreur si un pattern est invalide ou si aucun fichier ne correspond)
+    pub fn new(patterns: Vec<String>, start: StartAt, tag: TailTag) -> Result<Self, String> {
+        let files = expand(&patterns)?;
+        if files.is_empty() {
+            return Err(format!("No file matches {}", patterns.join(", ")));
+        }
+        Ok(Self { patterns, files, start, tag, new_files: None })
+    }
+
+    /// Fichiers trouvés au lancement
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// Signale les fichiers trouvés après le lancement (nom et "commande")
+    pub fn with_new_files(mut self, tx: mpsc::UnboundedSender<(String, Vec<String>)>) -> Self {
+        self.new_files = Some(tx);
+        self
+    }
+
+    /// Nom et "commande" d'un fichier suivi, tels qu'affichés dans la TUI
+    pub fn label(path: &Path) -> (String, Vec<String>) {
+        (Self::name(path), vec!["tail".to_string(), path.display().to_string()])
+    }
+
+    /// Nom sous lequel un fichier apparaît (nom de processus ou projet)
+    pub fn name(path: &Path) -> String {
+        path.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.display().to_string())
+    }
+
+    /// Suit les fichiers et ceux qui apparaissent ensuite (jusqu'à l'abandon de la tâche)
+    ///
+    /// Chaque ligne passe par la même capture que les processus (filtres,
+    /// affichage en mode echo, envoi au serveur MCP).
+    pub fn spawn(
+        self,
+        project: String,
+        config: Config,
+        pipeline: Pipeline,
+        echo: bool,
+        tx: mpsc::Sender<LogMessage>,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let width = self.files.iter().map(|f| Self::name(f).len()).max().unwrap_or(0);
+            let multi = self.files.len() > 1 || self.patterns.iter().any(|p| is_glob(p));
+            let mut tasks = Followers(HashMap::new());
+            let mut next_index = 0;
+            // Fichiers (device, inode) déjà lus, quel que soit leur nom actuel
+            let mut seen: HashSet<(u64, u64)> = HashSet::new();
+
+            let context = |path: &Path, index: usize| {
+                let name = Self::name(path);
+                CaptureContext {
+                    project: match self.tag {
+                        TailTag::Project => name.clone(),
+                        TailTag::Source => project.clone(),
+                    },
+                    pid: 0,
+                    config: config.clone(),
+                    pipeline: pipeline.clone(),
+                    echo,
+                    process: (self.tag == TailTag::Source).then(|| name.clone()),
+                    prefix: (echo && multi)
+                        .then(|| process::prefix(&name, index, width, config.output.colors)),
+                    ready: None,
+                    lifecycle: None,
+                }
+            };
+
+            for path in &self.files {
+                seen.extend(identity(path));
+                let task = follow(path.clone(), self.start, context(path, next_index), tx.clone());
+                tasks.0.insert(path.clone(), task);
+                next_index += 1;
+            }
+
+            // Nouveaux fichiers (rotation vers un nouveau nom, service démarré plus tard...)
+            loop {
+                tokio::time::sleep(RESCAN_INTERVAL).await;
+                let Ok(files) = expand(&self.patterns) else { continue };
+
+                // Fichiers disparus (globs): plus suivis, leur nom pourra resservir
+                tasks.0.retain(|path, task| {
+                    let keep = files.contains(path);
+                    if !keep {
+                        task.abort();
+                    }
+                    keep
+                });
+
+                // Fichier suivi remplacé par un nouveau (rotation): son inode
+                // actuel est lu par le suivi existant. Les inodes qui ne
+                // correspondent plus à aucun fichier sont oubliés.
+                let present: HashSet<(u64, u64)> = files.iter().filter_map(|path| identity(path)).collect();
+                seen.extend(tasks.0.keys().filter_map(|path| identity(path)));
+                seen.retain(|id| present.contains(id));
+
+                for path in files {
+                    if tasks.0.contains_key(&path) {
+                        continue;
+                    }
+                    // Ancien fichier renommé (logrotate): son contenu a déjà été lu
+                    let id = identity(&path);
+                    if id.is_some_and(|id| seen.contains(&id)) {
+                        continue;
+                    }
+                    seen.extend(id);
+
+                    if echo {
+                        eprintln!("{}", format!("✓ Following {}", path.display()).bright_black());
+                    }
+                    if let Some(new_files) = &self.new_files {
+                        let _ = new_files.send(Self::label(&path));
+                    }
+                    let ctx = context(&path, next_index);
+                    next_index += 1;
+                    tasks.0.insert(path.clone(), follow(path, StartAt::Beginning, ctx, tx.clone()));
+                }
+            }
+        })
+    }
+}
+
+/// Tâches de suivi des fichiers, arrêtées avec la tâche principale
+struct Followers(HashMap<PathBuf, JoinHandle<()>>);
+
+impl Drop for Followers {
+    fn drop(&mut self) {
+        for task in self.0.values() {
+            task.abort();
+        }
+    }
+}
+
+/// Device et inode d'un fichier (None s'il n'existe pas)
+fn identity(path: &Path) -> Option<(u64, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+/// Fichiers correspondant aux patterns (un chemin sans glob est gardé même s'il n'existe pas encore)
+fn expand(patterns: &[String]) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    for pattern in patterns {
+        if !is_glob(pattern) {
+            files.push(PathBuf::from(pattern));
+            continue;
+        }
+        let paths = glob::glob(pattern).map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))?;
+        files.extend(paths.flatten().filter(|path| path.is_file()));
+    }
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '['])
+}
+
+/// Suit un fichier: ses lignes passent par `capture_stream` via un pipe en mémoire
+fn follow(
+    path: PathBuf,
+    start: StartAt,
+    ctx: CaptureContext,
+    tx: mpsc::Sender<LogMessage>,
+) -> JoinHandle<()> {
+    let (reader, writer) = tokio::io::duplex(CHUNK_SIZE * 4);
+    tokio::spawn(async move {
+        tokio::join!(
+            follow_file(path, start, writer),
+            capture_stream(BufReader::new(reader), LogSource::Stdout, ctx, tx),
+        );
+    })
+}
+
+/// Fichier ouvert et position de lecture
+struct OpenFile {
+    file: File,
+    inode: u64,
+    pos: u64,
+    /// Dernier octet lu (détecte un fichier tronqué puis réécrit entre deux lectures)
+    last_byte: Option<u8>,
+}
+
+impl OpenFile {
+    /// Vrai si l'octet avant la position de lecture a changé (truncate suivi d'écritures)
+    async fn rewritten(&mut self) -> bool {
+        let Some(expected) = self.last_byte else {
+            return false;
+        };
+        let mut byte = [0u8; 1];
+        let read = async {
+            self.file.seek(SeekFrom::Start(self.pos - 1)).await?;
+            self.file.read_exact(&mut byte).await?;
+            self.file.seek(SeekFrom::Start(self.pos)).await
+        };
+        match read.await {
+            Ok(_) => byte[0] != expected,
+            Err(_) => true,
+        }
+    }
+}
+
+/// Copie le contenu ajouté au fichier, en suivant les rotations
+///
+/// - rename (logrotate par défaut): la fin de l'ancien fichier est lue, puis
+///   le nouveau fichier est suivi depuis le début
+/// - truncate (copytruncate): la lecture reprend au début
+/// - fichier absent: attendu jusqu'à ce qu'il (ré)apparaisse
+async fn follow_file<W: AsyncWrite + Unpin>(path: PathBuf, start: StartAt, mut out: W) {
+    let mut current = open(&path, start).await;
+    // Une ligne commencée dans l'ancien fichier est terminée avant de passer au suivant
+    let mut partial = false;
+
+    loop {
+        // Rotation ou troncature: vérifiées avant de lire la suite du fichier
+        let metadata = tokio::fs::metadata(&path).await.ok();
+        let rotated = match (&current, &metadata) {
+            (None, Some(_)) => true,
+            (Some(open_file), Some(metadata)) => metadata.ino() != open_file.inode,
+            _ => false,
+        };
+        let truncated = match (current.as_mut(), &metadata) {
+            (Some(open_file), Some(metadata)) if !rotated => {
+                metadata.len() < open_file.pos || open_file.rewritten().await
+            }
+            _ => false,
+        };
+
+        if rotated || truncated {
+            // Lignes écrites dans l'ancien fichier juste avant la rotation
+            if rotated && let Some(open_file) = current.as_mut() {
+                match drain(open_file, &mut out).await {
+                    Ok(Some(ends_with_newline)) => partial = !ends_with_newline,
+                    Ok(None) => {}
+                    Err(_) => return, // Capture arrêtée
+                }
+            }
+            if partial {
+                if out.write_all(b"\n").await.is_err() {
+                    return;
+                }
+                partial = false;
+            }
+            if truncated && let Some(open_file) = current.as_mut() {
+                open_file.pos = 0;
+                open_file.last_byte = None;
+                if open_file.file.seek(SeekFrom::Start(0)).await.is_err() {
+                    current = None;
+                }
+            } else {
+                current = open(&path, StartAt::Beginning).await;
+            }
+        }
+
+        if let Some(open_file) = current.as_mut() {
+            match drain(open_file, &mut out).await {
+                Ok(Some(ends_with_newline)) => partial = !ends_with_newline,
+                Ok(None) => {}
+                Err(_) => return,
+            }
+        }
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Ouvre le fichier à la position de départ (None s'il n'existe pas)
+async fn open(path: &Path, start: StartAt) -> Option<OpenFile> {
+    let mut file = File::open(path).await.ok()?;
+    let metadata = file.metadata().await.ok()?;
+    let pos = match start {
+        StartAt::Beginning => 0,
+        StartAt::End => metadata.len(),
+        StartAt::LastLines(n) => last_lines_offset(&mut file, metadata.len(), n).await.ok()?,
+    };
+    let last_byte = if pos > 0 {
+        let mut byte = [0u8; 1];
+        file.seek(SeekFrom::Start(pos - 1)).await.ok()?;
+        file.read_exact(&mut byte).await.ok()?;
+        Some(byte[0])
+    } else {
+        None
+    };
+    file.seek(SeekFrom::Start(pos)).await.ok()?;
+    Some(OpenFile { file, inode: metadata.ino(), pos, last_byte })
+}
+
+/// Position du début des `n` dernières lignes (lecture à rebours)
+async fn last_lines_offset(file: &mut File, len: u64, n: usize) -> std::io::Result<u64> {
+    // -n 0: aucune ligne existante, comme tail
+    if n == 0 {
+        return Ok(len);
+    }
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    let mut end = len;
+    let mut newlines = 0;
+
+    while end > 0 {
+        let start = end.saturating_sub(CHUNK_SIZE as u64);
+        let size = (end - start) as usize;
+        file.seek(SeekFrom::Start(start)).await?;
+        file.read_exact(&mut buf[..size]).await?;
+
+        for (i, byte) in buf[..size].iter().enumerate().rev() {
+            // Le saut de ligne final ne compte pas: il termine la dernière ligne
+            if *byte == b'\n' && start + i as u64 + 1 != len {
+                newlines += 1;
+                if newlines == n {
+                    return Ok(start + i as u64 + 1);
+                }
+            }
+        }
+        end = start;
+    }
+    Ok(0)
+}
+
+/// Copie ce qui a été ajouté depuis la dernière lecture
+///
+/// Retourne si le dernier octet copié est un saut de ligne (None si rien de nouveau).
+async fn drain<W: AsyncWrite + Unpin>(open_file: &mut OpenFile, out: &mut W) -> std::io::Result<Option<bool>> {
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    let mut last = None;
+    loop {
+        let n = match open_file.file.read(&mut buf).await {
+            Ok(0) | Err(_) => return Ok(last),
+            Ok(n) => n,
+        };
+        out.write_all(&buf[..n]).await?;
+        open_file.pos += n as u64;
+        open_file.last_byte = Some(buf[n - 1]);
+        last = Some(buf[n - 1] == b'\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn offset(name: &str, content: &str, n: usize) -> u64 {
+        let path = std::env::temp_dir()
+            .join(format!("mcp-log-agent-tail-{}-{}", name, std::process::id()));
+        std::fs::write(&path, content).unwrap();
+        let mut file = File::open(&path).await.unwrap();
+        let offset = last_lines_offset(&mut file, content.len() as u64, n).await.unwrap();
+        let _ = std::fs::remove_file(&path);
+        offset
+    }
+
+    #[tokio::test]
+    async fn last_lines_zero_starts_at_end() {
+        assert_eq!(offset("zero", "a\nb\nc\n", 0).await, 6);
+    }
+
+    #[tokio::test]
+    async fn last_lines_ignore_trailing_newline() {
+        assert_eq!(offset("two", "a\nb\nc\n", 2).await, 2);
+        assert_eq!(offset("unterminated", "a\nb\nc", 1).await, 4);
+        assert_eq!(offset("all", "a\nb\n", 10).await, 0);
+    }
+}
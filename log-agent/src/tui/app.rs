@@ -1,15 +1,41 @@
-use crate::config::Config;
-use crate::types::{LogLevel, LogMessage, LogSource};
+use crate::capture;
+use crate::config::{AnsiMode, Config};
+use crate::socket::ConnectionState;
+use crate::types::{LogLevel, LogMessage, LogSource, ProcessEvent, ResourceUsage};
 use regex::{Regex, RegexBuilder};
-use std::collections::VecDeque;
-use std::time::Instant;
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, Instant};
 
 /// État de l'application TUI
 #[derive(Debug, Clone, PartialEq)]
 pub enum AppState {
     Running,
+    /// Processus lancés, au moins un attend encore sa condition ready_when
+    Starting,
+    /// Toutes les conditions ready_when sont remplies
+    Ready,
     WaitingCountdown(u8),
     Restarting,
+    /// Tous les processus sont arrêtés, au moins un en crash loop
+    CrashLoop,
+}
+
+impl AppState {
+    /// Vrai tant que les processus tournent (Running, Starting ou Ready)
+    pub fn is_live(&self) -> bool {
+        matches!(self, AppState::Running | AppState::Starting | AppState::Ready)
+    }
+}
+
+/// Disponibilité d'un processus ayant une condition ready_when
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReadyState {
+    /// En attente depuis l'instant donné
+    Starting(Instant),
+    /// Prêt après la durée donnée
+    Ready(Duration),
+    /// Condition non remplie dans le délai
+    Failed,
 }
 
 /// Mode d'interaction
@@ -19,6 +45,8 @@ pub enum InputMode {
     Search,
     Help,
     SavePrompt,
+    /// Ligne complète affichée en plein écran
+    Detail,
 }
 
 /// Filtre par niveau de log
@@ -66,6 +94,37 @@ impl LevelFilter {
     }
 }
 
+/// Processus supervisé tel qu'affiché dans la TUI
+#[derive(Debug, Clone)]
+pub struct ProcessInfo {
+    /// Nom affiché (commande prédéfinie ou programme)
+    pub name: String,
+    pub command: Vec<String>,
+    pub pid: Option<u32>,
+    /// Échéance du redémarrage automatique programmé
+    pub restart_at: Option<Instant>,
+    /// Redémarrages automatiques abandonnés (trop de sorties dans la fenêtre)
+    pub crash_loop: bool,
+    /// Disponibilité (None si la commande n'a pas de ready_when)
+    pub ready: Option<ReadyState>,
+    /// Lancement en attente des dépendances (depends_on)
+    pub waiting: bool,
+}
+
+impl ProcessInfo {
+    pub fn new(name: String, command: Vec<String>) -> Self {
+        Self {
+            name,
+            command,
+            pid: None,
+            restart_at: None,
+            crash_loop: false,
+            ready: None,
+            waiting: false,
+        }
+    }
+}
+
 /// Ligne de log pour l'affichage
 #[derive(Debug, Clone)]
 pub struct LogLine {
@@ -75,21 +134,83 @@ pub struct LogLine {
     #[allow(dead_code)]
     pub source: LogSource,
     pub is_system: bool,
+    /// Processus d'origine (mode multi-process)
+    pub process: Option<String>,
+    /// Octets d'origine si le message a été décodé ou réparé (écrits tels quels par 's')
+    pub raw: Option<Vec<u8>>,
+    /// Ligne réécrite avec \r, mise à jour sur place jusqu'à son état final
+    pub progress: bool,
+    /// Octets non affichés d'une ligne plus longue que max_line_bytes
+    pub truncated_bytes: Option<usize>,
+    /// Message complet d'une ligne coupée (vue détaillée, copie, sauvegarde)
+    pub full: Option<String>,
+    /// Champs d'une ligne structurée (vue détaillée)
+    pub fields: serde_json::Map<String, serde_json::Value>,
+    /// Champs en "key=value" séparés par des espaces, pour la recherche
+    pub fields_text: String,
+    /// Événement multiligne affiché en entier (sinon sa première ligne seulement)
+    pub expanded: bool,
+    /// Message avec ses codes ANSI, affiché à la place de message (ansi.tui = "styled")
+    pub styled: Option<String>,
 }
 
 impl From<LogMessage> for LogLine {
     fn from(log: LogMessage) -> Self {
+        let progress = log.is_progress();
+        let fields_text = fields_text(&log.data.fields);
         Self {
             timestamp: log.data.timestamp[11..19].to_string(), // HH:MM:SS
             level: log.data.level,
             message: log.data.message,
             source: log.data.source,
             is_system: false,
+            process: log.data.process,
+            raw: log.data.raw,
+            progress,
+            truncated_bytes: log.data.truncated_bytes,
+            full: log.data.full,
+            fields: log.data.fields,
+            fields_text,
+            expanded: false,
+            styled: log.data.styled,
         }
     }
 }
 
 impl LogLine {
+    /// "[name] " si le log vient d'un processus nommé, vide sinon
+    pub fn process_prefix(&self) -> String {
+        self.process
+            .as_ref()
+            .map(|name| format!("[{}] ", name))
+            .unwrap_or_default()
+    }
+
+    /// Texte affiché: version colorée si elle a été gardée, sinon le message
+    pub fn display_message(&self) -> &str {
+        self.styled.as_deref().unwrap_or(&self.message)
+    }
+
+    /// Message complet, même si la ligne a été coupée à l'affichage
+    pub fn full_message(&self) -> &str {
+        self.full.as_deref().unwrap_or(&self.message)
+    }
+
+    /// Lignes de continuation d'un événement multiligne (stack trace...)
+    pub fn extra_lines(&self) -> usize {
+        self.message.lines().count().saturating_sub(1)
+    }
+
+    /// Hauteur à l'écran: une ligne, sauf pour un bloc déplié
+    pub fn height(&self) -> usize {
+        if self.expanded { self.extra_lines() + 1 } else { 1 }
+    }
+
+    /// La recherche porte sur le message et les champs (user=42, req.id=7)
+    pub fn matches(&self, regex: &Regex) -> bool {
+        regex.is_match(&self.message) || regex.is_match(&self.fields_text)
+    }
+
     pub fn system(message: String) -> Self {
         Self {
             timestamp: chrono::Local::now().format("%H:%M:%S").to_string(),
@@ -97,10 +218,44 @@ impl LogLine {
             message,
             source: LogSource::Stdout,
             is_system: true,
+            process: None,
+            raw: None,
+            progress: false,
+            truncated_bytes: None,
+            full: None,
+            fields: serde_json::Map::new(),
+            fields_text: String::new(),
+            expanded: false,
+            styled: None,
         }
     }
 }
 
+/// Valeur d'un champ: texte sans guillemets, autres valeurs en JSON
+pub fn field_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// "key=value" de chaque champ, objets aplatis en clés pointées (req.id=7)
+fn fields_text(fields: &serde_json::Map<String, serde_json::Value>) -> String {
+    fn flatten(prefix: &str, fields: &serde_json::Map<String, serde_json::Value>, pairs: &mut Vec<String>) {
+        for (key, value) in fields {
+            let key = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+            match value {
+                serde_json::Value::Object(child) => flatten(&key, child, pairs),
+                other => pairs.push(format!("{}={}", key, field_value(other))),
+            }
+        }
+    }
+
+    let mut pairs = Vec::new();
+    flatten("", fields, &mut pairs);
+    pairs.join(" ")
+}
+
 /// État principal de l'application
 pub struct App {
     /// Buffer circulaire des logs
@@ -115,16 +270,14 @@ pub struct App {
     pub selected_line: Option<usize>,
     /// État du superviseur
     pub state: AppState,
-    /// PID du processus enfant
-    pub pid: Option<u32>,
+    /// Processus supervisés (un seul hors mode multi-process)
+    pub processes: Vec<ProcessInfo>,
     /// Heure de démarrage
     pub start_time: Instant,
     /// Flag pour quitter
     pub should_quit: bool,
     /// Nom du projet
     pub project: String,
-    /// Commande exécutée
-    pub command: Vec<String>,
     /// Configuration
     #[allow(dead_code)]
     pub config: Config,
@@ -148,11 +301,32 @@ pub struct App {
     /// Stats réseau
     pub total_logs_received: usize,
     pub total_logs_sent: usize,
+    /// Lignes écartées par [filters] avant d'atteindre la TUI
+    pub total_logs_filtered: usize,
     pub last_log_time: Option<Instant>,
+    /// État de la connexion au serveur MCP
+    pub connection_state: ConnectionState,
+    /// Dernier changement de fichier ayant déclenché un redémarrage
+    pub last_change: Option<String>,
+    /// Dernières mesures de ressources (total des processus, pour les sparklines)
+    pub metrics_history: VecDeque<ResourceUsage>,
+    /// Logs lus depuis des fichiers ou stdin (tail, pipe): la TUI reste active sans processus
+    pub reading_input: bool,
+    /// Flux dont une ligne réparée a déjà été signalée
+    pub repaired_streams: HashSet<String>,
+    /// Flux dont une ligne de progression (\r) est en cours d'affichage
+    pub progress_streams: HashSet<String>,
+    /// Ligne affichée par la vue détaillée (copie: les logs peuvent être évincés)
+    pub detail: Option<LogLine>,
+    /// Défilement de la vue détaillée, en lignes
+    pub detail_scroll: u16,
 }
 
+/// Nombre de mesures gardées pour les sparklines du header
+const METRICS_HISTORY: usize = 30;
+
 impl App {
-    pub fn new(project: String, command: Vec<String>, config: Config) -> Self {
+    pub fn new(project: String, processes: Vec<ProcessInfo>, config: Config) -> Self {
         let max_logs = config.performance.tui.max_logs;
 
         Self {
@@ -162,11 +336,10 @@ impl App {
             auto_scroll: true,
             selected_line: None,
             state: AppState::Running,
-            pid: None,
+            processes,
             start_time: Instant::now(),
             should_quit: false,
             project,
-            command,
             config,
             visible_height: 20,
             needs_redraw: true,
@@ -181,8 +354,26 @@ impl App {
             paused_logs: Vec::new(),
             total_logs_received: 0,
             total_logs_sent: 0,
+            total_logs_filtered: 0,
             last_log_time: None,
+            connection_state: ConnectionState::Idle,
+            last_change: None,
+            metrics_history: VecDeque::with_capacity(METRICS_HISTORY),
+            reading_input: false,
+            repaired_streams: HashSet::new(),
+            progress_streams: HashSet::new(),
+            detail: None,
+            detail_scroll: 0,
+        }
+    }
+
+    /// Ligne affichée pour un log (sans sa version colorée si ansi.tui = "clean")
+    fn log_line(&self, log: LogMessage) -> LogLine {
+        let mut line = LogLine::from(log);
+        if self.config.ansi.tui == AnsiMode::Clean {
+            line.styled = None;
         }
+        line
     }
 
     /// Ajoute un log au buffer
@@ -196,7 +387,14 @@ impl App {
             return;
         }
 
-        self.logs.push_back(log.into());
+        // État final d'une ligne de progression: remplace la ligne sur place
+        if let Some(index) = self.take_progress_line(&log) {
+            self.logs[index] = self.log_line(log);
+            self.needs_redraw = true;
+            return;
+        }
+
+        self.logs.push_back(self.log_line(log));
 
         // Éviction FIFO si trop de logs
         while self.logs.len() > self.max_logs {
@@ -211,6 +409,63 @@ impl App {
         self.needs_redraw = true;
     }
 
+    /// Met à jour la ligne de progression (\r) du flux, ou l'ajoute
+    ///
+    /// Les mises à jour reçues pendant la pause sont ignorées: seul l'état
+    /// final de la ligne compte.
+    pub fn update_progress(&mut self, log: LogMessage) {
+        if self.paused {
+            return;
+        }
+
+        let stream = capture::stream_name(&log);
+        match self.take_progress_line(&log) {
+            Some(index) => self.logs[index] = self.log_line(log),
+            None => {
+                self.logs.push_back(self.log_line(log));
+                while self.logs.len() > self.max_logs {
+                    self.logs.pop_front();
+                }
+                if self.auto_scroll {
+                    self.scroll_offset = 0;
+                }
+            }
+        }
+        self.progress_streams.insert(stream);
+        self.needs_redraw = true;
+    }
+
+    /// Position de la ligne de progression en cours du flux de `log` (et l'oublie)
+    fn take_progress_line(&mut self, log: &LogMessage) -> Option<usize> {
+        if !self.progress_streams.remove(&capture::stream_name(log)) {
+            return None;
+        }
+        self.logs
+            .iter()
+            .rposition(|line| line.progress && line.process == log.data.process && line.source == log.data.source)
+    }
+
+    /// Ajoute une source trouvée après le lancement (fichier correspondant à un glob)
+    pub fn add_input(&mut self, name: String, command: Vec<String>) {
+        if self.process_index(&name).is_some() {
+            return;
+        }
+        let message = format!("Following {}", command.last().map_or(name.as_str(), String::as_str));
+        self.processes.push(ProcessInfo::new(name, command));
+        self.add_system_log(message);
+    }
+
+    /// Ajoute un message système concernant un processus ("[name] ..." en mode multi-process)
+    pub fn add_process_log(&mut self, index: usize, message: String) {
+        match self.processes.get(index) {
+            Some(process) if self.processes.len() > 1 => {
+                let message = format!("[{}] {}", process.name, message);
+                self.add_system_log(message)
+            }
+            _ => self.add_system_log(message),
+        }
+    }
+
     /// Ajoute un message système
     pub fn add_system_log(&mut self, message: String) {
         self.logs.push_back(LogLine::system(message));
@@ -237,8 +492,7 @@ impl App {
 
     /// Scroll vers le haut
     pub fn scroll_up(&mut self, n: usize) {
-        let max_offset = self.logs.len().saturating_sub(self.visible_height);
-        self.scroll_offset = (self.scroll_offset + n).min(max_offset);
+        self.scroll_offset = (self.scroll_offset + n).min(self.max_scroll_offset());
         self.auto_scroll = false;
         self.needs_redraw = true;
     }
@@ -256,7 +510,7 @@ impl App {
 
     /// Scroll tout en haut
     pub fn scroll_to_top(&mut self) {
-        self.scroll_offset = self.logs.len().saturating_sub(self.visible_height);
+        self.scroll_offset = self.max_scroll_offset();
         self.auto_scroll = false;
         self.needs_redraw = true;
     }
@@ -284,16 +538,18 @@ impl App {
             return;
         }
 
-        // Calculer l'index du log basé sur le scroll
-        // Les logs sont affichés du plus ancien au plus récent
-        // scroll_offset = 0 signifie qu'on voit les derniers logs
-        let visible_start = total_logs.saturating_sub(self.visible_height + self.scroll_offset);
-        let log_index = visible_start + log_row;
-
-        if log_index < total_logs {
-            self.selected_line = Some(log_index);
-            self.auto_scroll = false;
-            self.needs_redraw = true;
+        // Les blocs dépliés occupent plusieurs lignes à l'écran
+        let (start, end) = self.visible_range();
+        let mut top = 0;
+        for log_index in start..end {
+            let height = self.logs[log_index].height();
+            if log_row < top + height {
+                self.selected_line = Some(log_index);
+                self.auto_scroll = false;
+                self.needs_redraw = true;
+                return;
+            }
+            top += height;
         }
     }
 
@@ -311,18 +567,204 @@ impl App {
         }
     }
 
-    /// Set le PID
-    pub fn set_pid(&mut self, pid: Option<u32>) {
-        self.pid = pid;
+    /// Set le PID d'un processus (un nouveau lancement repart en Starting)
+    pub fn set_pid(&mut self, index: usize, pid: Option<u32>) {
+        if let Some(process) = self.processes.get_mut(index) {
+            process.pid = pid;
+            if pid.is_some() && process.ready.is_some() {
+                process.ready = Some(ReadyState::Starting(Instant::now()));
+            }
+        }
+        self.needs_redraw = true;
+    }
+
+    /// Signale la première ligne réparée (octets invalides) de chaque flux
+    pub fn note_repaired(&mut self, log: &LogMessage) {
+        if log.data.repaired && self.repaired_streams.insert(capture::stream_name(log)) {
+            self.add_system_log(capture::repair_notice(log));
+        }
+    }
+
+    /// Applique un événement ready / startup_failed reçu d'un processus
+    pub fn apply_ready_event(&mut self, log: &LogMessage) {
+        let state = match log.data.event {
+            Some(ProcessEvent::Ready) => {
+                ReadyState::Ready(Duration::from_millis(log.data.ready_ms.unwrap_or_default()))
+            }
+            Some(ProcessEvent::StartupFailed) => ReadyState::Failed,
+            _ => return,
+        };
+        let index = match &log.data.process {
+            Some(name) => self.process_index(name),
+            None => Some(0),
+        };
+        if let Some(process) = index.and_then(|i| self.processes.get_mut(i)) {
+            process.ready = Some(state);
+        }
+        if self.state.is_live() {
+            self.set_live_state();
+        }
+    }
+
+    /// État des processus en cours: Starting tant qu'un ready_when est en attente,
+    /// Ready quand tous sont remplis, Running sans ready_when
+    pub fn set_live_state(&mut self) {
+        let probes: Vec<&ReadyState> = self
+            .processes
+            .iter()
+            .filter(|p| p.pid.is_some())
+            .filter_map(|p| p.ready.as_ref())
+            .collect();
+
+        let state = if probes.iter().any(|r| matches!(r, ReadyState::Starting(_))) {
+            AppState::Starting
+        } else if !probes.is_empty() && probes.iter().all(|r| matches!(r, ReadyState::Ready(_))) {
+            AppState::Ready
+        } else {
+            AppState::Running
+        };
+        self.set_state(state);
+    }
+
+    /// Disponibilité pour le header ("READY in 1.2s", "api STARTING 3s · web READY in 0.8s")
+    pub fn readiness_str(&self) -> Option<String> {
+        let describe = |state: &ReadyState| match state {
+            ReadyState::Starting(since) => format!("STARTING {:.0}s", since.elapsed().as_secs_f32()),
+            ReadyState::Ready(elapsed) => format!("READY in {:.1}s", elapsed.as_secs_f32()),
+            ReadyState::Failed => "STARTUP FAILED".to_string(),
+        };
+        let states: Vec<String> = self
+            .processes
+            .iter()
+            .filter_map(|p| p.ready.as_ref().map(|r| (p, r)))
+            .map(|(p, r)| match self.processes.len() {
+                1 => describe(r),
+                _ => format!("{} {}", p.name, describe(r)),
+            })
+            .collect();
+        (!states.is_empty()).then(|| states.join(" · "))
+    }
+
+    /// Vrai si un processus a échoué à démarrer (ready_when non rempli)
+    pub fn startup_failed(&self) -> bool {
+        self.processes.iter().any(|p| p.ready == Some(ReadyState::Failed))
+    }
+
+    /// Met à jour le redémarrage automatique programmé d'un processus
+    pub fn set_restart_at(&mut self, index: usize, restart_at: Option<Instant>) {
+        if let Some(process) = self.processes.get_mut(index) {
+            process.restart_at = restart_at;
+        }
+        self.needs_redraw = true;
+    }
+
+    /// Marque un processus en crash loop
+    pub fn set_crash_loop(&mut self, index: usize, crash_loop: bool) {
+        if let Some(process) = self.processes.get_mut(index) {
+            process.crash_loop = crash_loop;
+        }
+        self.needs_redraw = true;
+    }
+
+    /// Retient le fichier ayant déclenché un redémarrage ("api: src/main.rs" en mode multi-process)
+    pub fn set_last_change(&mut self, index: usize, change: String) {
+        self.last_change = match self.processes.get(index) {
+            Some(process) if self.processes.len() > 1 => Some(format!("{}: {}", process.name, change)),
+            _ => Some(change),
+        };
+        self.needs_redraw = true;
+    }
+
+    /// Marque un processus en attente de ses dépendances
+    pub fn set_waiting(&mut self, index: usize, waiting: bool) {
+        if let Some(process) = self.processes.get_mut(index) {
+            process.waiting = waiting;
+        }
         self.needs_redraw = true;
     }
 
+    /// Vrai si un processus attend encore ses dépendances
+    pub fn any_waiting(&self) -> bool {
+        self.processes.iter().any(|p| p.waiting)
+    }
+
+    /// Enregistre une mesure de ressources (somme des processus mesurés)
+    pub fn record_metrics(&mut self, samples: &[ResourceUsage]) {
+        let Some(total) = samples.iter().copied().reduce(|a, b| a + b) else {
+            return;
+        };
+        if self.metrics_history.len() == METRICS_HISTORY {
+            self.metrics_history.pop_front();
+        }
+        self.metrics_history.push_back(total);
+        self.needs_redraw = true;
+    }
+
+    /// Noms des processus en crash loop
+    pub fn crash_loop_names(&self) -> Vec<&str> {
+        self.processes
+            .iter()
+            .filter(|p| p.crash_loop)
+            .map(|p| p.name.as_str())
+            .collect()
+    }
+
+    /// Vrai si au moins un processus tourne encore
+    pub fn any_running(&self) -> bool {
+        self.processes.iter().any(|p| p.pid.is_some())
+    }
+
+    /// PID pour la barre de statut ("api:123 web:N/A" en mode multi-process)
+    pub fn pid_str(&self) -> String {
+        let pid_str = |process: &ProcessInfo| match (process.pid, process.restart_at) {
+            (Some(pid), _) => pid.to_string(),
+            (None, Some(at)) => format!(
+                "restart in {}s",
+                at.saturating_duration_since(Instant::now()).as_secs_f32().ceil()
+            ),
+            (None, None) if process.crash_loop => "CRASH LOOP".to_string(),
+            (None, None) if process.waiting => "waiting".to_string(),
+            (None, None) => "N/A".to_string(),
+        };
+        match self.processes.as_slice() {
+            [process] => pid_str(process),
+            processes => processes
+                .iter()
+                .map(|p| format!("{}:{}", p.name, pid_str(p)))
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+
+    /// Position d'un processus (sert à choisir sa couleur)
+    pub fn process_index(&self, name: &str) -> Option<usize> {
+        self.processes.iter().position(|p| p.name == name)
+    }
+
     /// Set l'état
     pub fn set_state(&mut self, state: AppState) {
         self.state = state;
         self.needs_redraw = true;
     }
 
+    /// Met à jour l'état de la connexion MCP et le signale dans les logs
+    pub fn set_connection_state(&mut self, state: ConnectionState) {
+        match &state {
+            ConnectionState::Connected => self.add_system_log("Connected to MCP server".to_string()),
+            ConnectionState::Disconnected(reason) => {
+                self.add_system_log(format!("MCP server unreachable: {}", reason))
+            }
+            ConnectionState::Reconnecting { attempt: 1, .. }
+                if self.connection_state == ConnectionState::Connected =>
+            {
+                self.add_system_log("Lost connection to MCP server, reconnecting...".to_string())
+            }
+            _ => {}
+        }
+        self.connection_state = state;
+        self.needs_redraw = true;
+    }
+
     /// Reset le timer de démarrage
     pub fn reset_start_time(&mut self) {
         self.start_time = Instant::now();
@@ -331,7 +773,14 @@ impl App {
 
     /// Retourne la commande formatée
     pub fn command_str(&self) -> String {
-        self.command.join(" ")
+        match self.processes.as_slice() {
+            [process] => process.command.join(" "),
+            processes => processes
+                .iter()
+                .map(|p| p.name.as_str())
+                .collect::<Vec<_>>()
+                .join(" · "),
+        }
     }
 
     // === Nouvelles fonctionnalités ===
@@ -361,6 +810,47 @@ impl App {
         self.needs_redraw = true;
     }
 
+    /// Ligne sélectionnée, sinon la dernière ligne affichée
+    fn current_line(&self) -> usize {
+        self.selected_line
+            .unwrap_or_else(|| self.logs.len().saturating_sub(self.scroll_offset + 1))
+    }
+
+    /// Déplie ou replie l'événement multiligne sélectionné (sinon le dernier affiché)
+    pub fn toggle_block(&mut self) {
+        let index = self.current_line();
+        if let Some(line) = self.logs.get_mut(index)
+            && line.extra_lines() > 0
+        {
+            line.expanded = !line.expanded;
+            self.needs_redraw = true;
+        }
+    }
+
+    /// Ouvre la vue détaillée de la ligne sélectionnée (sinon la dernière ligne affichée)
+    pub fn open_detail(&mut self) {
+        let index = self.current_line();
+        if let Some(line) = self.logs.get(index) {
+            self.detail = Some(line.clone());
+            self.detail_scroll = 0;
+            self.input_mode = InputMode::Detail;
+            self.needs_redraw = true;
+        }
+    }
+
+    /// Ferme la vue détaillée
+    pub fn close_detail(&mut self) {
+        self.detail = None;
+        self.input_mode = InputMode::Normal;
+        self.needs_redraw = true;
+    }
+
+    /// Fait défiler la vue détaillée (négatif = vers le haut)
+    pub fn scroll_detail(&mut self, lines: i32) {
+        self.detail_scroll = (self.detail_scroll as i32 + lines).clamp(0, u16::MAX as i32) as u16;
+        self.needs_redraw = true;
+    }
+
     /// Quitte le mode input actuel
     pub fn exit_input_mode(&mut self) {
         self.input_mode = InputMode::Normal;
@@ -401,7 +891,7 @@ impl App {
                 let matches = self
                     .logs
                     .iter()
-                    .filter(|l| regex.is_match(&l.message))
+                    .filter(|l| l.matches(&regex))
                     .count();
                 self.search_regex = Some(regex);
                 self.search_message = Some(format!("{} matches", matches));
@@ -427,7 +917,12 @@ impl App {
         let mut file = std::fs::File::create(&filename)?;
 
         for log in &self.logs {
-            writeln!(file, "[{}] {:?} {}", log.timestamp, log.level, log.message)?;
+            write!(file, "[{}] {:?} {}", log.timestamp, log.level, log.process_prefix())?;
+            match &log.raw {
+                Some(raw) => file.write_all(raw)?,
+                None => file.write_all(log.full_message().as_bytes())?,
+            }
+            writeln!(file)?;
         }
 
         self.add_system_log(format!("Saved {} logs to {}", self.logs.len(), filename));
@@ -445,7 +940,7 @@ impl App {
             // Reprendre: ajouter les logs en attente
             let paused = std::mem::take(&mut self.paused_logs);
             for log in paused {
-                self.logs.push_back(log.into());
+                self.logs.push_back(self.log_line(log));
             }
 
             // Éviction
@@ -465,7 +960,7 @@ impl App {
     pub fn copy_selected_line(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         if let Some(index) = self.selected_line {
             if let Some(log) = self.logs.get(index) {
-                let text = format!("[{}] {:?} {}", log.timestamp, log.level, log.message);
+                let text = format!("[{}] {:?} {}{}", log.timestamp, log.level, log.process_prefix(), log.full_message());
 
                 let mut clipboard = arboard::Clipboard::new()?;
                 clipboard.set_text(text)?;
@@ -484,11 +979,60 @@ impl App {
         self.needs_redraw = true;
     }
 
+    /// Logs affichés (début, fin exclue): autant que la hauteur en laisse, blocs dépliés compris
+    ///
+    /// Le dernier log est toujours affiché, même s'il dépasse à lui seul la hauteur.
+    fn visible_range(&self) -> (usize, usize) {
+        self.visible_range_at(self.scroll_offset)
+    }
+
+    fn visible_range_at(&self, scroll_offset: usize) -> (usize, usize) {
+        let end = self.logs.len().saturating_sub(scroll_offset);
+        let mut start = end;
+        let mut rows = 0;
+        while start > 0 {
+            let height = self.logs[start - 1].height();
+            if rows > 0 && rows + height > self.visible_height {
+                break;
+            }
+            start -= 1;
+            rows += height;
+            if rows >= self.visible_height {
+                break;
+            }
+        }
+        (start, end)
+    }
+
+    /// Plus petit décalage qui affiche le premier log (blocs dépliés compris)
+    fn max_scroll_offset(&self) -> usize {
+        let mut rows = 0;
+        let shown = self
+            .logs
+            .iter()
+            .take_while(|log| {
+                rows += log.height();
+                rows <= self.visible_height
+            })
+            .count();
+        self.logs.len() - shown.max(1).min(self.logs.len())
+    }
+
+    /// Position de la barre de défilement: (premier log affiché, nombre de positions)
+    ///
+    /// None si tous les logs tiennent à l'écran.
+    pub fn scroll_position(&self) -> Option<(usize, usize)> {
+        let (bottom, _) = self.visible_range_at(0);
+        if bottom == 0 {
+            return None;
+        }
+        let (start, _) = self.visible_range();
+        Some((start, bottom + 1))
+    }
+
     /// Retourne les logs visibles filtrés par recherche ET par niveau
     pub fn filtered_visible_logs(&self) -> Vec<(usize, &LogLine, bool)> {
-        let total = self.logs.len();
-        let start = total.saturating_sub(self.visible_height + self.scroll_offset);
-        let end = total.saturating_sub(self.scroll_offset);
+        let (start, end) = self.visible_range();
 
         self.logs
             .iter()
@@ -500,7 +1044,7 @@ impl App {
                 let search_match = self
                     .search_regex
                     .as_ref()
-                    .map(|re| re.is_match(&log.message))
+                    .map(|re| log.matches(re))
                     .unwrap_or(true);
 
                 let level_match = log.is_system || self.level_filter.matches(&log.level);
@@ -519,7 +1063,7 @@ impl App {
                 let search_match = self
                     .search_regex
                     .as_ref()
-                    .map(|re| re.is_match(&l.message))
+                    .map(|re| l.matches(re))
                     .unwrap_or(true);
 
                 let level_match = l.is_system || self.level_filter.matches(&l.level);
@@ -544,3 +1088,48 @@ impl App {
         self.total_logs_sent += 1;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 3 logs d'une ligne puis un bloc déplié de 5 lignes, fenêtre de 4 lignes
+    fn app() -> App {
+        let mut app = App::new("test".to_string(), Vec::new(), Config::default());
+        app.logs.clear();
+        for i in 0..3 {
+            app.logs.push_back(LogLine::system(format!("line {}", i)));
+        }
+        let mut block = LogLine::system("boom\n  at a\n  at b\n  at c\n  at d".to_string());
+        block.expanded = true;
+        app.logs.push_back(block);
+        app.visible_height = 4;
+        app
+    }
+
+    #[test]
+    fn scroll_to_top_counts_rows_of_expanded_blocks() {
+        let mut app = app();
+        // En bas, le bloc déplié remplit l'écran à lui seul
+        assert_eq!(app.visible_range(), (3, 4));
+        app.scroll_to_top();
+        assert_eq!(app.visible_range(), (0, 3));
+        assert_eq!(app.scroll_offset, 1);
+
+        app.scroll_down(1);
+        app.scroll_up(10);
+        assert_eq!(app.scroll_offset, 1);
+    }
+
+    #[test]
+    fn scrollbar_spans_first_visible_logs() {
+        let mut app = app();
+        assert_eq!(app.scroll_position(), Some((3, 4)));
+        app.scroll_to_top();
+        assert_eq!(app.scroll_position(), Some((0, 4)));
+
+        app.logs.truncate(3);
+        app.scroll_to_bottom();
+        assert_eq!(app.scroll_position(), None);
+    }
+}
@@ -2,13 +2,23 @@ mod app;
 mod event;
 mod ui;
 
-pub use app::{App, AppState, InputMode};
+pub use app::{App, AppState, InputMode, ProcessInfo, ReadyState};
 pub use event::{Event, EventHandler};
+pub use ui::log_pane_size;
 
 use crate::config::Config;
-use crate::socket::SocketClient;
+use crate::capture::describe_exit;
+use crate::deps::{self, Lifecycles};
+use crate::metrics::{self, TreeSampler};
+use crate::capture::stop::StopOutcome;
+use crate::pipeline::Pipeline;
+use crate::process::ProcessSpec;
+use crate::restart::RestartDecision;
+use crate::socket::{ConnectionState, SocketClient};
 use crate::supervisor::Supervisor;
+use crate::input::Input;
 use crate::types::LogMessage;
+use crate::watcher::{FileChange, FileWatcher};
 
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture},
@@ -17,13 +27,15 @@ use crossterm::{
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::io;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 
-/// Lance la TUI avec supervision du processus
+/// Lance la TUI avec supervision des processus (un superviseur par processus)
 pub async fn run_tui(
     project: String,
-    command: Vec<String>,
+    processes: Vec<ProcessSpec>,
+    input: Option<Input>,
     config: Config,
+    pipeline: Pipeline,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     // Setup terminal
     enable_raw_mode()?;
@@ -33,34 +45,69 @@ pub async fn run_tui(
     let mut terminal = Terminal::new(backend)?;
 
     // Créer l'application
-    let mut app = App::new(project.clone(), command.clone(), config.clone());
+    let mut infos: Vec<ProcessInfo> = processes
+        .iter()
+        .map(|spec| ProcessInfo {
+            ready: spec.ready_when.as_ref().map(|_| ReadyState::Starting(std::time::Instant::now())),
+            ..ProcessInfo::new(spec.label(), spec.command.clone())
+        })
+        .collect();
+    // Fichiers suivis ou stdin (tail, pipe), après les processus
+    if let Some(input) = &input {
+        infos.extend(input.labels().into_iter().map(|(name, command)| ProcessInfo::new(name, command)));
+    }
+    let mut app = App::new(project.clone(), infos, config.clone());
+    app.reading_input = input.is_some();
 
     // Créer le channel pour les logs
     let (tx_log, rx_log) = mpsc::channel::<LogMessage>(config.performance.buffer_size);
 
     // Démarrer le socket worker
-    let socket_path = config.agent.socket_path.clone();
-    let socket_client = SocketClient::new(Some(socket_path));
+    let socket_client = SocketClient::from_config(&config);
+    let connection_state = socket_client.subscribe();
     let (tx_socket, rx_socket) = mpsc::channel::<LogMessage>(config.performance.buffer_size);
     let socket_task = tokio::spawn(async move {
         let _ = socket_client.start_worker(rx_socket).await;
     });
 
-    // Créer le superviseur
-    let mut supervisor = Supervisor::new(project, command, config.clone());
+    // Créer un superviseur par processus (logs tagués s'il y en a plusieurs)
+    let multi = processes.len() > 1;
+    let lifecycles = Lifecycles::new(&processes);
+    let mut supervisors: Vec<Supervisor> = processes
+        .into_iter()
+        .map(|spec| {
+            let supervisor = Supervisor::new(project.clone(), spec, pipeline.clone())
+                .with_lifecycles(lifecycles.clone());
+            if multi { supervisor.with_process_tag() } else { supervisor }
+        })
+        .collect();
 
-    // Démarrer le processus
-    match supervisor.start(tx_log.clone()).await {
-        Ok(pid) => {
-            app.set_pid(Some(pid));
-            app.set_state(AppState::Running);
-        }
-        Err(e) => {
-            app.add_system_log(format!("Failed to start process: {}", e));
-            app.set_state(AppState::WaitingCountdown(5));
+    // Surveiller les watch_paths (redémarrage sur changement de fichier)
+    let (tx_watch, rx_watch) = mpsc::channel::<FileChange>(16);
+    let debounce = std::time::Duration::from_millis(config.agent.watch_debounce_ms);
+    let mut _watchers = Vec::new();
+    for (index, supervisor) in supervisors.iter().enumerate() {
+        match FileWatcher::spawn(index, supervisor.spec(), debounce, tx_watch.clone()) {
+            Ok(Some(watcher)) => _watchers.push(watcher),
+            Ok(None) => {}
+            Err(e) => app.add_process_log(index, format!("File watching disabled: {}", e)),
         }
     }
 
+    // Démarrer les processus (ordre des dépendances; les autres attendent)
+    start_waiting(&mut app, &mut supervisors, &tx_log).await;
+    let (tx_sources, rx_sources) = mpsc::unbounded_channel::<(String, Vec<String>)>();
+    let input_task = input.map(|input| {
+        input
+            .with_new_sources(tx_sources)
+            .spawn(project.clone(), config.clone(), pipeline.clone(), false, tx_log.clone())
+    });
+    if app.any_running() || app.any_waiting() || app.reading_input {
+        app.set_live_state();
+    } else {
+        app.set_state(AppState::WaitingCountdown(5));
+    }
+
     // Créer le handler d'événements
     let tick_rate = std::time::Duration::from_millis(config.performance.tui.tick_rate_ms);
     let mut event_handler = EventHandler::new(tick_rate);
@@ -69,30 +116,41 @@ pub async fn run_tui(
     let frame_duration = std::time::Duration::from_millis(config.performance.tui.frame_rate_ms);
     let mut last_frame = std::time::Instant::now();
 
+    // Mesures de ressources (une échéance manquée est sautée, pas rattrapée)
+    let mut metrics_tick = tokio::time::interval(std::time::Duration::from_millis(
+        config.agent.metrics_interval_ms.max(1),
+    ));
+    metrics_tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
     // Boucle principale
     let mut channels = Channels {
         rx_log,
         tx_log: tx_log.clone(),
         tx_socket,
+        connection_state,
+        rx_watch,
+        metrics_tick,
+        input_task,
+        rx_sources,
+        pipeline,
     };
     
     let result = run_app_loop(
         &mut terminal,
         &mut app,
-        &mut supervisor,
+        &mut supervisors,
         &mut event_handler,
         &mut channels,
         frame_duration,
         &mut last_frame,
-        &config,
     )
     .await;
 
-    // Cleanup with timeout to prevent hanging on quit
-    let _ = tokio::time::timeout(
-        std::time::Duration::from_secs(1),
-        supervisor.stop()
-    ).await;
+    // Cleanup with timeout to prevent hanging on quit (grace period + 1s per tier)
+    let _ = tokio::time::timeout(stop_all_timeout(&supervisors), stop_all(&mut supervisors)).await;
+    if let Some(task) = &channels.input_task {
+        task.abort();
+    }
     drop(tx_log);
     let _ = tokio::time::timeout(
         std::time::Duration::from_millis(500),
@@ -111,22 +169,193 @@ pub async fn run_tui(
     result
 }
 
+/// Lance les processus pas encore démarrés dont les dépendances sont remplies
+async fn start_waiting(
+    app: &mut App,
+    supervisors: &mut [Supervisor],
+    tx_log: &mpsc::Sender<LogMessage>,
+) {
+    for (index, supervisor) in supervisors.iter_mut().enumerate() {
+        if !supervisor.waiting_deps() {
+            continue;
+        }
+        let was_waiting = app.processes[index].waiting;
+        match supervisor.start_when_ready(tx_log.clone()).await {
+            None if !was_waiting => {
+                let waiting: Vec<String> =
+                    supervisor.spec().depends_on.iter().map(|d| d.to_string()).collect();
+                app.add_process_log(index, format!("Waiting for {}", waiting.join(", ")));
+                app.set_waiting(index, true);
+            }
+            None => {}
+            Some(Ok(pid)) => {
+                app.set_waiting(index, false);
+                app.set_pid(index, Some(pid));
+            }
+            Some(Err(e)) if was_waiting => {
+                app.set_waiting(index, false);
+                app.add_process_log(index, format!("Not started: {}", e));
+            }
+            Some(Err(e)) => app.add_process_log(index, format!("Failed to start process: {}", e)),
+        }
+    }
+}
+
+/// Arrête tous les processus supervisés (ordre inverse du démarrage)
+///
+/// Les processus d'un même niveau de dépendances sont arrêtés en parallèle:
+/// la sortie prend au plus la somme des délais de grâce des niveaux.
+async fn stop_all(supervisors: &mut [Supervisor]) {
+    let specs: Vec<&ProcessSpec> = supervisors.iter().map(Supervisor::spec).collect();
+    let tiers = deps::stop_tiers(&specs);
+
+    for tier in tiers {
+        let stops: Vec<_> = supervisors
+            .iter_mut()
+            .enumerate()
+            .filter(|(index, _)| tier.contains(index))
+            .map(|(_, supervisor)| Box::pin(supervisor.stop()))
+            .collect();
+        join_all(stops).await;
+    }
+}
+
+/// Délai max de `stop_all`: pour chaque niveau, le délai de grâce le plus long
+/// plus 1s (fin de la capture des dernières lignes)
+fn stop_all_timeout(supervisors: &[Supervisor]) -> std::time::Duration {
+    let specs: Vec<&ProcessSpec> = supervisors.iter().map(Supervisor::spec).collect();
+    deps::stop_tiers(&specs)
+        .iter()
+        .map(|tier| {
+            let grace = tier.iter().map(|&i| supervisors[i].stop_grace()).max().unwrap_or_default();
+            grace + std::time::Duration::from_secs(1)
+        })
+        .sum()
+}
+
+/// Attend que tous les futures soient terminés, en les faisant avancer ensemble
+///
+/// Les résultats sont rendus dans l'ordre d'achèvement.
+async fn join_all<F: std::future::Future + Unpin>(mut futures: Vec<F>) -> Vec<F::Output> {
+    let mut outputs = Vec::with_capacity(futures.len());
+    std::future::poll_fn(|cx| {
+        futures.retain_mut(|future| match std::pin::Pin::new(future).poll(cx) {
+            std::task::Poll::Ready(output) => {
+                outputs.push(output);
+                false
+            }
+            std::task::Poll::Pending => true,
+        });
+        if futures.is_empty() {
+            std::task::Poll::Ready(())
+        } else {
+            std::task::Poll::Pending
+        }
+    })
+    .await;
+    outputs
+}
+
+/// Redémarre un processus à la demande (touche 'r' ou fichier modifié)
+async fn restart_process(
+    app: &mut App,
+    index: usize,
+    supervisor: &mut Supervisor,
+    tx_log: mpsc::Sender<LogMessage>,
+) {
+    let (outcome, result) = supervisor.restart(tx_log).await;
+    app.set_restart_at(index, None);
+    app.set_crash_loop(index, false);
+    if outcome != StopOutcome::NotRunning {
+        app.add_process_log(index, format!("Process {}", outcome));
+    }
+    match result {
+        Ok(pid) => {
+            app.set_pid(index, Some(pid));
+            app.add_process_log(index, format!("Process restarted (PID: {})", pid));
+        }
+        Err(e) => {
+            app.set_pid(index, None);
+            app.add_process_log(index, format!("Restart failed: {}", e));
+        }
+    }
+}
+
+/// Redémarre tous les processus (touche 'r')
+///
+/// Arrêt dans l'ordre inverse des dépendances, puis relance comme au
+/// démarrage: chacun attend que ses dépendances remplissent leur condition.
+async fn restart_all(
+    app: &mut App,
+    supervisors: &mut [Supervisor],
+    tx_log: &mpsc::Sender<LogMessage>,
+) {
+    let specs: Vec<&ProcessSpec> = supervisors.iter().map(Supervisor::spec).collect();
+    let tiers = deps::stop_tiers(&specs);
+
+    for tier in tiers {
+        let stops: Vec<_> = supervisors
+            .iter_mut()
+            .enumerate()
+            .filter(|(index, _)| tier.contains(index))
+            .map(|(index, supervisor)| Box::pin(async move { (index, supervisor.stop().await) }))
+            .collect();
+        for (index, outcome) in join_all(stops).await {
+            if outcome != StopOutcome::NotRunning {
+                app.add_process_log(index, format!("Process {}", outcome));
+            }
+        }
+    }
+
+    for (index, supervisor) in supervisors.iter_mut().enumerate() {
+        supervisor.rearm();
+        app.set_pid(index, None);
+        app.set_restart_at(index, None);
+        app.set_crash_loop(index, false);
+        app.set_waiting(index, false);
+    }
+    // Petit délai pour s'assurer que tout est clean
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    start_waiting(app, supervisors, tx_log).await;
+}
+
+/// État de la TUI après un redémarrage à la demande
+fn after_manual_restart(app: &mut App) {
+    if app.any_running() || app.any_waiting() || app.reading_input {
+        app.set_live_state();
+        app.reset_start_time();
+    } else {
+        app.set_state(AppState::WaitingCountdown(5));
+    }
+}
+
 /// Structure pour regrouper les channels de communication
 struct Channels {
     rx_log: mpsc::Receiver<LogMessage>,
     tx_log: mpsc::Sender<LogMessage>,
     tx_socket: mpsc::Sender<LogMessage>,
+    /// État de la connexion au serveur MCP (publié par le socket worker)
+    connection_state: watch::Receiver<ConnectionState>,
+    /// Changements de fichiers des watch_paths
+    rx_watch: mpsc::Receiver<FileChange>,
+    /// Échéances des mesures de ressources (agent.metrics_interval_ms)
+    metrics_tick: tokio::time::Interval,
+    /// Lecture des fichiers ou de stdin (tail, pipe)
+    input_task: Option<tokio::task::JoinHandle<()>>,
+    /// Fichiers suivis trouvés après le lancement (nom, "commande")
+    rx_sources: mpsc::UnboundedReceiver<(String, Vec<String>)>,
+    /// Pipeline partagé par les processus et les entrées (compte des lignes filtrées)
+    pipeline: Pipeline,
 }
 
 async fn run_app_loop(
     terminal: &mut Terminal<CrosstermBackend<io::Stderr>>,
     app: &mut App,
-    supervisor: &mut Supervisor,
+    supervisors: &mut [Supervisor],
     event_handler: &mut EventHandler,
     channels: &mut Channels,
     frame_duration: std::time::Duration,
     last_frame: &mut std::time::Instant,
-    config: &Config,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     loop {
         // Dessiner l'interface seulement si nécessaire et si assez de temps s'est écoulé
@@ -146,19 +375,18 @@ async fn run_app_loop(
                         use crossterm::event::{KeyCode, KeyModifiers};
                         
                         // Handle Ctrl+C globally (same as 'q')
-                        if let KeyCode::Char('c') = key.code {
-                            if key.modifiers.contains(KeyModifiers::CONTROL) {
-                                // Kill the process before quitting
-                                supervisor.stop().await;
+                        if let KeyCode::Char('c') = key.code
+                            && key.modifiers.contains(KeyModifiers::CONTROL) {
+                                // Kill the processes before quitting
+                                stop_all(supervisors).await;
                                 app.should_quit = true;
                                 continue;
                             }
-                        }
                         
                         // Handle 'q' globally to quit from any mode
                         if let KeyCode::Char('q') = key.code {
-                            // Kill the process before quitting
-                            supervisor.stop().await;
+                            // Kill the processes before quitting
+                            stop_all(supervisors).await;
                             app.should_quit = true;
                             continue;
                         }
@@ -175,18 +403,8 @@ async fn run_app_loop(
                                         terminal.draw(|f| ui::draw(f, app))?;
                                         *last_frame = std::time::Instant::now();
                                         
-                                        match supervisor.restart(channels.tx_log.clone()).await {
-                                            Ok(pid) => {
-                                                app.set_pid(Some(pid));
-                                                app.set_state(AppState::Running);
-                                                app.reset_start_time();
-                                                app.add_system_log(format!("Process restarted (PID: {})", pid));
-                                            }
-                                            Err(e) => {
-                                                app.add_system_log(format!("Restart failed: {}", e));
-                                                app.set_state(AppState::WaitingCountdown(5));
-                                            }
-                                        }
+                                        restart_all(app, supervisors, &channels.tx_log).await;
+                                        after_manual_restart(app);
                                     }
                                     KeyCode::Char('c') => {
                                         // Clear logs
@@ -218,6 +436,14 @@ async fn run_app_loop(
                                         // Toggle help
                                         app.toggle_help();
                                     }
+                                    KeyCode::Enter => {
+                                        // Ligne complète (lignes coupées par max_line_bytes)
+                                        app.open_detail();
+                                    }
+                                    KeyCode::Tab => {
+                                        // Déplier / replier un événement multiligne
+                                        app.toggle_block();
+                                    }
                                     KeyCode::Up | KeyCode::Char('k') => {
                                         app.scroll_up(1);
                                     }
@@ -264,11 +490,26 @@ async fn run_app_loop(
                                 // Any key closes help
                                 app.toggle_help();
                             }
+                            InputMode::Detail => {
+                                match key.code {
+                                    KeyCode::Up | KeyCode::Char('k') => app.scroll_detail(-1),
+                                    KeyCode::Down | KeyCode::Char('j') => app.scroll_detail(1),
+                                    KeyCode::PageUp => app.scroll_detail(-10),
+                                    KeyCode::PageDown => app.scroll_detail(10),
+                                    _ => app.close_detail(),
+                                }
+                            }
                         }
                     }
                     Event::Mouse(mouse) => {
                         use crossterm::event::MouseEventKind;
                         match mouse.kind {
+                            MouseEventKind::ScrollUp if app.input_mode == InputMode::Detail => {
+                                app.scroll_detail(-3);
+                            }
+                            MouseEventKind::ScrollDown if app.input_mode == InputMode::Detail => {
+                                app.scroll_detail(3);
+                            }
                             MouseEventKind::ScrollUp => {
                                 app.scroll_up(3);
                             }
@@ -297,22 +538,112 @@ async fn run_app_loop(
                             }
                         }
                         
-                        // Vérifier si le processus est terminé
-                        if let AppState::Running = app.state
-                            && let Some(status) = supervisor.try_wait() {
-                                app.set_pid(None);
-                                if status.success() {
-                                    app.add_system_log("Process exited successfully".to_string());
-                                } else {
-                                    app.add_system_log(format!("Process exited with status: {}", status));
+                        // Vérifier si des processus sont terminés
+                        if app.state.is_live() {
+                            // Processus en attente de leurs dépendances
+                            if app.any_waiting() {
+                                start_waiting(app, supervisors, &channels.tx_log).await;
+                                app.set_live_state();
+                            }
+
+                            let mut stopped = false;
+
+                            // Fin de stdin (mcp-log-agent pipe)
+                            if app.reading_input
+                                && channels.input_task.as_ref().is_some_and(|task| task.is_finished())
+                            {
+                                app.reading_input = false;
+                                app.add_system_log("Input closed (EOF)".to_string());
+                                stopped = true;
+                            }
+                            for (index, supervisor) in supervisors.iter_mut().enumerate() {
+                                if let Some(status) = supervisor.try_wait() {
+                                    stopped = true;
+                                    app.set_pid(index, None);
+                                    if status.success() {
+                                        app.add_process_log(index, "Process exited successfully".to_string());
+                                    } else {
+                                        app.add_process_log(index, format!("Process {}", describe_exit(&status)));
+                                    }
+
+                                    // Informer le serveur MCP de la sortie
+                                    if channels.tx_socket.send(supervisor.exit_event(&status)).await.is_ok() {
+                                        app.increment_sent();
+                                    }
+
+                                    // Politique de redémarrage automatique
+                                    match supervisor.schedule_restart(&status) {
+                                        RestartDecision::Stay => {}
+                                        RestartDecision::Restart { attempt, delay } => {
+                                            app.set_restart_at(index, supervisor.restart_at());
+                                            app.add_process_log(index, format!(
+                                                "Restarting in {:.1}s (attempt {}/{})",
+                                                delay.as_secs_f32(),
+                                                attempt,
+                                                supervisor.max_restarts()
+                                            ));
+                                        }
+                                        RestartDecision::CrashLoop { restarts, window } => {
+                                            let event = supervisor.crash_loop_event(restarts, window);
+                                            app.set_crash_loop(index, true);
+                                            app.add_process_log(index, format!("{} (press 'r' to restart)", event.data.message));
+                                            if channels.tx_socket.send(event).await.is_ok() {
+                                                app.increment_sent();
+                                            }
+                                        }
+                                    }
                                 }
-                                
-                                // Comportement selon auto_quit
-                                if config.agent.auto_quit {
+
+                                // Redémarrage programmé arrivé à échéance
+                                if let Some((attempt, result)) = supervisor.restart_if_due(channels.tx_log.clone()).await {
+                                    app.set_restart_at(index, None);
+                                    match result {
+                                        Ok(pid) => {
+                                            app.set_pid(index, Some(pid));
+                                            app.set_live_state();
+                                            app.add_process_log(index, format!(
+                                                "Process restarted (PID: {}, attempt {}/{})",
+                                                pid,
+                                                attempt,
+                                                supervisor.max_restarts()
+                                            ));
+                                            if channels.tx_socket.send(supervisor.restart_event(attempt)).await.is_ok() {
+                                                app.increment_sent();
+                                            }
+                                        }
+                                        Err(e) => {
+                                            stopped = true;
+                                            app.add_process_log(index, format!("Restart failed: {}", e));
+                                        }
+                                    }
+                                }
+                            }
+
+                            // Une fois tous les processus arrêtés sans redémarrage en attente
+                            if stopped
+                                && !app.any_running()
+                                && !app.any_waiting()
+                                && !supervisors.iter().any(Supervisor::restart_pending)
+                            {
+                                if !app.crash_loop_names().is_empty() {
+                                    // Crash loop: rester affiché jusqu'à 'r' ou 'q'
+                                    app.set_state(AppState::CrashLoop);
+                                } else if app.config.agent.auto_quit {
                                     // auto_quit = true: compte à rebours puis quit
-                                    app.set_state(AppState::WaitingCountdown(config.agent.auto_quit_delay));
+                                    app.set_state(AppState::WaitingCountdown(app.config.agent.auto_quit_delay));
                                 }
                             }
+                        }
+
+                        // Lignes écartées par [filters]: le compteur est commun aux clones du
+                        // pipeline (tous les processus, fichiers suivis et stdin)
+                        app.total_logs_filtered = channels.pipeline.dropped_count();
+
+                        // Suivre l'état de la connexion MCP
+                        if channels.connection_state.has_changed().unwrap_or(false) {
+                            let state = channels.connection_state.borrow_and_update().clone();
+                            app.set_connection_state(state);
+                        }
                         
                         // Forcer un redraw périodique pour l'uptime
                         app.needs_redraw = true;
@@ -324,8 +655,68 @@ async fn run_app_loop(
                 }
             }
             
+            // Nouveau fichier suivi (glob de mcp-log-agent tail)
+            Some((name, command)) = channels.rx_sources.recv() => {
+                app.add_input(name, command);
+            }
+
+            // Fichier modifié dans les watch_paths d'un processus
+            Some(change) = channels.rx_watch.recv() => {
+                if let Some(supervisor) = supervisors.get_mut(change.index) {
+                    app.set_last_change(change.index, change.to_string());
+                    app.add_process_log(change.index, format!("{} changed, restarting...", change));
+                    app.set_state(AppState::Restarting);
+                    terminal.draw(|f| ui::draw(f, app))?;
+                    *last_frame = std::time::Instant::now();
+
+                    restart_process(app, change.index, supervisor, channels.tx_log.clone()).await;
+                    after_manual_restart(app);
+                }
+            }
+
+            // Mesure des ressources de chaque processus
+            _ = channels.metrics_tick.tick(), if app.config.agent.metrics_interval_ms > 0 => {
+                // Parcours de /proc dans un thread bloquant, pas sur la boucle de la TUI
+                let (indices, mut samplers): (Vec<usize>, Vec<TreeSampler>) = supervisors
+                    .iter_mut()
+                    .enumerate()
+                    .filter_map(|(index, supervisor)| Some((index, supervisor.take_sampler()?)))
+                    .unzip();
+                let Ok((samplers, usages)) = tokio::task::spawn_blocking(move || {
+                    let usages = metrics::sample_all(&mut samplers);
+                    (samplers, usages)
+                })
+                .await
+                else {
+                    continue;
+                };
+
+                let mut samples = Vec::with_capacity(usages.len());
+                for ((index, sampler), usage) in indices.into_iter().zip(samplers).zip(usages) {
+                    let supervisor = &mut supervisors[index];
+                    supervisor.return_sampler(sampler);
+                    let Some(usage) = usage else { continue };
+                    samples.push(usage);
+                    if channels.tx_socket.send(supervisor.metrics_event(usage)).await.is_ok() {
+                        app.increment_sent();
+                    }
+                }
+                app.record_metrics(&samples);
+            }
+
             // Nouveau log du processus
             Some(log) = channels.rx_log.recv() => {
+                // Ligne réécrite avec \r: mise à jour sur place, seul l'état final part au serveur MCP
+                if log.is_progress() {
+                    app.update_progress(log);
+                    continue;
+                }
+
+                // ready / startup_failed: met à jour l'état du processus
+                app.apply_ready_event(&log);
+                // Octets invalides: notice système à la première ligne réparée du flux
+                app.note_repaired(&log);
+
                 // Ajouter à l'affichage
                 app.add_log(log.clone());
                 
@@ -7,6 +7,20 @@ use ratatui::{
     Frame,
 };
 
+/// Colonnes avant le message d'un log: "HH:MM:SS " et le niveau ("ERROR ")
+pub const MESSAGE_COLUMN: u16 = 13;
+
+/// Taille de la zone des messages du panneau des logs pour un terminal cols x rows
+///
+/// Retire le header (1), la barre de statut (3), les bordures et les colonnes
+/// affichées avant le message (heure, niveau, nom du processus).
+pub fn log_pane_size(cols: u16, rows: u16, process: Option<&str>) -> (u16, u16) {
+    let name = process.map_or(0, |name| name.chars().count() as u16 + 1);
+    let width = cols.saturating_sub(2 + MESSAGE_COLUMN + name).max(20);
+    let height = rows.saturating_sub(1 + 3 + 2).max(1);
+    (width, height)
+}
+
 /// Dessine l'interface complète
 pub fn draw(frame: &mut Frame, app: &mut App) {
     // Si mode aide, afficher l'overlay d'aide
@@ -14,6 +28,10 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
         draw_help_overlay(frame, app);
         return;
     }
+    if app.input_mode == InputMode::Detail {
+        draw_detail_overlay(frame, app);
+        return;
+    }
 
     // Layout principal: header (1) + logs (flexible) + status bar (3 ou 4)
     let status_height = if app.input_mode != InputMode::Normal { 4 } else { 3 };
@@ -0,0 +1,82 @@
+use crate::tui::app::{field_value, App};
+use crate::types::format_bytes;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// Dessine la vue détaillée: la ligne complète, même coupée par max_line_bytes,
+/// suivie des champs d'une ligne structurée
+pub fn draw_detail_overlay(frame: &mut Frame, app: &App) {
+    let Some(log) = &app.detail else {
+        return;
+    };
+
+    let border_color = app.config.performance.tui.colors.border.to_ratatui_color();
+    let help_bg = app.config.performance.tui.colors.help_bg.to_ratatui_color();
+    let search_dimmed = app.config.performance.tui.colors.search_dimmed.to_ratatui_color();
+    let status_fg = app.config.performance.tui.colors.status_fg.to_ratatui_color();
+
+    // Même taille que l'aide (80% x 90%)
+    let area = frame.area();
+    let popup_width = (area.width as f32 * 0.8) as u16;
+    let popup_height = (area.height as f32 * 0.9) as u16;
+
+    let popup_area = Rect {
+        x: (area.width.saturating_sub(popup_width)) / 2,
+        y: (area.height.saturating_sub(popup_height)) / 2,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    frame.render_widget(Clear, popup_area);
+
+    let message = log.full_message();
+    let title = format!(
+        " {} {:?} {}· {} ",
+        log.timestamp,
+        log.level,
+        log.process_prefix(),
+        format_bytes(message.len() as u64)
+    );
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(border_color))
+        .title(Span::styled(
+            title,
+            Style::default().fg(border_color).add_modifier(Modifier::BOLD),
+        ))
+        .title_bottom(Span::styled(
+            " ↑/↓ PgUp/PgDn scroll · any other key closes ",
+            Style::default().fg(search_dimmed).add_modifier(Modifier::ITALIC),
+        ))
+        .style(Style::default().bg(help_bg));
+
+    let inner_area = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let mut text: Vec<Line> = message
+        .lines()
+        .map(|line| Line::from(Span::styled(line, Style::default().fg(status_fg))))
+        .collect();
+
+    if !log.fields.is_empty() {
+        text.push(Line::from(""));
+        for (key, value) in &log.fields {
+            let value = field_value(value);
+            text.push(Line::from(vec![
+                Span::styled(format!("{} = ", key), Style::default().fg(search_dimmed)),
+                Span::styled(value, Style::default().fg(status_fg)),
+            ]));
+        }
+    }
+
+    let paragraph = Paragraph::new(text)
+        .wrap(Wrap { trim: false })
+        .scroll((app.detail_scroll, 0));
+
+    frame.render_widget(paragraph, inner_area);
+}
@@ -1,4 +1,5 @@
-use crate::tui::app::App;
+use crate::tui::app::{App, AppState};
+use crate::types::format_bytes;
 use ratatui::{
     layout::Rect,
     style::{Modifier, Style},
@@ -14,7 +15,7 @@ pub fn draw_header(frame: &mut Frame, app: &App, area: Rect) {
     let header_fg = app.config.performance.tui.colors.header_fg.to_ratatui_color();
     let status_fg = app.config.performance.tui.colors.status_fg.to_ratatui_color();
     
-    let header = Paragraph::new(Line::from(vec![
+    let mut spans = vec![
         Span::styled(
             format!(" {} ", app.project),
             Style::default()
@@ -24,7 +25,76 @@ pub fn draw_header(frame: &mut Frame, app: &App, area: Rect) {
         ),
         Span::raw(" "),
         Span::styled(app.command_str(), Style::default().fg(status_fg)),
-    ]));
+    ];
+
+    // Disponibilité des processus ayant un ready_when
+    if let Some(readiness) = app.readiness_str() {
+        let color_style = match app.state {
+            _ if app.startup_failed() => &app.config.colors.system.error,
+            AppState::Ready => &app.config.colors.system.success,
+            _ => &app.config.colors.warn,
+        };
+        let color = color_style
+            .fg
+            .as_ref()
+            .map_or(status_fg, |c| c.to_ratatui_color());
+        spans.push(Span::raw("  "));
+        spans.push(Span::styled(
+            readiness,
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    // Ressources des processus (CPU, RSS, fds) et leur évolution récente
+    if app.any_running()
+        && let Some(latest) = app.metrics_history.back()
+    {
+        let spark_color = app
+            .config
+            .colors
+            .system
+            .info
+            .fg
+            .as_ref()
+            .map_or(status_fg, |c| c.to_ratatui_color());
+        let series: [(String, Vec<f64>); 3] = [
+            (
+                format!("CPU {:.1}%", latest.cpu_percent),
+                app.metrics_history.iter().map(|m| m.cpu_percent as f64).collect(),
+            ),
+            (
+                format!("RSS {}", format_bytes(latest.rss_bytes)),
+                app.metrics_history.iter().map(|m| m.rss_bytes as f64).collect(),
+            ),
+            (
+                format!("{} fds", latest.fds),
+                app.metrics_history.iter().map(|m| m.fds as f64).collect(),
+            ),
+        ];
+        for (label, values) in series {
+            spans.push(Span::raw("  "));
+            spans.push(Span::styled(format!("{} ", label), Style::default().fg(status_fg)));
+            spans.push(Span::styled(sparkline(&values), Style::default().fg(spark_color)));
+        }
+    }
+
+    let header = Paragraph::new(Line::from(spans));
 
     frame.render_widget(header, area);
 }
+
+/// Sparkline en caractères blocs, à l'échelle du maximum de la série
+fn sparkline(values: &[f64]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().copied().fold(0.0, f64::max);
+    values
+        .iter()
+        .map(|v| {
+            if max <= 0.0 {
+                BARS[0]
+            } else {
+                BARS[((v / max) * (BARS.len() - 1) as f64).round() as usize]
+            }
+        })
+        .collect()
+}
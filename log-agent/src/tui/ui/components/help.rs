@@ -99,7 +99,7 @@ pub fn draw_help_overlay(frame: &mut Frame, app: &App) {
         ]),
         Line::from(vec![
             Span::styled("  /             ", Style::default().fg(status_fg)),
-            Span::raw("Search logs (supports regex)"),
+            Span::raw("Search logs and fields (supports regex, user=42)"),
         ]),
         Line::from(vec![
             Span::styled("  s             ", Style::default().fg(status_fg)),
@@ -109,6 +109,14 @@ pub fn draw_help_overlay(frame: &mut Frame, app: &App) {
             Span::styled("  y             ", Style::default().fg(status_fg)),
             Span::raw("Copy selected line to clipboard"),
         ]),
+        Line::from(vec![
+            Span::styled("  Enter         ", Style::default().fg(status_fg)),
+            Span::raw("Show the full selected line and its fields (or the last one)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  Tab           ", Style::default().fg(status_fg)),
+            Span::raw("Expand/collapse the selected multiline event (or the last one)"),
+        ]),
         Line::from(""),
         Line::from(vec![
             Span::styled("Search Mode", Style::default().fg(search_match).add_modifier(Modifier::BOLD)),
@@ -154,9 +162,13 @@ pub fn draw_help_overlay(frame: &mut Frame, app: &App) {
             Span::styled("  LIVE/PAUSED   ", Style::default().fg(status_fg)),
             Span::raw("Capture status"),
         ]),
+        Line::from(vec![
+            Span::styled("  MCP           ", Style::default().fg(status_fg)),
+            Span::raw("MCP server connection (ON, RETRY n/max, OFF)"),
+        ]),
         Line::from(vec![
             Span::styled("  ↓↑ Stats      ", Style::default().fg(status_fg)),
-            Span::raw("Logs received/sent and rate per second"),
+            Span::raw("Logs received/sent, filtered (⊘) and rate per second"),
         ]),
         Line::from(""),
         Line::from(""),
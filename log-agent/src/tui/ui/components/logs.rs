@@ -1,4 +1,5 @@
 use crate::tui::app::{App, LogLine};
+use crate::tui::ui::MESSAGE_COLUMN;
 use crate::types::LogLevel;
 use ansi_to_tui::IntoText;
 use ratatui::{
@@ -9,6 +10,16 @@ use ratatui::{
     Frame,
 };
 
+/// Couleurs des noms de processus en mode multi-process (même ordre qu'en one-shot)
+const PROCESS_COLORS: [Color; 6] = [
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+    Color::Green,
+    Color::Blue,
+    Color::LightRed,
+];
+
 /// Dessine la zone des logs avec scrollbar et filtrage
 pub fn draw_logs_panel(frame: &mut Frame, app: &mut App, area: Rect) {
     // Extract colors from config
@@ -67,7 +78,7 @@ pub fn draw_logs_panel(frame: &mut Frame, app: &mut App, area: Rect) {
     frame.render_widget(list, inner_area);
 
     // Scrollbar
-    if app.logs.len() > app.visible_height {
+    if let Some((position, total)) = app.scroll_position() {
         let scrollbar_area = Rect {
             x: area.x + area.width - 1,
             y: area.y + 1,
@@ -75,9 +86,6 @@ pub fn draw_logs_panel(frame: &mut Frame, app: &mut App, area: Rect) {
             height: area.height - 2,
         };
 
-        let total = app.logs.len();
-        let position = total.saturating_sub(app.scroll_offset + app.visible_height);
-
         let mut scrollbar_state = ScrollbarState::new(total).position(position);
         let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
             .begin_symbol(Some("▲"))
@@ -162,17 +170,17 @@ fn log_to_list_item(
     // Si le log ne matche pas le filtre, l'afficher en grisé
     let dimmed = !matches_filter;
 
-    let line = if log.is_system {
+    let lines = if log.is_system {
         // Message système - use a magenta/purple color
         let system_color = Color::Magenta;
-        Line::from(vec![
+        vec![Line::from(vec![
             Span::styled(format!("{} ", log.timestamp), base_style.fg(search_dimmed)),
             Span::styled(
                 "SYS ",
                 base_style.fg(system_color).add_modifier(Modifier::BOLD),
             ),
             Span::styled(log.message.clone(), base_style.fg(system_color)),
-        ])
+        ])]
     } else {
         // Log normal - Parse ANSI codes if present
         let msg_color = if dimmed {
@@ -186,49 +194,11 @@ fn log_to_list_item(
             }
         };
 
-        // Try to parse ANSI codes in the message
-        let message_spans = if log.message.contains('\x1b') || log.message.contains("\\033") {
-            // Replace literal \033 with actual escape character if needed
-            let normalized_msg = log.message.replace("\\033", "\x1b");
-
-            // Parse ANSI codes using ansi-to-tui
-            match normalized_msg.as_bytes().into_text() {
-                Ok(parsed_text) => {
-                    // Convert parsed text to our spans with base style applied
-                    let mut spans = Vec::new();
-                    for line in parsed_text.lines {
-                        for span in line.spans {
-                            // Apply base_style and dimming if needed
-                            let mut style = base_style;
-                            if !dimmed {
-                                // Preserve ANSI colors
-                                if let Some(fg) = span.style.fg {
-                                    style = style.fg(fg);
-                                } else {
-                                    style = style.fg(msg_color);
-                                }
-                                if let Some(bg) = span.style.bg {
-                                    style = style.bg(bg);
-                                }
-                                style = style.add_modifier(span.style.add_modifier);
-                            } else {
-                                // Override with dimmed color
-                                style = style.fg(search_dimmed);
-                            }
-                            spans.push(Span::styled(span.content.to_string(), style));
-                        }
-                    }
-                    spans
-                }
-                Err(_) => {
-                    // If parsing fails, fall back to plain text
-                    vec![Span::styled(log.message.clone(), base_style.fg(msg_color))]
-                }
-            }
-        } else {
-            // No ANSI codes, use plain text
-            vec![Span::styled(log.message.clone(), base_style.fg(msg_color))]
-        };
+        // Événement multiligne: première ligne, les suivantes si le bloc est déplié
+        let mut message_lines = log.display_message().lines();
+        let head = message_lines.next().unwrap_or_default();
+        let extra_lines = log.extra_lines();
+        let head_spans = message_spans(head, base_style, msg_color, dimmed, search_dimmed);
 
         let mut line_spans = vec![
             Span::styled(format!("{} ", log.timestamp), base_style.fg(search_dimmed)),
@@ -243,10 +213,114 @@ fn log_to_list_item(
                     }),
             ),
         ];
-        line_spans.extend(message_spans);
 
-        Line::from(line_spans)
+        // Nom du processus d'origine (mode multi-process)
+        if let Some(name) = &log.process {
+            let color = app
+                .process_index(name)
+                .map(|i| PROCESS_COLORS[i % PROCESS_COLORS.len()])
+                .unwrap_or(search_dimmed);
+            line_spans.push(Span::styled(
+                format!("{} ", name),
+                base_style.fg(if dimmed { search_dimmed } else { color }),
+            ));
+        }
+
+        // Ligne de progression (\r) pas encore terminée
+        if log.progress {
+            line_spans.push(Span::styled("⟳ ", base_style.fg(search_dimmed)));
+        }
+
+        if extra_lines > 0 {
+            let marker = if log.expanded { "▾ " } else { "▸ " };
+            line_spans.push(Span::styled(marker, base_style.fg(search_dimmed)));
+        }
+
+        line_spans.extend(head_spans);
+
+        // Bloc replié: nombre de lignes masquées (Tab pour déplier)
+        if extra_lines > 0 && !log.expanded {
+            line_spans.push(Span::styled(
+                format!(" [+{} lines]", extra_lines),
+                base_style.fg(search_dimmed).add_modifier(Modifier::ITALIC),
+            ));
+        }
+
+        // Ligne coupée (max_line_bytes): Enter affiche la ligne complète
+        if let Some(bytes) = log.truncated_bytes {
+            line_spans.push(Span::styled(
+                format!(" [+{} bytes]", bytes),
+                base_style.fg(search_dimmed).add_modifier(Modifier::ITALIC),
+            ));
+        }
+
+        let mut lines = vec![Line::from(line_spans)];
+
+        // Bloc déplié: lignes de continuation alignées sous le message
+        if log.expanded {
+            let indent = " ".repeat(
+                MESSAGE_COLUMN as usize
+                    + log.process.as_ref().map_or(0, |name| name.chars().count() + 1)
+                    + 2,
+            );
+            for text in message_lines {
+                let mut spans = vec![Span::styled(indent.clone(), base_style)];
+                spans.extend(message_spans(text, base_style, msg_color, dimmed, search_dimmed));
+                lines.push(Line::from(spans));
+            }
+        }
+
+        lines
     };
 
-    ListItem::new(line)
+    ListItem::new(lines)
+}
+
+/// Spans d'une ligne de message, codes ANSI compris
+fn message_spans(
+    text: &str,
+    base_style: Style,
+    msg_color: Color,
+    dimmed: bool,
+    search_dimmed: Color,
+) -> Vec<Span<'static>> {
+    if !text.contains('\x1b') {
+        // No ANSI codes, use plain text
+        return vec![Span::styled(text.to_string(), base_style.fg(msg_color))];
+    }
+
+    // Parse ANSI codes using ansi-to-tui
+    match text.as_bytes().into_text() {
+        Ok(parsed_text) => {
+            // Convert parsed text to our spans with base style applied
+            let mut spans = Vec::new();
+            for line in parsed_text.lines {
+                for span in line.spans {
+                    // Apply base_style and dimming if needed
+                    let mut style = base_style;
+                    if !dimmed {
+                        // Preserve ANSI colors
+                        if let Some(fg) = span.style.fg {
+                            style = style.fg(fg);
+                        } else {
+                            style = style.fg(msg_color);
+                        }
+                        if let Some(bg) = span.style.bg {
+                            style = style.bg(bg);
+                        }
+                        style = style.add_modifier(span.style.add_modifier);
+                    } else {
+                        // Override with dimmed color
+                        style = style.fg(search_dimmed);
+                    }
+                    spans.push(Span::styled(span.content.to_string(), style));
+                }
+            }
+            spans
+        }
+        Err(_) => {
+            // If parsing fails, fall back to plain text
+            vec![Span::styled(text.to_string(), base_style.fg(msg_color))]
+        }
+    }
 }
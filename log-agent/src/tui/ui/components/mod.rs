@@ -3,9 +3,11 @@ mod header;
 mod logs;
 mod status;
 mod help;
+mod detail;
 mod widgets;
 
 pub use header::draw_header;
 pub use logs::draw_logs_panel;
 pub use status::draw_status_bar;
 pub use help::draw_help_overlay;
+pub use detail::draw_detail_overlay;
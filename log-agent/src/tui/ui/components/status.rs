@@ -1,4 +1,5 @@
 use super::widgets::{ShortcutList, StatusInfoList};
+use crate::socket::ConnectionState;
 use crate::tui::app::{App, AppState, InputMode};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
@@ -14,7 +15,7 @@ pub fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect) {
         InputMode::Normal => draw_normal_status(frame, app, area),
         InputMode::Search => draw_search_input(frame, app, area),
         InputMode::SavePrompt => draw_save_input(frame, app, area),
-        InputMode::Help => {} // Géré par help_overlay
+        InputMode::Help | InputMode::Detail => {} // Gérés par les overlays
     }
 }
 
@@ -53,8 +54,8 @@ fn draw_normal_status(frame: &mut Frame, app: &App, area: Rect) {
 
     // Contenu selon l'état
     let lines = match &app.state {
-        AppState::Running => {
-            let pid_str = app.pid.map_or("N/A".to_string(), |p| p.to_string());
+        AppState::Running | AppState::Starting | AppState::Ready => {
+            let pid_str = app.pid_str();
             let scroll_str = if app.auto_scroll {
                 "AUTO".to_string()
             } else {
@@ -73,9 +74,16 @@ fn draw_normal_status(frame: &mut Frame, app: &App, area: Rect) {
                 format!("{}!", filter_label) // Ajouter ! pour indiquer un filtre actif
             };
 
-            let info_spans = StatusInfoList::new()
+            let mut info = StatusInfoList::new()
                 .add(" PID", pid_str, status_fg)
-                .add("Uptime", app.uptime(), status_fg)
+                .add("Uptime", app.uptime(), status_fg);
+
+            // Fichier ayant déclenché le dernier redémarrage (watch_paths)
+            if let Some(change) = &app.last_change {
+                info = info.add("Changed", change.clone(), search_match);
+            }
+
+            let info_spans = info
                 .add(
                     "Filter",
                     filter_text,
@@ -91,12 +99,22 @@ fn draw_normal_status(frame: &mut Frame, app: &App, area: Rect) {
                     if app.paused { "PAUSED" } else { "LIVE" },
                     if app.paused { search_match } else { status_fg },
                 )
+                .add(
+                    "MCP",
+                    connection_label(&app.connection_state),
+                    if app.connection_state == ConnectionState::Connected {
+                        status_fg
+                    } else {
+                        search_match
+                    },
+                )
                 .add(
                     "Stats",
                     format!(
-                        "↓{} ↑{} {:.1}/s",
+                        "↓{} ↑{} ⊘{} {:.1}/s",
                         app.total_logs_received,
                         app.total_logs_sent,
+                        app.total_logs_filtered,
                         app.logs_per_second()
                     ),
                     status_fg,
@@ -146,6 +164,32 @@ fn draw_normal_status(frame: &mut Frame, app: &App, area: Rect) {
 
             vec![Line::from(spans)]
         }
+        AppState::CrashLoop => {
+            let shortcuts = ShortcutList::new()
+                .add("r", "Restart")
+                .add("q", "Quit")
+                .to_spans();
+
+            let mut spans = vec![
+                Span::styled(
+                    format!(" Crash loop: {} ", app.crash_loop_names().join(", ")),
+                    Style::default()
+                        .fg(search_match)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(" │ ", Style::default().fg(search_dimmed)),
+            ];
+            spans.extend(shortcuts);
+            spans.extend(vec![
+                Span::styled(" │ ", Style::default().fg(search_dimmed)),
+                Span::styled(
+                    "Automatic restarts stopped",
+                    Style::default().fg(search_dimmed),
+                ),
+            ]);
+
+            vec![Line::from(spans)]
+        }
         AppState::Restarting => {
             vec![Line::from(vec![Span::styled(
                 " Restarting... ",
@@ -158,6 +202,16 @@ fn draw_normal_status(frame: &mut Frame, app: &App, area: Rect) {
     render_lines(frame, inner_area, &lines);
 }
 
+/// Libellé court de l'état de connexion pour la barre de statut
+fn connection_label(state: &ConnectionState) -> String {
+    match state {
+        ConnectionState::Idle | ConnectionState::Connecting => "…".to_string(),
+        ConnectionState::Connected => "ON".to_string(),
+        ConnectionState::Reconnecting { attempt, max } => format!("RETRY {}/{}", attempt, max),
+        ConnectionState::Disconnected(_) => "OFF".to_string(),
+    }
+}
+
 /// Input de recherche
 fn draw_search_input(frame: &mut Frame, app: &App, area: Rect) {
     // Récupérer les couleurs de la config
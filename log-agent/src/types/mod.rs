@@ -1,5 +1,18 @@
+use crate::pipeline::ansi;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Identifiant des lignes envoyées en morceaux (unique pour un run de l'agent)
+static NEXT_CHUNK_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Taille max conservée d'une ligne coupée (`full`), quel que soit max_line_bytes
+const MAX_FULL_BYTES: usize = 1024 * 1024;
+
+/// Octets d'une ligne conservés au plus pour max_line_bytes (au-delà, ils sont seulement comptés)
+pub fn full_limit(max_line_bytes: usize) -> usize {
+    MAX_FULL_BYTES.max(max_line_bytes)
+}
 
 /// Message de log envoyé au serveur MCP
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +32,135 @@ pub struct LogData {
     pub project: String,
     pub message: String,
     pub pid: u32,
+    /// Nom du processus quand plusieurs commandes tournent ensemble
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process: Option<String>,
+    /// Événement du cycle de vie (messages de type "process_event")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event: Option<ProcessEvent>,
+    /// Code de sortie (128+signal si tué par un signal)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    /// Numéro du redémarrage automatique dans la fenêtre (événement "restarted")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restart_attempt: Option<u32>,
+    /// Temps de démarrage en ms (événement "ready")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ready_ms: Option<u64>,
+    /// Ressources de l'arbre de processus (messages de type "metrics")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<ResourceUsage>,
+    /// Octets invalides dans l'encodage attendu, remplacés par U+FFFD dans message
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub repaired: bool,
+    /// Octets d'origine de la ligne quand message n'en est pas la copie exacte
+    /// (ligne réparée ou autre encodage que UTF-8), pour les sauvegardes fichier
+    #[serde(skip)]
+    pub raw: Option<Vec<u8>>,
+    /// Octets retirés de message (ligne plus longue que max_line_bytes)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub truncated_bytes: Option<usize>,
+    /// Morceau d'une ligne trop longue envoyée en plusieurs messages (long_lines = "split")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunk: Option<LineChunk>,
+    /// Message complet d'une ligne coupée (vue détaillée de la TUI, envoi en morceaux)
+    #[serde(skip)]
+    pub full: Option<String>,
+    /// Message avec ses codes ANSI (couleurs), message n'en contenant plus
+    ///
+    /// Distinct de `raw` (octets d'origine): affiché par le terminal et la TUI, ou
+    /// envoyé au serveur MCP selon la section [ansi] de la config.
+    #[serde(skip)]
+    pub styled: Option<String>,
+    /// Version colorée de `full` (envoi en morceaux si ansi.socket = "styled")
+    #[serde(skip)]
+    pub styled_full: Option<String>,
+    /// Champs d'une ligne structurée autres que le niveau, le message et l'horodatage
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Position d'un morceau dans la ligne d'origine
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LineChunk {
+    /// Commun à tous les morceaux d'une même ligne
+    pub id: u64,
+    /// Position du morceau, à partir de 1 (la ligne continue tant que index < count)
+    pub index: u32,
+    pub count: u32,
+}
+
+/// Ressources utilisées par un processus et ses descendants
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResourceUsage {
+    /// CPU depuis la mesure précédente (100 = un cœur)
+    pub cpu_percent: f32,
+    /// Mémoire résidente totale
+    pub rss_bytes: u64,
+    /// Descripteurs de fichiers ouverts
+    pub fds: u32,
+    pub threads: u32,
+    /// Nombre de processus dans l'arbre
+    pub processes: u32,
+}
+
+/// Total de plusieurs processus
+impl std::ops::Add for ResourceUsage {
+    type Output = Self;
+
+    fn add(self, other: ResourceUsage) -> Self {
+        Self {
+            cpu_percent: self.cpu_percent + other.cpu_percent,
+            rss_bytes: self.rss_bytes + other.rss_bytes,
+            fds: self.fds + other.fds,
+            threads: self.threads + other.threads,
+            processes: self.processes + other.processes,
+        }
+    }
+}
+
+impl std::fmt::Display for ResourceUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "CPU {:.1}% · RSS {} · {} fds · {} threads",
+            self.cpu_percent,
+            format_bytes(self.rss_bytes),
+            self.fds,
+            self.threads
+        )
+    }
+}
+
+/// Taille lisible ("145.2 MB")
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Événement du cycle de vie d'un processus
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessEvent {
+    Exited,
+    /// Redémarré automatiquement (politique restart)
+    Restarted,
+    /// Redémarrages abandonnés: trop de sorties dans la fenêtre
+    CrashLoop,
+    /// Condition ready_when remplie
+    Ready,
+    /// Condition ready_when non remplie dans le délai
+    StartupFailed,
 }
 
 /// Niveau de log
@@ -32,7 +174,7 @@ pub enum LogLevel {
 }
 
 /// Source du log (stdout ou stderr)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum LogSource {
     Stdout,
@@ -41,34 +183,305 @@ pub enum LogSource {
 
 impl LogMessage {
     /// Crée un nouveau message de log
+    ///
+    /// Niveau Info: celui d'une ligne capturée est donné par le pipeline ([levels]).
     pub fn new(project: String, message: String, source: LogSource, pid: u32) -> Self {
-        let level = Self::infer_level(&message);
-
         Self {
             version: "1.0".to_string(),
             msg_type: "log_entry".to_string(),
             data: LogData {
                 timestamp: Utc::now().to_rfc3339(),
-                level,
+                level: LogLevel::Info,
                 source,
                 project,
                 message,
                 pid,
+                process: None,
+                event: None,
+                exit_code: None,
+                restart_attempt: None,
+                ready_ms: None,
+                metrics: None,
+                repaired: false,
+                raw: None,
+                truncated_bytes: None,
+                chunk: None,
+                full: None,
+                styled: None,
+                styled_full: None,
+                fields: serde_json::Map::new(),
             },
         }
     }
 
-    /// Infère le niveau de log depuis le message
-    fn infer_level(message: &str) -> LogLevel {
-        let lower = message.to_lowercase();
-        if lower.contains("error") || lower.contains("err") || lower.contains("fatal") {
-            LogLevel::Error
-        } else if lower.contains("warn") || lower.contains("warning") {
-            LogLevel::Warn
-        } else if lower.contains("debug") || lower.contains("trace") {
-            LogLevel::Debug
-        } else {
-            LogLevel::Info
+    /// Événement "process exited", envoyé une fois le processus terminé
+    pub fn exited(project: String, message: String, pid: u32, exit_code: i32) -> Self {
+        let mut log = Self::new(project, message, LogSource::Stdout, pid);
+        log.msg_type = "process_event".to_string();
+        log.data.level = if exit_code == 0 { LogLevel::Info } else { LogLevel::Error };
+        log.data.event = Some(ProcessEvent::Exited);
+        log.data.exit_code = Some(exit_code);
+        log
+    }
+
+    /// Événement "process restarted", envoyé après un redémarrage automatique
+    pub fn restarted(project: String, message: String, pid: u32, attempt: u32) -> Self {
+        let mut log = Self::new(project, message, LogSource::Stdout, pid);
+        log.msg_type = "process_event".to_string();
+        log.data.level = LogLevel::Warn;
+        log.data.event = Some(ProcessEvent::Restarted);
+        log.data.restart_attempt = Some(attempt);
+        log
+    }
+
+    /// Événement "crash loop", envoyé quand les redémarrages sont abandonnés
+    pub fn crash_loop(project: String, message: String, pid: u32) -> Self {
+        let mut log = Self::new(project, message, LogSource::Stdout, pid);
+        log.msg_type = "process_event".to_string();
+        log.data.level = LogLevel::Error;
+        log.data.event = Some(ProcessEvent::CrashLoop);
+        log
+    }
+
+    /// Événement "ready", envoyé quand la condition ready_when est remplie
+    pub fn ready(project: String, message: String, pid: u32, ready_ms: u64) -> Self {
+        let mut log = Self::new(project, message, LogSource::Stdout, pid);
+        log.msg_type = "process_event".to_string();
+        log.data.level = LogLevel::Info;
+        log.data.event = Some(ProcessEvent::Ready);
+        log.data.ready_ms = Some(ready_ms);
+        log
+    }
+
+    /// Événement "startup failed", envoyé si ready_when n'est pas remplie à temps
+    pub fn startup_failed(project: String, message: String, pid: u32) -> Self {
+        let mut log = Self::new(project, message, LogSource::Stdout, pid);
+        log.msg_type = "process_event".to_string();
+        log.data.level = LogLevel::Error;
+        log.data.event = Some(ProcessEvent::StartupFailed);
+        log
+    }
+
+    /// Mesure périodique des ressources (CPU, RSS, fds, threads)
+    pub fn metrics(project: String, usage: ResourceUsage, pid: u32) -> Self {
+        let mut log = Self::new(project, usage.to_string(), LogSource::Stdout, pid);
+        log.msg_type = "metrics".to_string();
+        log.data.level = LogLevel::Info;
+        log.data.metrics = Some(usage);
+        log
+    }
+
+    /// État intermédiaire d'une ligne réécrite avec \r (barre de progression)
+    ///
+    /// Affiché sur place dans la TUI, jamais envoyé au serveur MCP: seul
+    /// l'état final de la ligne l'est.
+    pub fn progress(project: String, message: String, source: LogSource, pid: u32) -> Self {
+        let mut log = Self::new(project, message, source, pid);
+        log.msg_type = "progress".to_string();
+        log
+    }
+
+    /// Vrai pour une mise à jour de progression (voir `progress`)
+    pub fn is_progress(&self) -> bool {
+        self.msg_type == "progress"
+    }
+
+    /// Vrai pour une mesure de ressources (sans intérêt une fois périmée)
+    pub fn is_metrics(&self) -> bool {
+        self.msg_type == "metrics"
+    }
+
+    /// Conserve les octets d'origine d'une ligne décodée
+    pub fn with_raw(mut self, raw: Option<Vec<u8>>, repaired: bool) -> Self {
+        self.data.raw = raw;
+        self.data.repaired = repaired;
+        self
+    }
+
+    /// Octets d'une ligne trop longue déjà écartés à la capture (au-delà de `full_limit`)
+    pub fn with_dropped(mut self, bytes: usize) -> Self {
+        self.add_dropped(bytes);
+        self
+    }
+
+    /// Ajoute des octets écartés au compte de `truncated_bytes`
+    pub fn add_dropped(&mut self, bytes: usize) {
+        if bytes > 0 {
+            self.data.truncated_bytes = Some(self.data.truncated_bytes.unwrap_or(0) + bytes);
+        }
+    }
+
+    /// Coupe le message à max_bytes (0 = pas de limite)
+    ///
+    /// Le texte complet reste dans `full`, lui-même limité à `full_limit`:
+    /// `truncated_bytes` compte tout ce qui manque au message.
+    pub fn limit_length(mut self, max_bytes: usize) -> Self {
+        if max_bytes == 0 || self.data.message.len() <= max_bytes {
+            return self;
+        }
+        let mut full = std::mem::take(&mut self.data.message);
+        let cut = floor_boundary(&full, max_bytes);
+        self.data.message = full[..cut].to_string();
+        self = self.with_dropped(full.len() - cut);
+        full.truncate(floor_boundary(&full, full_limit(max_bytes)));
+
+        // Version colorée coupée aux mêmes caractères, entre deux séquences
+        if let Some(mut styled) = self.data.styled.take() {
+            styled.truncate(ansi::offset_of(&styled, full.len()));
+            self.data.styled = Some(styled[..ansi::offset_of(&styled, cut)].to_string());
+            self.data.styled_full = Some(styled);
         }
+        self.data.full = Some(full);
+        self
+    }
+
+    /// Découpe une ligne coupée par `limit_length` en morceaux liés de max_bytes au plus
+    pub fn into_chunks(self, max_bytes: usize) -> Vec<LogMessage> {
+        let Some(full) = &self.data.full else {
+            return vec![self];
+        };
+
+        // Fin de chaque morceau dans `full`
+        let mut ends = Vec::new();
+        let mut start = 0;
+        while start < full.len() {
+            // Au moins un caractère, même plus long que max_bytes
+            let rest = &full[start..];
+            let cut = match floor_boundary(rest, max_bytes) {
+                0 => ceil_boundary(rest, 1),
+                cut => cut,
+            };
+            start += cut;
+            ends.push(start);
+        }
+
+        // Octets de la ligne d'origine au-delà de `full`
+        let dropped = (self.data.message.len() + self.data.truncated_bytes.unwrap_or(0))
+            .saturating_sub(full.len());
+        let id = NEXT_CHUNK_ID.fetch_add(1, Ordering::Relaxed);
+        let count = ends.len() as u32;
+        let (mut start, mut styled_start) = (0, 0);
+        ends.iter()
+            .enumerate()
+            .map(|(i, &end)| {
+                let mut chunk = self.clone();
+                chunk.data.message = full[start..end].to_string();
+                start = end;
+                // Les séquences placées entre deux morceaux ouvrent le suivant
+                chunk.data.styled = self.data.styled_full.as_ref().map(|styled| {
+                    let styled_end = ansi::offset_of(styled, end);
+                    let piece = styled[styled_start..styled_end].to_string();
+                    styled_start = styled_end;
+                    piece
+                });
+                chunk.data.full = None;
+                chunk.data.styled_full = None;
+                // Seul le dernier morceau indique ce qui n'a pas été conservé
+                chunk.data.truncated_bytes = None;
+                if i + 1 == ends.len() {
+                    chunk = chunk.with_dropped(dropped);
+                }
+                // Champs envoyés une seule fois, avec le premier morceau
+                if i > 0 {
+                    chunk.data.fields.clear();
+                }
+                chunk.data.chunk = Some(LineChunk { id, index: i as u32 + 1, count });
+                chunk
+            })
+            .collect()
+    }
+
+    /// Ajoute une ligne de continuation (événement multiligne: stack trace, panic...)
+    pub fn append_line(&mut self, line: &str, raw: Option<Vec<u8>>, repaired: bool) {
+        // Octets d'origine: ceux de chaque ligne, ou son texte si elle n'en a pas
+        if self.data.raw.is_some() || raw.is_some() {
+            let mut bytes = self
+                .data
+                .raw
+                .take()
+                .unwrap_or_else(|| self.data.message.clone().into_bytes());
+            bytes.push(b'\n');
+            bytes.extend(raw.unwrap_or_else(|| line.as_bytes().to_vec()));
+            self.data.raw = Some(bytes);
+        }
+        self.data.message.push('\n');
+        self.data.message.push_str(line);
+        self.data.repaired |= repaired;
+    }
+
+    /// Associe le log à un processus nommé (mode multi-process)
+    pub fn with_process(mut self, process: Option<String>) -> Self {
+        self.data.process = process;
+        self
+    }
+}
+
+/// Dernière limite de caractère avant l'octet `index` (inclus)
+fn floor_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// Première limite de caractère après l'octet `index` (inclus)
+fn ceil_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(message: &str) -> LogMessage {
+        LogMessage::new("test".to_string(), message.to_string(), LogSource::Stdout, 1)
+    }
+
+    #[test]
+    fn limit_length_keeps_full_up_to_the_hard_limit() {
+        let line = "x".repeat(MAX_FULL_BYTES + 10);
+        let log = log(&line).limit_length(100);
+        assert_eq!(log.data.message.len(), 100);
+        assert_eq!(log.data.full.as_ref().map(String::len), Some(MAX_FULL_BYTES));
+        assert_eq!(log.data.truncated_bytes, Some(MAX_FULL_BYTES + 10 - 100));
+    }
+
+    #[test]
+    fn chunks_report_bytes_beyond_full_on_the_last_one() {
+        // 10 octets écartés à la capture en plus de la coupure
+        let chunks = log("abcdefghij").with_dropped(10).limit_length(4).into_chunks(4);
+        let messages: Vec<&str> = chunks.iter().map(|c| c.data.message.as_str()).collect();
+        assert_eq!(messages, ["abcd", "efgh", "ij"]);
+        assert_eq!(chunks[0].data.truncated_bytes, None);
+        assert_eq!(chunks[2].data.truncated_bytes, Some(10));
+        assert_eq!(chunks[2].data.chunk.map(|c| (c.index, c.count)), Some((3, 3)));
+    }
+
+    #[test]
+    fn lines_are_cut_between_characters() {
+        let cut = log("aé€b").limit_length(4);
+        assert_eq!(cut.data.message, "aé");
+        let chunks = log("€€").limit_length(2).into_chunks(2);
+        let messages: Vec<&str> = chunks.iter().map(|c| c.data.message.as_str()).collect();
+        assert_eq!(messages, ["€", "€"]);
+    }
+
+    #[test]
+    fn long_colored_lines_keep_their_colors() {
+        let mut colored = log("abcdefghij");
+        colored.data.styled = Some("\x1b[31mabcdef\x1b[0m\x1b[1mghij\x1b[0m".to_string());
+
+        let cut = colored.clone().limit_length(6);
+        assert_eq!(cut.data.message, "abcdef");
+        assert_eq!(cut.data.styled.as_deref(), Some("\x1b[31mabcdef"));
+
+        let chunks = colored.limit_length(4).into_chunks(4);
+        let styled: Vec<&str> = chunks.iter().filter_map(|c| c.data.styled.as_deref()).collect();
+        assert_eq!(styled, ["\x1b[31mabcd", "ef\x1b[0m\x1b[1mgh", "ij"]);
     }
 }
@@ -0,0 +1,187 @@
+use crate::process::ProcessSpec;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Chemins toujours ignorés (dépôt git, dépendances, sorties de build, fichiers d'éditeur)
+const DEFAULT_IGNORES: [&str; 5] = [
+    "**/.git/**",
+    "**/node_modules/**",
+    "**/target/**",
+    "**/*.swp",
+    "**/*~",
+];
+
+/// Rafale de changements ayant déclenché un redémarrage
+#[derive(Debug, Clone)]
+pub struct FileChange {
+    /// Position du processus concerné
+    pub index: usize,
+    /// Premier fichier modifié, relatif au cwd de la commande
+    pub path: PathBuf,
+    /// Nombre d'autres fichiers modifiés dans la même rafale
+    pub others: usize,
+}
+
+impl fmt::Display for FileChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if self.others > 0 {
+            write!(f, " (+{})", self.others)?;
+        }
+        Ok(())
+    }
+}
+
+/// Surveillance des watch_paths d'un processus (arrêtée au drop)
+pub struct FileWatcher {
+    _watcher: RecommendedWatcher,
+    task: JoinHandle<()>,
+}
+
+impl FileWatcher {
+    /// Surveille les watch_paths de `spec` (None si la commande n'en a pas)
+    ///
+    /// Les changements sont regroupés: un seul `FileChange` est envoyé une
+    /// fois qu'aucun fichier n'a bougé pendant `debounce`.
+    pub fn spawn(
+        index: usize,
+        spec: &ProcessSpec,
+        debounce: Duration,
+        tx: mpsc::Sender<FileChange>,
+    ) -> Result<Option<Self>, String> {
+        if spec.watch_paths.is_empty() {
+            return Ok(None);
+        }
+
+        let base = spec.env.cwd_path().unwrap_or_else(|| PathBuf::from("."));
+        let base = base
+            .canonicalize()
+            .map_err(|e| format!("cwd not found: {} ({})", base.display(), e))?;
+        let ignore = ignore_set(&spec.ignore_globs)?;
+
+        let (raw_tx, raw_rx) = mpsc::unbounded_channel::<PathBuf>();
+        let root = base.clone();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+            let Ok(event) = res else { return };
+            if !is_change(&event.kind) {
+                return;
+            }
+            for path in event.paths {
+                if !ignore.is_match(relative(&path, &root)) {
+                    let _ = raw_tx.send(path);
+                }
+            }
+        })
+        .map_err(|e| format!("Failed to start file watcher: {}", e))?;
+
+        for path in &spec.watch_paths {
+            let full = base.join(path);
+            if !full.exists() {
+                return Err(format!("watch path not found: {}", path));
+            }
+            watcher
+                .watch(&full, RecursiveMode::Recursive)
+                .map_err(|e| format!("Failed to watch {}: {}", path, e))?;
+        }
+
+        let task = tokio::spawn(debounce_changes(index, base, raw_rx, debounce, tx));
+        Ok(Some(Self { _watcher: watcher, task }))
+    }
+}
+
+impl Drop for FileWatcher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Compile les globs ignorés (défauts + ceux de la commande)
+pub fn ignore_set(globs: &[String]) -> Result<GlobSet, String> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in DEFAULT_IGNORES.iter().copied().chain(globs.iter().map(String::as_str)) {
+        let glob = Glob::new(pattern)
+            .map_err(|e| format!("Invalid ignore glob '{}': {}", pattern, e))?;
+        builder.add(glob);
+    }
+    builder.build().map_err(|e| e.to_string())
+}
+
+/// Création, suppression, écriture ou renommage (les changements de
+/// métadonnées seules, comme un `touch` ou un `chmod`, sont ignorés)
+fn is_change(kind: &EventKind) -> bool {
+    matches!(
+        kind,
+        EventKind::Create(_)
+            | EventKind::Modify(ModifyKind::Data(_) | ModifyKind::Name(_))
+            | EventKind::Remove(_)
+    )
+}
+
+/// Chemin relatif au dossier de la commande (inchangé s'il est en dehors)
+fn relative<'a>(path: &'a Path, base: &Path) -> &'a Path {
+    path.strip_prefix(base).unwrap_or(path)
+}
+
+/// Attend la fin d'une rafale de changements avant de la signaler
+async fn debounce_changes(
+    index: usize,
+    base: PathBuf,
+    mut rx: mpsc::UnboundedReceiver<PathBuf>,
+    debounce: Duration,
+    tx: mpsc::Sender<FileChange>,
+) {
+    while let Some(first) = rx.recv().await {
+        let mut changed = vec![first];
+        loop {
+            match tokio::time::timeout(debounce, rx.recv()).await {
+                Ok(Some(path)) => {
+                    if !changed.contains(&path) {
+                        changed.push(path);
+                    }
+                }
+                Ok(None) => return,
+                Err(_) => break,
+            }
+        }
+
+        let change = FileChange {
+            index,
+            path: relative(&changed[0], &base).to_path_buf(),
+            others: changed.len() - 1,
+        };
+        if tx.send(change).await.is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, DataChange, MetadataKind, RenameMode};
+
+    #[test]
+    fn metadata_only_changes_are_ignored() {
+        assert!(is_change(&EventKind::Create(CreateKind::File)));
+        assert!(is_change(&EventKind::Modify(ModifyKind::Data(DataChange::Content))));
+        assert!(is_change(&EventKind::Modify(ModifyKind::Name(RenameMode::Both))));
+        assert!(!is_change(&EventKind::Modify(ModifyKind::Metadata(
+            MetadataKind::Permissions
+        ))));
+        assert!(!is_change(&EventKind::Access(notify::event::AccessKind::Any)));
+    }
+
+    #[test]
+    fn default_ignores_apply() {
+        let set = ignore_set(&["*.log".to_string()]).unwrap();
+        assert!(set.is_match("node_modules/a/index.js"));
+        assert!(set.is_match("app.log"));
+        assert!(!set.is_match("src/main.rs"));
+    }
+}
@@ -8,6 +8,7 @@ import {
 } from "@modelcontextprotocol/sdk/types.js";
 
 import { LogStore } from "./src/store/index.js";
+import { MetricsStore } from "./src/store/metrics.js";
 import { SocketServer } from "./src/server/index.js";
 import { ToolHandlers } from "./src/mcp/handlers.js";
 import { TOOLS } from "./src/mcp/tools.js";
@@ -30,12 +31,20 @@ async function main() {
   // Store global pour les logs
   const logStore = new LogStore(config.storage.max_logs);
 
+  // Mesures CPU/mémoire des processus (séparées des logs)
+  const metricsStore = new MetricsStore();
+
   // Serveur Unix socket
-  const socketServer = new SocketServer(logStore, config.server.socket_path, verbose);
+  const socketServer = new SocketServer(
+    logStore,
+    metricsStore,
+    config.server.socket_path,
+    verbose
+  );
   await socketServer.start();
 
   // Handlers pour les outils MCP
-  const toolHandlers = new ToolHandlers(logStore, socketServer);
+  const toolHandlers = new ToolHandlers(logStore, metricsStore, socketServer);
 
   // Serveur MCP
   const mcpServer = new Server(
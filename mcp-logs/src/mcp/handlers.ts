@@ -1,7 +1,9 @@
 import type { CallToolResult } from "@modelcontextprotocol/sdk/types.js";
 import type { LogStore } from "../store/index.js";
+import type { MetricsStore } from "../store/metrics.js";
 import type { LogFilter, LogLevel, LogSource, AnalyticsOptions } from "../types/index.js";
 import type { SocketServer } from "../server/index.js";
+import { searchableText } from "../utils/fields.js";
 
 interface ToolArguments {
   [key: string]: unknown;
@@ -10,6 +12,7 @@ interface ToolArguments {
 export class ToolHandlers {
   constructor(
     private store: LogStore,
+    private metrics: MetricsStore,
     private socketServer?: SocketServer
   ) {}
 
@@ -22,6 +25,7 @@ export class ToolHandlers {
         get_analytics: () => this.getAnalytics(args),
         search_logs: () => this.searchLogs(args),
         get_errors: () => this.getErrors(args),
+        get_metrics: () => this.getMetrics(args),
         clear_logs: () => this.clearLogs(),
         list_projects: () => this.listProjects(),
       };
@@ -68,6 +72,7 @@ export class ToolHandlers {
                 source: log.data.source,
                 message: log.data.message,
                 pid: log.data.pid,
+                process: log.data.process,
               })),
             },
             null,
@@ -191,7 +196,7 @@ export class ToolHandlers {
     if (useRegex) {
       try {
         const regex = new RegExp(query, "i"); // Case insensitive
-        logs = logs.filter((log) => regex.test(log.data.message));
+        logs = logs.filter((log) => regex.test(searchableText(log)));
       } catch (error) {
         throw new Error(
           `Invalid regex pattern: ${error instanceof Error ? error.message : String(error)}`
@@ -200,7 +205,7 @@ export class ToolHandlers {
     } else {
       const queryLower = query.toLowerCase();
       logs = logs.filter((log) =>
-        log.data.message.toLowerCase().includes(queryLower)
+        searchableText(log).toLowerCase().includes(queryLower)
       );
     }
 
@@ -245,7 +250,42 @@ export class ToolHandlers {
             {
               project,
               count: logs.length,
-              errors: logs.map((log) => log.data),
+              // Mesure CPU/mémoire du processus la plus proche de chaque erreur
+              errors: logs.map((log) => ({
+                ...log.data,
+                resources: this.metrics.nearest(log),
+              })),
+            },
+            null,
+            2
+          ),
+        },
+      ],
+    };
+  }
+
+  private getMetrics(args?: ToolArguments): CallToolResult {
+    const project =
+      typeof args?.project === "string" ? args.project : undefined;
+    const processName =
+      typeof args?.process === "string" ? args.process : undefined;
+    const limit = Math.min(
+      typeof args?.limit === "number" ? args.limit : 30,
+      300
+    );
+
+    const series = this.metrics.getSeries(project, processName, limit);
+
+    return {
+      content: [
+        {
+          type: "text",
+          text: JSON.stringify(
+            {
+              project,
+              process: processName,
+              count: series.length,
+              series,
             },
             null,
             2
@@ -258,6 +298,7 @@ export class ToolHandlers {
   private clearLogs(): CallToolResult {
     const beforeCount = this.store.count();
     this.store.clear();
+    this.metrics.clear();
 
     return {
       content: [
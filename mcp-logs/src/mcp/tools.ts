@@ -40,7 +40,7 @@ export const TOOLS: Tool[] = [
         },
         search: {
           type: "string",
-          description: "Search for text in log messages",
+          description: "Search for text in log messages and structured fields (e.g. 'user=42')",
         },
         startTime: {
           type: ["string", "number"],
@@ -107,7 +107,7 @@ export const TOOLS: Tool[] = [
   {
     name: "search_logs",
     description:
-      "Search logs by text content. Returns matching logs with context. Supports both simple text search and regex patterns.",
+      "Search logs by text content. Returns matching logs with context. Supports both simple text search and regex patterns. Structured fields (JSON, logfmt) are matched as 'key=value', nested keys as 'req.id=7'.",
     inputSchema: {
       type: "object",
       properties: {
@@ -138,7 +138,7 @@ export const TOOLS: Tool[] = [
   {
     name: "get_errors",
     description:
-      "Get all error-level logs. Useful for debugging and finding issues.",
+      "Get all error-level logs. Useful for debugging and finding issues. Each error includes the CPU/memory sample of its process closest in time, when available.",
     inputSchema: {
       type: "object",
       properties: {
@@ -154,6 +154,29 @@ export const TOOLS: Tool[] = [
       },
     },
   },
+  {
+    name: "get_metrics",
+    description:
+      "Get CPU, memory (RSS), file descriptor and thread samples of the processes run by the log agents. Useful to spot leaks or CPU spikes around errors.",
+    inputSchema: {
+      type: "object",
+      properties: {
+        project: {
+          type: "string",
+          description: "Optional: filter by project name",
+        },
+        process: {
+          type: "string",
+          description: "Optional: filter by process name (when an agent runs several commands)",
+        },
+        limit: {
+          type: "number",
+          description: "Number of most recent samples per process (default: 30, max: 300)",
+          default: 30,
+        },
+      },
+    },
+  },
   {
     name: "clear_logs",
     description: "Clear all logs from memory. Use with caution!",
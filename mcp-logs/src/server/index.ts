@@ -1,6 +1,7 @@
 import { unlink } from "node:fs/promises";
 import type { LogMessage } from "../types/index.js";
 import { LogStore } from "../store/index.js";
+import { MetricsStore } from "../store/metrics.js";
 
 export const SOCKET_PATH = "/tmp/log-agent.sock";
 
@@ -10,12 +11,23 @@ export const SOCKET_PATH = "/tmp/log-agent.sock";
 export class SocketServer {
   private server: ReturnType<typeof Bun.listen> | null = null;
   private store: LogStore;
+  private metrics: MetricsStore;
   private socketPath: string;
   private verbose: boolean;
   private connectedProjects: Set<string> = new Set();
+  // Fin de ligne incomplète par client (les agents gardent une connexion ouverte)
+  private pending: WeakMap<object, string> = new WeakMap();
+  // Morceaux reçus des lignes trop longues (long_lines = "split"), par client et par id
+  private chunks: WeakMap<object, Map<number, LogMessage[]>> = new WeakMap();
 
-  constructor(store: LogStore, socketPath: string = SOCKET_PATH, verbose = false) {
+  constructor(
+    store: LogStore,
+    metrics: MetricsStore,
+    socketPath: string = SOCKET_PATH,
+    verbose = false
+  ) {
     this.store = store;
+    this.metrics = metrics;
     this.socketPath = socketPath;
     this.verbose = verbose;
   }
@@ -35,12 +47,14 @@ export class SocketServer {
       unix: this.socketPath,
       socket: {
         data: (socket, data) => {
-          this.handleData(data);
+          this.handleData(socket, data);
         },
         open: (socket) => {
           if (this.verbose) console.log("✓ Client connected");
         },
         close: (socket) => {
+          this.pending.delete(socket);
+          this.chunks.delete(socket);
           if (this.verbose) console.log("✓ Client disconnected");
         },
         error: (socket, error) => {
@@ -55,14 +69,19 @@ export class SocketServer {
   /**
    * Traite les données reçues
    */
-  private handleData(data: Buffer): void {
-    const text = data.toString("utf-8");
-    const lines = text.split("\n").filter((line) => line.trim());
+  private handleData(socket: object, data: Buffer): void {
+    const text = (this.pending.get(socket) ?? "") + data.toString("utf-8");
+    const parts = text.split("\n");
+    // La dernière partie peut être une ligne coupée entre deux paquets
+    this.pending.set(socket, parts.pop() ?? "");
+    const lines = parts.filter((line) => line.trim());
 
     for (const line of lines) {
       try {
-        const log: LogMessage = JSON.parse(line);
-        this.store.add(log);
+        const log = this.reassemble(socket, JSON.parse(line));
+        if (!log) {
+          continue;
+        }
 
         // Enregistrer le projet comme connecté
         if (!this.connectedProjects.has(log.data.project)) {
@@ -70,6 +89,13 @@ export class SocketServer {
           console.log(`✓ Agent connected: ${log.data.project}`);
         }
 
+        // Les mesures de ressources ont leur propre série par processus
+        if (log.type === "metrics") {
+          this.metrics.add(log);
+          continue;
+        }
+        this.store.add(log);
+
         // Affiche le log dans la console du serveur seulement en mode verbose
         if (this.verbose) {
           const emoji = this.getLevelEmoji(log.data.level);
@@ -83,6 +109,43 @@ export class SocketServer {
     }
   }
 
+  /**
+   * Recolle les morceaux d'une ligne trop longue (long_lines = "split")
+   *
+   * Retourne le log complet au dernier morceau, undefined pour les autres.
+   * Les champs structurés viennent du premier morceau, truncated_bytes du dernier.
+   */
+  private reassemble(socket: object, log: LogMessage): LogMessage | undefined {
+    const chunk = log.data.chunk;
+    if (!chunk) {
+      return log;
+    }
+
+    let lines = this.chunks.get(socket);
+    if (!lines) {
+      lines = new Map();
+      this.chunks.set(socket, lines);
+    }
+    const parts = lines.get(chunk.id) ?? [];
+    parts.push(log);
+    if (chunk.index < chunk.count) {
+      lines.set(chunk.id, parts);
+      return undefined;
+    }
+    lines.delete(chunk.id);
+
+    const first = parts[0]!;
+    return {
+      ...first,
+      data: {
+        ...first.data,
+        message: parts.map((part) => part.data.message).join(""),
+        chunk: undefined,
+        truncated_bytes: log.data.truncated_bytes,
+      },
+    };
+  }
+
   /**
    * Retourne la liste des projets connectés
    */
@@ -1,5 +1,6 @@
 import type { LogMessage, LogFilter, Analytics, AnalyticsOptions } from "../types/index.js";
 import { isInTimeRange, parseTimeInput, formatDuration, groupByTimeInterval } from "../utils/time.js";
+import { searchableText } from "../utils/fields.js";
 
 /**
  * Store en mémoire pour les logs en temps réel
@@ -56,11 +57,11 @@ export class LogStore {
       );
     }
 
-    // Recherche textuelle
+    // Recherche textuelle (message et champs structurés)
     if (filter.search) {
       const searchLower = filter.search.toLowerCase();
       filtered = filtered.filter((log) =>
-        log.data.message.toLowerCase().includes(searchLower)
+        searchableText(log).toLowerCase().includes(searchLower)
       );
     }
 
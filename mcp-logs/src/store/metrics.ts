@@ -0,0 +1,116 @@
+import type { LogMessage, ResourceUsage } from "../types/index.js";
+
+/**
+ * Mesure de ressources d'un processus à un instant donné
+ */
+export interface MetricsSample extends ResourceUsage {
+  timestamp: string;
+}
+
+/**
+ * Série de mesures d'un processus
+ */
+export interface MetricsSeries {
+  project: string;
+  process?: string;
+  pid: number;
+  samples: MetricsSample[];
+}
+
+/**
+ * Store en mémoire des mesures de ressources (messages de type "metrics")
+ *
+ * Séparé du LogStore: une mesure toutes les 2s par processus évincerait
+ * sinon les vrais logs. Chaque processus garde ses `maxSamples` dernières
+ * mesures.
+ */
+export class MetricsStore {
+  private series: Map<string, MetricsSeries> = new Map();
+  private maxSamples: number;
+
+  constructor(maxSamples: number = 300) {
+    this.maxSamples = maxSamples;
+  }
+
+  /**
+   * Ajoute une mesure à la série de son processus
+   */
+  add(log: LogMessage): void {
+    const usage = log.data.metrics;
+    if (!usage) {
+      return;
+    }
+
+    const key = seriesKey(log);
+    let series = this.series.get(key);
+    if (!series) {
+      series = {
+        project: log.data.project,
+        process: log.data.process,
+        pid: log.data.pid,
+        samples: [],
+      };
+      this.series.set(key, series);
+    }
+
+    // Nouveau PID après un redémarrage: même série
+    series.pid = log.data.pid;
+    series.samples.push({ timestamp: log.data.timestamp, ...usage });
+
+    // Limite le nombre de mesures par processus (FIFO)
+    if (series.samples.length > this.maxSamples) {
+      series.samples.shift();
+    }
+  }
+
+  /**
+   * Séries des processus (filtrées par projet/processus), `limit` dernières mesures
+   */
+  getSeries(project?: string, processName?: string, limit?: number): MetricsSeries[] {
+    return Array.from(this.series.values())
+      .filter((series) => !project || series.project === project)
+      .filter((series) => !processName || series.process === processName)
+      .map((series) => ({
+        ...series,
+        samples: limit && limit > 0 ? series.samples.slice(-limit) : [...series.samples],
+      }));
+  }
+
+  /**
+   * Mesure la plus proche d'un log du même processus
+   *
+   * Undefined si aucune mesure n'est à moins de `maxDistanceMs` du log.
+   */
+  nearest(log: LogMessage, maxDistanceMs: number = 10000): MetricsSample | undefined {
+    const series = this.series.get(seriesKey(log));
+    if (!series) {
+      return undefined;
+    }
+
+    const time = new Date(log.data.timestamp).getTime();
+    let best: MetricsSample | undefined;
+    let bestDistance = maxDistanceMs;
+    for (const sample of series.samples) {
+      const distance = Math.abs(new Date(sample.timestamp).getTime() - time);
+      if (distance <= bestDistance) {
+        best = sample;
+        bestDistance = distance;
+      }
+    }
+    return best;
+  }
+
+  /**
+   * Vide le store
+   */
+  clear(): void {
+    this.series.clear();
+  }
+}
+
+/**
+ * Clé d'une série: le nom du processus s'il est connu, sinon son PID
+ */
+function seriesKey(log: LogMessage): string {
+  return `${log.data.project}\u0000${log.data.process ?? log.data.pid}`;
+}
@@ -9,8 +9,32 @@ export interface LogData {
   level: LogLevel;
   source: LogSource;
   project: string;
-  message: string;
+  message: string; // Sans codes ANSI (sauf ansi.socket = "styled"), plusieurs lignes pour un événement multiligne
   pid: number;
+  process?: string; // Nom du processus quand l'agent en lance plusieurs
+  event?: "exited" | "restarted" | "crash_loop" | "ready" | "startup_failed"; // Messages de type "process_event"
+  exit_code?: number; // 128+signal si le processus a été tué
+  restart_attempt?: number; // Numéro du redémarrage automatique (event "restarted")
+  ready_ms?: number; // Temps de démarrage (event "ready")
+  metrics?: ResourceUsage; // Messages de type "metrics"
+  repaired?: boolean; // Octets invalides remplacés par U+FFFD
+  truncated_bytes?: number; // Octets retirés d'une ligne trop longue (long_lines = "truncate")
+  chunk?: LineChunk; // Morceau d'une ligne trop longue (long_lines = "split"), recollé par le serveur avant le store
+  fields?: Record<string, unknown>; // Autres clés d'une ligne structurée (JSON)
+}
+
+export interface LineChunk {
+  id: number; // Commun aux morceaux d'une même ligne
+  index: number; // À partir de 1, la ligne continue tant que index < count
+  count: number;
+}
+
+export interface ResourceUsage {
+  cpu_percent: number; // Depuis la mesure précédente (100 = un cœur)
+  rss_bytes: number;
+  fds: number;
+  threads: number;
+  processes: number; // Processus dans l'arbre
 }
 
 export type LogLevel = "info" | "warn" | "error" | "debug";
@@ -0,0 +1,31 @@
+/**
+ * Utilitaires pour les champs des lignes structurées (JSON, logfmt)
+ */
+
+import type { LogMessage } from "../types/index.js";
+
+/**
+ * Champs d'un log en "key=value" séparés par des espaces
+ * Les objets sont aplatis en clés pointées: { req: { id: 7 } } -> "req.id=7"
+ */
+export function fieldsToText(fields: Record<string, unknown>, prefix = ""): string {
+  return Object.entries(fields)
+    .map(([key, value]) => {
+      const path = prefix ? `${prefix}.${key}` : key;
+      if (value !== null && typeof value === "object" && !Array.isArray(value)) {
+        return fieldsToText(value as Record<string, unknown>, path);
+      }
+      return `${path}=${typeof value === "string" ? value : JSON.stringify(value)}`;
+    })
+    .join(" ");
+}
+
+/**
+ * Texte sur lequel portent les recherches: le message puis les champs
+ */
+export function searchableText(log: LogMessage): string {
+  if (!log.data.fields) {
+    return log.data.message;
+  }
+  return `${log.data.message} ${fieldsToText(log.data.fields)}`;
+}